@@ -0,0 +1,232 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use colored::Colorize;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::error::VsixError;
+use crate::filename;
+use crate::http::HttpClient;
+use crate::marketplace::{GalleryVersion, VSIX_ASSET_TYPE};
+use crate::warnings::{self, WarningKind};
+
+/// 다운로드 디렉토리 안에서 확장 프로그램별 부가 자산을 두는 디렉토리
+pub const ASSETS_DIR: &str = "assets";
+
+/// Marketplace가 버전마다 제공하는 자산 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AssetKind {
+    /// VSIX를 포함한 모든 자산
+    All,
+    /// VSIX 패키지 (항상 받음)
+    Vsix,
+    /// 라이선스
+    License,
+    /// 아이콘
+    Icon,
+    /// README (Marketplace 상세 페이지 내용)
+    Readme,
+    /// 변경 기록
+    Changelog,
+    /// package.json
+    Manifest,
+    /// extension.vsixmanifest
+    Vsixmanifest,
+    /// Marketplace 서명 (.p7s)
+    Signature,
+}
+
+impl AssetKind {
+    const EXTRAS: [AssetKind; 7] = [
+        AssetKind::License,
+        AssetKind::Icon,
+        AssetKind::Readme,
+        AssetKind::Changelog,
+        AssetKind::Manifest,
+        AssetKind::Vsixmanifest,
+        AssetKind::Signature,
+    ];
+
+    /// extensionquery의 `files[].assetType`
+    pub fn asset_type(self) -> &'static str {
+        match self {
+            AssetKind::All | AssetKind::Vsix => VSIX_ASSET_TYPE,
+            AssetKind::License => "Microsoft.VisualStudio.Services.Content.License",
+            AssetKind::Icon => "Microsoft.VisualStudio.Services.Icons.Default",
+            AssetKind::Readme => "Microsoft.VisualStudio.Services.Content.Details",
+            AssetKind::Changelog => "Microsoft.VisualStudio.Services.Content.Changelog",
+            AssetKind::Manifest => "Microsoft.VisualStudio.Code.Manifest",
+            AssetKind::Vsixmanifest => "Microsoft.VisualStudio.Services.VsixManifest",
+            AssetKind::Signature => "Microsoft.VisualStudio.Services.VsixSignature",
+        }
+    }
+
+    /// 확장 프로그램별 자산 디렉토리에 저장할 파일 이름
+    fn file_name(self) -> &'static str {
+        match self {
+            AssetKind::All | AssetKind::Vsix => "extension.vsix",
+            AssetKind::License => "LICENSE",
+            AssetKind::Icon => "icon.png",
+            AssetKind::Readme => "README.md",
+            AssetKind::Changelog => "CHANGELOG.md",
+            AssetKind::Manifest => "package.json",
+            AssetKind::Vsixmanifest => "extension.vsixmanifest",
+            AssetKind::Signature => "signature.p7s",
+        }
+    }
+}
+
+/// `--assets`로 고른 종류 중 VSIX 외에 받을 자산 (`all`이면 전부)
+pub fn extras(kinds: &[AssetKind]) -> Vec<AssetKind> {
+    if kinds.contains(&AssetKind::All) {
+        return AssetKind::EXTRAS.to_vec();
+    }
+
+    AssetKind::EXTRAS.into_iter().filter(|kind| kinds.contains(kind)).collect()
+}
+
+/// 자산 하나의 다운로드 URL 후보
+#[derive(Debug, Clone)]
+pub struct AssetSource {
+    kind: AssetKind,
+    urls: Vec<String>,
+}
+
+/// 받은 자산 (다운로드 정보 파일의 `assets`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadedAsset {
+    pub kind: AssetKind,
+    pub path: String,
+    pub size: u64,
+}
+
+/// 갤러리 버전 정보에서 고른 자산의 URL 후보를 찾습니다. 버전에 없는 자산은 건너뜁니다.
+pub fn sources(kinds: &[AssetKind], version: &GalleryVersion) -> Vec<AssetSource> {
+    extras(kinds).into_iter()
+        .filter_map(|kind| {
+            let urls = version.asset_urls(kind.asset_type());
+            (!urls.is_empty()).then_some(AssetSource { kind, urls })
+        })
+        .collect()
+}
+
+/// 확장 프로그램의 자산을 `<다운로드 디렉토리>/assets/<ID>/`에 받습니다.
+/// 자산은 보관용이므로 실패해도 VSIX 다운로드는 성공으로 두고 경고만 출력합니다.
+pub async fn download_all(
+    client: &HttpClient,
+    extension_id: &str,
+    sources: &[AssetSource],
+    output_dir: &Path
+) -> Result<Vec<DownloadedAsset>> {
+    if sources.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let dir = output_dir.join(ASSETS_DIR).join(filename::sanitize_file_name(extension_id));
+    fs::create_dir_all(filename::long_path(&dir))
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let mut downloaded = Vec::new();
+    for source in sources {
+        let path = dir.join(source.kind.file_name());
+
+        let mut last_error = None;
+        for url in &source.urls {
+            let result = match download_asset(client, url, &path).await {
+                Ok(size) if source.kind == AssetKind::Signature => check_signature(extension_id, &path, size).map(|()| size),
+                result => result,
+            };
+            match result {
+                Ok(size) => {
+                    downloaded.push(DownloadedAsset { kind: source.kind, path: path.to_string_lossy().to_string(), size });
+                    last_error = None;
+                    break;
+                },
+                // 받았지만 서명 형식이 아니었던 이유를 다음 URL의 오류로 덮지 않음
+                Err(e) if !matches!(last_error, Some(VsixError::SignatureInvalid { .. })) => last_error = Some(e),
+                Err(_) => {},
+            }
+        }
+
+        // 서명 자산을 받지 못했으면 서명을 확인할 수 없는 것으로 분류
+        let last_error = match last_error {
+            Some(e) if source.kind == AssetKind::Signature && !matches!(e, VsixError::NotFound { .. } | VsixError::SignatureInvalid { .. }) => {
+                Some(VsixError::SignatureInvalid { id: extension_id.to_string(), reason: e.to_string() })
+            },
+            last_error => last_error,
+        };
+
+        match last_error {
+            None => {},
+            // 서명이 없는 확장 프로그램도 많으므로 404는 조용히 건너뜀
+            Some(VsixError::NotFound { .. }) => println!("{} {}: {} 자산 없음", "건너뜀".yellow(), extension_id, source.kind),
+            Some(e) => {
+                println!("{} {}: {} 자산을 받지 못했습니다 ({}, {})", "경고:".yellow(), extension_id, source.kind, e.code().as_str(), e);
+                // 서명 문제는 결과 파일의 warnings에 남겨 자동화 도구가 확인할 수 있게 함
+                if let VsixError::SignatureInvalid { .. } = e {
+                    warnings::push(extension_id, WarningKind::SignatureInvalid, e.to_string());
+                }
+            },
+        }
+    }
+
+    if !downloaded.is_empty() {
+        println!("{} {} ({}개)", "자산 저장:".green(), dir.display(), downloaded.len());
+    }
+
+    Ok(downloaded)
+}
+
+async fn download_asset(client: &HttpClient, url: &str, path: &Path) -> Result<u64, VsixError> {
+    let response = client.get(url).await?;
+    if !response.status.is_success() {
+        return Err(VsixError::from_status(response.status, url));
+    }
+
+    let io_error = |source| VsixError::Io { path: path.to_path_buf(), source };
+    let mut file = File::create(filename::long_path(path)).map_err(io_error)?;
+    let mut stream = response.bytes_stream();
+    let mut size = 0;
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| VsixError::from_request(e, url))?;
+        file.write_all(&chunk).map_err(io_error)?;
+        size += chunk.len() as u64;
+    }
+
+    Ok(size)
+}
+
+/// 받은 서명 파일이 PKCS#7 DER 구조(SEQUENCE 태그 0x30으로 시작)인지 확인하고, 아니면 지웁니다.
+/// 오류 페이지나 빈 응답이 서명 파일로 남지 않게 합니다.
+fn check_signature(extension_id: &str, path: &Path, size: u64) -> Result<(), VsixError> {
+    let mut head = [0u8; 1];
+    let readable = File::open(filename::long_path(path))
+        .and_then(|mut file| file.read_exact(&mut head))
+        .is_ok();
+
+    if size > 0 && readable && head[0] == 0x30 {
+        return Ok(());
+    }
+
+    let _ = fs::remove_file(filename::long_path(path));
+    Err(VsixError::SignatureInvalid {
+        id: extension_id.to_string(),
+        reason: if size == 0 { "빈 파일".to_string() } else { "PKCS#7 서명 형식이 아님".to_string() },
+    })
+}
+
+/// `--assets`에 쓰는 이름 (예: `license`)
+impl std::fmt::Display for AssetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.to_possible_value() {
+            Some(value) => f.write_str(value.get_name()),
+            None => write!(f, "{:?}", self),
+        }
+    }
+}
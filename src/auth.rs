@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+/// OS 키링에 토큰을 저장할 때 쓰는 서비스 이름
+const KEYRING_SERVICE: &str = "vsix-downloader";
+
+/// 토큰을 저장할 수 있는 레지스트리 (목록 항목의 `source`로도 씀)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Registry {
+    /// VSCode Marketplace (Azure DevOps 개인 액세스 토큰)
+    Marketplace,
+    /// Open VSX
+    #[serde(alias = "open-vsx")]
+    OpenVsx,
+}
+
+impl Registry {
+    const ALL: [Registry; 2] = [Registry::Marketplace, Registry::OpenVsx];
+
+    /// 키링 항목의 계정 이름
+    fn account(self) -> &'static str {
+        match self {
+            Registry::Marketplace => "marketplace",
+            Registry::OpenVsx => "open-vsx",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Registry::Marketplace => "VSCode Marketplace",
+            Registry::OpenVsx => "Open VSX",
+        }
+    }
+
+    /// URL의 호스트를 맡은 레지스트리
+    pub fn for_url(url: &str) -> Option<Registry> {
+        let host = reqwest::Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+        Registry::ALL.into_iter().find(|registry| registry.serves(&host))
+    }
+
+    /// 이 레지스트리로 가는 요청의 호스트인지 (Marketplace는 조회 API와 VSIX CDN)
+    fn serves(self, host: &str) -> bool {
+        match self {
+            Registry::Marketplace => host == "marketplace.visualstudio.com" || host.ends_with(".vsassets.io"),
+            Registry::OpenVsx => host == "open-vsx.org",
+        }
+    }
+}
+
+/// 요청에 붙일 레지스트리 토큰
+#[derive(Debug, Default, Clone)]
+pub struct Credentials {
+    marketplace: Option<String>,
+    open_vsx: Option<String>,
+}
+
+impl Credentials {
+    /// OS 키링에 저장된 토큰을 읽습니다. 키링을 쓸 수 없는 환경(헤드리스 Linux 등)에서는 토큰 없이 진행합니다.
+    /// tokio 런타임 스레드에서 부르면 멈출 수 있으므로 `spawn_blocking` 안에서 불러야 합니다.
+    pub fn from_keyring() -> Self {
+        Credentials {
+            marketplace: read_token(Registry::Marketplace).ok().flatten(),
+            open_vsx: read_token(Registry::OpenVsx).ok().flatten(),
+        }
+    }
+
+    /// 레지스트리의 토큰을 지정합니다 (파일이나 표준 입력으로 받은 토큰).
+    pub fn set(&mut self, registry: Registry, token: String) {
+        match registry {
+            Registry::Marketplace => self.marketplace = Some(token),
+            Registry::OpenVsx => self.open_vsx = Some(token),
+        }
+    }
+
+    /// URL의 호스트에 맞는 레지스트리와 토큰
+    pub fn token_for(&self, url: &str) -> Option<(Registry, &str)> {
+        let registry = Registry::for_url(url)?;
+        self.token(registry).map(|token| (registry, token))
+    }
+
+    fn token(&self, registry: Registry) -> Option<&str> {
+        match registry {
+            Registry::Marketplace => self.marketplace.as_deref(),
+            Registry::OpenVsx => self.open_vsx.as_deref(),
+        }
+    }
+}
+
+fn entry(registry: Registry) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, registry.account())
+}
+
+/// 저장된 토큰 (없으면 None)
+fn read_token(registry: Registry) -> keyring::Result<Option<String>> {
+    match entry(registry)?.get_password() {
+        Ok(token) => Ok(Some(token)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// 파일에서 토큰을 읽습니다. 앞뒤 공백과 줄바꿈은 무시합니다.
+pub fn read_token_file(path: &Path) -> Result<String> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read token file {}", path.display()))?;
+    let token = content.trim();
+    if token.is_empty() {
+        return Err(anyhow!("토큰 파일이 비어 있습니다: {}", path.display()));
+    }
+    Ok(token.to_string())
+}
+
+/// 표준 입력의 첫 줄에서 토큰을 읽습니다.
+/// 한 실행에서 여러 번 불려도(mirror-* 명령의 검색과 동기화) 표준 입력은 한 번만 읽습니다.
+pub fn read_token_stdin() -> Result<String> {
+    static TOKEN: OnceLock<String> = OnceLock::new();
+
+    if let Some(token) = TOKEN.get() {
+        return Ok(token.clone());
+    }
+
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)
+        .with_context(|| "Failed to read token from stdin")?;
+    let token = line.trim();
+    if token.is_empty() {
+        return Err(anyhow!("표준 입력에서 토큰을 읽지 못했습니다"));
+    }
+    Ok(TOKEN.get_or_init(|| token.to_string()).clone())
+}
+
+/// 표준 입력에서 토큰을 읽어 OS 키링에 저장합니다 (`auth login`).
+pub async fn login(registry: Registry) -> Result<()> {
+    println!("{} 토큰을 입력하세요:", registry.label());
+
+    let mut token = String::new();
+    std::io::stdin().read_line(&mut token)
+        .with_context(|| "Failed to read token from stdin")?;
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        return Err(anyhow!("토큰이 비어 있습니다"));
+    }
+
+    tokio::task::spawn_blocking(move || entry(registry)?.set_password(&token)).await?
+        .with_context(|| format!("{} 토큰을 OS 키링에 저장하지 못했습니다", registry.label()))?;
+
+    println!("{}", format!("{} 토큰을 OS 키링에 저장했습니다.", registry.label()).green());
+    Ok(())
+}
+
+/// OS 키링에서 토큰을 지웁니다 (`auth logout`).
+pub async fn logout(registry: Registry) -> Result<()> {
+    let deleted = tokio::task::spawn_blocking(move || match entry(registry)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e),
+    }).await?
+        .with_context(|| format!("{} 토큰을 OS 키링에서 지우지 못했습니다", registry.label()))?;
+
+    if deleted {
+        println!("{}", format!("{} 토큰을 OS 키링에서 지웠습니다.", registry.label()).green());
+    } else {
+        println!("{}", format!("저장된 {} 토큰이 없습니다.", registry.label()).yellow());
+    }
+    Ok(())
+}
+
+/// 레지스트리마다 키링에 토큰이 저장되어 있는지 출력합니다 (`auth status`). 토큰 값은 출력하지 않습니다.
+pub async fn status() -> Result<()> {
+    for registry in Registry::ALL {
+        let stored = tokio::task::spawn_blocking(move || read_token(registry)).await?;
+        match stored {
+            Ok(Some(_)) => println!("{}: {}", registry.label(), "저장됨".green()),
+            Ok(None) => println!("{}: {}", registry.label(), "없음".yellow()),
+            Err(e) => println!("{}: {} ({})", registry.label(), "키링을 읽을 수 없음".red(), e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,408 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::filename;
+use crate::size;
+use crate::vsix;
+use crate::{BundleArgs, ImportArgs};
+
+/// 번들 안의 매니페스트 (항상 첫 항목)
+const MANIFEST_ENTRY: &str = "manifest.json";
+
+/// 번들 안에서 VSIX 파일을 두는 디렉토리
+const EXTENSIONS_DIR: &str = "extensions";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum BundleKind {
+    /// 미러의 VSIX를 모두 담은 번들
+    Full,
+    /// `--since` 매니페스트 이후 추가되거나 바뀐 VSIX만 담은 번들
+    Delta,
+}
+
+/// 번들을 만든 시점의 미러 상태와 번들에 담은 파일
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleManifest {
+    created: String,
+    kind: BundleKind,
+    /// 기준 매니페스트를 만든 시각 (delta 번들만)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    since: Option<String>,
+    /// 번들을 만든 시점의 미러 전체. 다음 `--since`의 기준이 됩니다.
+    extensions: Vec<BundleEntry>,
+    /// 번들에 담은 파일 이름
+    included: Vec<String>,
+    /// 기준 매니페스트에는 있었지만 미러에서 사라진 파일 이름
+    #[serde(default)]
+    removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BundleEntry {
+    id: String,
+    version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target_platform: Option<String>,
+    file: String,
+    size: u64,
+    sha256: String,
+}
+
+impl BundleEntry {
+    /// 같은 확장 프로그램의 같은 플랫폼 빌드끼리 비교
+    fn key(&self) -> (String, Option<String>) {
+        (self.id.to_lowercase(), self.target_platform.clone())
+    }
+}
+
+/// 다운로드 디렉토리의 VSIX를 tar.gz 번들로 묶습니다. `--since`를 지정하면 기준 매니페스트 이후
+/// 추가되었거나 버전 또는 내용이 바뀐 VSIX만 담습니다. 매니페스트는 번들 안과 번들 옆에 함께 기록합니다.
+pub fn create(args: &BundleArgs) -> Result<()> {
+    let extensions = mirror_state(&args.output_dir)?;
+    if extensions.is_empty() {
+        return Err(anyhow!("{} 디렉토리에 VSIX 파일이 없습니다", args.output_dir.display()));
+    }
+
+    let base = args.since.as_deref().map(read_manifest).transpose()?;
+    let (included, removed) = match &base {
+        Some(base) => changes(&base.extensions, &extensions),
+        None => (extensions.iter().map(|e| e.file.clone()).collect(), Vec::new()),
+    };
+
+    let manifest = BundleManifest {
+        created: Utc::now().to_rfc3339(),
+        kind: if base.is_some() { BundleKind::Delta } else { BundleKind::Full },
+        since: base.map(|b| b.created),
+        extensions,
+        included,
+        removed,
+    };
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .with_context(|| "Failed to serialize bundle manifest")?;
+
+    write_bundle(&args.bundle, &args.output_dir, &manifest, &manifest_json)?;
+
+    let manifest_path = args.manifest.clone().unwrap_or_else(|| sidecar_path(&args.bundle));
+    fs::write(&manifest_path, &manifest_json)
+        .with_context(|| format!("Failed to write bundle manifest to {}", manifest_path.display()))?;
+
+    let bundle_size = fs::metadata(&args.bundle).map(|m| m.len()).unwrap_or(0);
+    let mirror_size: u64 = manifest.extensions.iter().map(|e| e.size).sum();
+    println!(
+        "{} {} ({}개 포함, {}개 삭제, 번들 {} / 미러 전체 {})",
+        "번들 작성:".green(),
+        args.bundle.display(),
+        manifest.included.len(),
+        manifest.removed.len(),
+        size::format_size(bundle_size),
+        size::format_size(mirror_size)
+    );
+    println!("{} {}", "매니페스트:".blue(), manifest_path.display());
+    if manifest.kind == BundleKind::Delta && manifest.included.is_empty() && manifest.removed.is_empty() {
+        println!("{}", "기준 매니페스트 이후 바뀐 확장 프로그램이 없습니다.".yellow());
+    }
+    Ok(())
+}
+
+/// 번들의 VSIX를 다운로드 디렉토리에 풉니다. delta 번들이면 기준이 되는 파일이 모두 미러에 있는지 먼저 확인하고,
+/// 파일마다 해시를 검증한 뒤 제자리로 옮깁니다. 삭제된 파일은 `--prune`을 지정해야 지웁니다.
+pub fn import(args: &ImportArgs) -> Result<()> {
+    let file = File::open(&args.bundle)
+        .with_context(|| format!("Failed to open {}", args.bundle.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut entries = archive.entries()
+        .with_context(|| format!("Failed to read bundle: {}", args.bundle.display()))?;
+
+    let manifest: BundleManifest = {
+        let mut first = entries.next()
+            .ok_or_else(|| anyhow!("번들이 비어 있습니다: {}", args.bundle.display()))?
+            .with_context(|| format!("Failed to read bundle: {}", args.bundle.display()))?;
+        if first.path().ok().as_deref() != Some(Path::new(MANIFEST_ENTRY)) {
+            return Err(anyhow!("번들의 첫 항목이 {}가 아닙니다: {}", MANIFEST_ENTRY, args.bundle.display()));
+        }
+        let mut content = String::new();
+        first.read_to_string(&mut content)
+            .with_context(|| format!("Failed to read {} in {}", MANIFEST_ENTRY, args.bundle.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {} in {}", MANIFEST_ENTRY, args.bundle.display()))?
+    };
+
+    // 번들은 외부에서 들어온 파일이므로 디스크를 건드리기 전에 모든 파일 이름을 확인
+    check_file_names(&manifest)?;
+
+    fs::create_dir_all(&args.output_dir)
+        .with_context(|| format!("Failed to create directory: {}", args.output_dir.display()))?;
+
+    if manifest.kind == BundleKind::Delta {
+        check_base(&manifest, &args.output_dir)?;
+    }
+
+    let expected: BTreeMap<&str, &BundleEntry> = manifest.extensions.iter()
+        .filter(|e| manifest.included.contains(&e.file))
+        .map(|e| (e.file.as_str(), e))
+        .collect();
+
+    let mut imported = 0;
+    for entry in entries {
+        let mut entry = entry.with_context(|| format!("Failed to read bundle: {}", args.bundle.display()))?;
+        let entry_path = entry.path()
+            .with_context(|| format!("Failed to read bundle: {}", args.bundle.display()))?
+            .into_owned();
+
+        // extensions/<파일 이름> 외의 항목이나 매니페스트에 없는 파일은 풀지 않음
+        let name = match entry_path.strip_prefix(EXTENSIONS_DIR) {
+            Ok(rest) if is_plain_vsix_name(&rest.to_string_lossy()) => rest.to_string_lossy().into_owned(),
+            _ => {
+                println!("{} {}", "번들의 알 수 없는 항목이라 건너뜀:".yellow(), entry_path.display());
+                continue;
+            },
+        };
+        let Some(expected) = expected.get(name.as_str()) else {
+            println!("{} {}", "매니페스트에 없는 파일이라 건너뜀:".yellow(), name);
+            continue;
+        };
+
+        let target = filename::long_path(&args.output_dir.join(&name));
+        let staged = filename::long_path(&args.output_dir.join(format!("{}.importing", name)));
+        let mut staged_file = File::create(&staged)
+            .with_context(|| format!("Failed to create {}", staged.display()))?;
+        io::copy(&mut entry, &mut staged_file)
+            .with_context(|| format!("Failed to extract {} from {}", name, args.bundle.display()))?;
+        drop(staged_file);
+
+        let (size, sha256) = vsix::file_digest(&staged)?;
+        if size != expected.size || sha256 != expected.sha256 {
+            let _ = fs::remove_file(&staged);
+            return Err(anyhow!(
+                "번들의 {} 해시가 매니페스트와 다릅니다 (예상 {}, 실제 {}). 번들이 손상되었을 수 있습니다",
+                name, expected.sha256, sha256
+            ));
+        }
+        fs::rename(&staged, &target)
+            .with_context(|| format!("Failed to move {} to {}", staged.display(), target.display()))?;
+        println!("{} {} {}", "가져옴:".green(), expected.id, expected.version);
+        imported += 1;
+    }
+
+    if imported != manifest.included.len() {
+        return Err(anyhow!(
+            "번들에 매니페스트의 파일이 모두 들어 있지 않습니다 ({}/{}개)",
+            imported, manifest.included.len()
+        ));
+    }
+
+    let mut pruned = 0;
+    for name in &manifest.removed {
+        let path = args.output_dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        if args.prune {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            println!("{} {}", "삭제:".red(), name);
+            pruned += 1;
+        } else {
+            println!("{} {} (--prune으로 삭제)", "미러에서 사라진 파일:".yellow(), name);
+        }
+    }
+
+    println!(
+        "{}",
+        format!("{}개를 가져왔습니다{}.", imported, if pruned > 0 { format!(", {}개를 삭제했습니다", pruned) } else { String::new() }).green()
+    );
+    Ok(())
+}
+
+/// 다운로드 디렉토리의 VSIX마다 ID, 버전, 대상 플랫폼, 크기, 해시
+fn mirror_state(dir: &Path) -> Result<Vec<BundleEntry>> {
+    let mut extensions = Vec::new();
+    for package in vsix::scan_directory(dir)? {
+        let (size, sha256) = vsix::file_digest(&package.path)?;
+        let target_platform = vsix::read_identity(&package.path).ok().and_then(|i| i.target_platform);
+        let file = package.path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        extensions.push(BundleEntry { id: package.id, version: package.version, target_platform, file, size, sha256 });
+    }
+    extensions.sort_by(|a, b| a.key().cmp(&b.key()).then_with(|| a.file.cmp(&b.file)));
+    Ok(extensions)
+}
+
+/// 기준 상태 이후 추가되었거나 버전 또는 내용이 바뀐 파일, 그리고 사라진 파일.
+/// 버전이 바뀌면 파일 이름이나 해시가 달라지므로 같은 이름, 같은 해시의 파일만 그대로인 것으로 봅니다.
+fn changes(base: &[BundleEntry], current: &[BundleEntry]) -> (Vec<String>, Vec<String>) {
+    let base_by_file: BTreeMap<&str, &BundleEntry> = base.iter().map(|e| (e.file.as_str(), e)).collect();
+
+    let included = current.iter()
+        .filter(|entry| base_by_file.get(entry.file.as_str()).is_none_or(|b| b.sha256 != entry.sha256))
+        .map(|e| e.file.clone())
+        .collect();
+
+    let removed = base.iter()
+        .filter(|b| !current.iter().any(|e| e.file == b.file))
+        .map(|b| b.file.clone())
+        .collect();
+
+    (included, removed)
+}
+
+/// 매니페스트의 파일 이름이 모두 다운로드 디렉토리 바로 아래의 `.vsix` 파일을 가리키는지 확인합니다.
+/// `../`, 절대 경로, 하위 디렉토리를 가리키는 이름이 하나라도 있으면 아무것도 하지 않고 실패합니다.
+fn check_file_names(manifest: &BundleManifest) -> Result<()> {
+    let invalid: Vec<&str> = manifest.included.iter()
+        .chain(&manifest.removed)
+        .chain(manifest.extensions.iter().map(|e| &e.file))
+        .map(String::as_str)
+        .filter(|name| !is_plain_vsix_name(name))
+        .collect();
+
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "번들 매니페스트에 다운로드 디렉토리 밖이나 VSIX가 아닌 파일을 가리키는 이름이 있어 가져오지 않습니다: {}",
+        invalid.join(", ")
+    ))
+}
+
+/// 디렉토리 구분자나 `..` 없이 파일 이름 하나로만 이루어진 `.vsix` 이름인지
+fn is_plain_vsix_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!((components.next(), components.next()), (Some(Component::Normal(_)), None))
+        && !name.contains(['/', '\\'])
+        && Path::new(name).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("vsix"))
+}
+
+/// delta 번들에 담기지 않은 파일이 미러에 기준과 같은 내용으로 있는지 확인합니다.
+fn check_base(manifest: &BundleManifest, output_dir: &Path) -> Result<()> {
+    let mut missing = Vec::new();
+    for entry in manifest.extensions.iter().filter(|e| !manifest.included.contains(&e.file)) {
+        let path = output_dir.join(&entry.file);
+        let matches = path.exists() && vsix::file_digest(&path).is_ok_and(|(_, sha256)| sha256 == entry.sha256);
+        if !matches {
+            missing.push(entry.file.as_str());
+        }
+    }
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    for name in &missing {
+        println!("  {}", name);
+    }
+    Err(anyhow!(
+        "delta 번들의 기준({})이 되는 파일 {}개가 미러에 없거나 내용이 다릅니다. 이전 번들을 먼저 가져오세요",
+        manifest.since.as_deref().unwrap_or("알 수 없음"),
+        missing.len()
+    ))
+}
+
+/// 매니페스트 JSON 파일 또는 번들(tar.gz) 안의 매니페스트를 읽습니다.
+fn read_manifest(path: &Path) -> Result<BundleManifest> {
+    let content = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut archive = tar::Archive::new(GzDecoder::new(file));
+        let mut first = archive.entries()
+            .with_context(|| format!("Failed to read bundle: {}", path.display()))?
+            .next()
+            .ok_or_else(|| anyhow!("번들이 비어 있습니다: {}", path.display()))?
+            .with_context(|| format!("Failed to read bundle: {}", path.display()))?;
+        let mut content = String::new();
+        first.read_to_string(&mut content)
+            .with_context(|| format!("Failed to read {} in {}", MANIFEST_ENTRY, path.display()))?;
+        content
+    };
+
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse bundle manifest {}", path.display()))
+}
+
+/// 매니페스트를 첫 항목으로, 포함할 VSIX를 `extensions/` 아래에 담습니다.
+/// 중간에 실패해도 이전 번들이 절반만 쓰인 파일로 바뀌지 않도록 임시 파일에 쓴 뒤 이름을 바꿉니다.
+fn write_bundle(bundle: &Path, output_dir: &Path, manifest: &BundleManifest, manifest_json: &str) -> Result<()> {
+    let staged = {
+        let mut name = bundle.as_os_str().to_os_string();
+        name.push(".partial");
+        PathBuf::from(name)
+    };
+
+    let file = File::create(&staged)
+        .with_context(|| format!("Failed to create {}", staged.display()))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_ENTRY, manifest_json.as_bytes())
+        .with_context(|| format!("Failed to write {}", staged.display()))?;
+
+    for name in &manifest.included {
+        builder.append_path_with_name(output_dir.join(name), Path::new(EXTENSIONS_DIR).join(name))
+            .with_context(|| format!("Failed to add {} to {}", name, staged.display()))?;
+    }
+
+    builder.into_inner()
+        .and_then(|encoder| encoder.finish())
+        .with_context(|| format!("Failed to write {}", staged.display()))?;
+    fs::rename(&staged, bundle)
+        .with_context(|| format!("Failed to replace {}", bundle.display()))
+}
+
+/// 번들 옆에 두는 매니페스트 (`mirror.tar.gz` -> `mirror.tar.gz.manifest.json`)
+fn sidecar_path(bundle: &Path) -> PathBuf {
+    let mut name = bundle.as_os_str().to_os_string();
+    name.push(".manifest.json");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_plain_vsix_names() {
+        assert!(is_plain_vsix_name("ms-python.python-2024.1.0.vsix"));
+        assert!(is_plain_vsix_name("rust-lang.rust-analyzer@linux-x64.VSIX"));
+
+        assert!(!is_plain_vsix_name("../../home/u/.ssh/id_rsa"));
+        assert!(!is_plain_vsix_name("../evil.vsix"));
+        assert!(!is_plain_vsix_name("/etc/evil.vsix"));
+        assert!(!is_plain_vsix_name("sub/evil.vsix"));
+        assert!(!is_plain_vsix_name("sub\\evil.vsix"));
+        assert!(!is_plain_vsix_name(".."));
+        assert!(!is_plain_vsix_name("notes.txt"));
+        assert!(!is_plain_vsix_name(""));
+    }
+
+    #[test]
+    fn rejects_manifest_with_traversal() {
+        let manifest = BundleManifest {
+            created: String::new(),
+            kind: BundleKind::Delta,
+            since: None,
+            extensions: Vec::new(),
+            included: vec!["a.vsix".to_string()],
+            removed: vec!["../../home/u/.ssh/id_rsa".to_string()],
+        };
+        assert!(check_file_names(&manifest).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::auth;
+use crate::error::ErrorCode;
+use crate::http::HttpClient;
+use crate::marketplace;
+use crate::output;
+use crate::warnings::{self, Warning, WarningKind};
+use crate::{AvailableExtension, CheckOutcome, Extension, UnavailableExtension};
+
+/// Open VSX 확인 결과 캐시 파일 (현재 디렉토리, 한 줄에 확인 하나인 JSON Lines).
+/// 확인 결과는 프로필과 관계없이 같으므로 모든 프로필과 daemon 실행이 함께 씁니다.
+pub const CACHE_FILE: &str = "check-cache.jsonl";
+
+/// 확장 프로그램 하나의 Open VSX 확인 결과
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedCheck {
+    /// `id@버전` (버전을 고정하지 않았으면 확인할 때의 Marketplace 최신 버전, 확인된 네임스페이스만 받을 때는 `#verified`가 붙음)
+    key: String,
+    checked_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    available: Option<AvailableExtension>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unavailable: Option<UnavailableExtension>,
+    /// 확인할 때 남긴 경고 (사용 중단, ID 대소문자)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<Warning>,
+}
+
+struct Cache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CachedCheck>>,
+    /// 버전을 고정하지 않은 항목의 Marketplace 최신 버전 (소문자 ID 기준, 동기화마다 새로 조회)
+    latest: Mutex<HashMap<String, String>>,
+}
+
+static CACHE: OnceLock<Cache> = OnceLock::new();
+
+/// `--check-ttl`로 캐시를 켭니다. 기한이 지난 항목을 빼고 캐시 파일을 다시 씁니다.
+/// 한 프로세스에서 여러 번 동기화해도(daemon, --all-profiles) 처음 정한 기한을 씁니다.
+pub fn enable(ttl: Duration) -> Result<()> {
+    if CACHE.get().is_some() {
+        return Ok(());
+    }
+
+    let path = Path::new(CACHE_FILE);
+    let mut entries = HashMap::new();
+    if path.exists() {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        // 기록 도중 끊긴 줄은 버림
+        for entry in content.lines().filter_map(|line| serde_json::from_str::<CachedCheck>(line).ok()) {
+            if is_fresh(&entry, ttl) {
+                entries.insert(entry.key.clone(), entry);
+            }
+        }
+    }
+
+    let mut compacted = String::new();
+    for entry in entries.values() {
+        compacted.push_str(&serde_json::to_string(entry).with_context(|| "Failed to serialize check cache entry")?);
+        compacted.push('\n');
+    }
+    output::write_atomically(path, compacted.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let _ = CACHE.set(Cache { ttl, entries: Mutex::new(entries), latest: Mutex::new(HashMap::new()) });
+    Ok(())
+}
+
+/// 버전을 고정하지 않은 항목의 최신 버전을 Marketplace에 한꺼번에 조회합니다.
+/// 캐시는 이 버전으로 찾으므로 새 버전이 나오면 기한 안이라도 다시 확인합니다.
+/// 조회하지 못한 항목(Marketplace에 없거나 조회 실패)은 캐시를 쓰지 않고 매번 확인합니다.
+pub async fn resolve_latest(client: &HttpClient, extensions: &[Extension]) {
+    let Some(cache) = CACHE.get() else {
+        return;
+    };
+
+    let unpinned: Vec<&str> = extensions.iter()
+        .filter(|e| !e.id.is_empty() && e.version.is_none() && e.source != Some(auth::Registry::Marketplace))
+        .map(|e| e.id.as_str())
+        .collect();
+    if unpinned.is_empty() {
+        return;
+    }
+
+    let latest = match marketplace::latest_versions(client, &unpinned).await {
+        Ok(latest) => latest,
+        Err(e) => {
+            println!("{} {:#}", "최신 버전을 조회하지 못해 버전을 고정하지 않은 항목은 캐시 없이 확인합니다:".yellow(), e);
+            HashMap::new()
+        },
+    };
+    *cache.latest.lock().expect("check cache lock poisoned") = latest;
+}
+
+/// 기한 안에 확인한 결과가 있으면 이번 목록 항목의 정보(UUID, 목록 파일 등)를 채워 돌려줍니다.
+pub fn lookup(extension: &Extension, verified_only: bool) -> Option<CheckOutcome> {
+    let cache = CACHE.get()?;
+    let key = key(cache, extension, verified_only)?;
+    let entries = cache.entries.lock().expect("check cache lock poisoned");
+    let entry = entries.get(&key).filter(|entry| is_fresh(entry, cache.ttl))?;
+    for warning in &entry.warnings {
+        warnings::push(&extension.id, warning.kind, warning.message.clone());
+    }
+
+    if let Some(available) = &entry.available {
+        return Some(CheckOutcome::Available(AvailableExtension {
+            id: extension.id.clone(),
+            uuid: extension.uuid.clone(),
+            source_file: extension.source_file.clone(),
+            ..available.clone()
+        }));
+    }
+    entry.unavailable.as_ref().map(|unavailable| CheckOutcome::Unavailable(UnavailableExtension {
+        id: extension.id.clone(),
+        uuid: extension.uuid.clone(),
+        version: extension.version.clone(),
+        source: extension.source,
+        source_file: extension.source_file.clone(),
+        ..unavailable.clone()
+    }))
+}
+
+/// 확인 결과를 캐시에 기록합니다. 네트워크 오류처럼 다음에 달라질 수 있는 결과는 기록하지 않습니다.
+pub fn store(extension: &Extension, verified_only: bool, outcome: &CheckOutcome) -> Result<()> {
+    let Some(cache) = CACHE.get() else {
+        return Ok(());
+    };
+    let Some(key) = key(cache, extension, verified_only) else {
+        return Ok(());
+    };
+
+    let (available, unavailable) = match outcome {
+        CheckOutcome::Available(available) => (Some(available.clone()), None),
+        CheckOutcome::Unavailable(unavailable) if matches!(unavailable.error, None | Some(ErrorCode::NotFound)) => {
+            (None, Some(unavailable.clone()))
+        },
+        CheckOutcome::Unavailable(_) => return Ok(()),
+    };
+    let entry = CachedCheck {
+        key,
+        checked_at: Utc::now().to_rfc3339(),
+        available,
+        unavailable,
+        warnings: warnings::of(&extension.id).into_iter()
+            .filter(|w| matches!(w.kind, WarningKind::Deprecated | WarningKind::IdCase))
+            .collect(),
+    };
+
+    let mut line = serde_json::to_string(&entry)
+        .with_context(|| "Failed to serialize check cache entry")?;
+    line.push('\n');
+
+    let mut entries = cache.entries.lock().expect("check cache lock poisoned");
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(CACHE_FILE)
+        .with_context(|| format!("Failed to open {}", CACHE_FILE))?;
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("Failed to append to {}", CACHE_FILE))?;
+    entries.insert(entry.key.clone(), entry);
+    Ok(())
+}
+
+/// 고정한 버전이나 조회한 최신 버전으로 만든 캐시 키. 최신 버전을 모르면 캐시를 쓰지 않습니다.
+fn key(cache: &Cache, extension: &Extension, verified_only: bool) -> Option<String> {
+    let id = extension.id.to_ascii_lowercase();
+    let version = match &extension.version {
+        Some(version) => version.clone(),
+        None => cache.latest.lock().expect("check cache lock poisoned").get(&id)?.clone(),
+    };
+    Some(format!("{}@{}{}", id, version, if verified_only { "#verified" } else { "" }))
+}
+
+fn is_fresh(entry: &CachedCheck, ttl: Duration) -> bool {
+    DateTime::parse_from_rfc3339(&entry.checked_at)
+        .ok()
+        .and_then(|checked_at| (Utc::now() - checked_at.with_timezone(&Utc)).to_std().ok())
+        .is_some_and(|age| age < ttl)
+}
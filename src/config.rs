@@ -0,0 +1,24 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+
+use crate::encoding;
+
+/// 설정 파일에서 최상위 키 하나를 읽습니다. 설정 파일이나 키가 없으면 기본값입니다.
+pub fn section<T: DeserializeOwned + Default>(config: &Path, key: &str) -> Result<T> {
+    if !config.exists() {
+        return Ok(T::default());
+    }
+
+    let content = encoding::read_file(config)
+        .with_context(|| format!("Failed to read config file {}", config.display()))?;
+    let value: serde_yaml::Value = serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse config file {}", config.display()))?;
+
+    match value.get(key) {
+        Some(section) if !section.is_null() => serde_yaml::from_value(section.clone())
+            .with_context(|| format!("Failed to parse {} in {}", key, config.display())),
+        _ => Ok(T::default()),
+    }
+}
@@ -0,0 +1,45 @@
+use anyhow::{anyhow, Result};
+use reqwest::StatusCode;
+
+use crate::error::VsixError;
+use crate::http::HttpClient;
+use crate::OPEN_VSX_API;
+
+/// Open VSX에 같은 버전(플랫폼별 빌드면 같은 대상 플랫폼)이 게시되어 있으면 그 SHA-256을 가져옵니다.
+/// Open VSX에 해당 버전이 없거나 해시가 게시되지 않았으면 None입니다.
+pub async fn published_sha256(
+    client: &HttpClient,
+    extension_id: &str,
+    version: &str,
+    target: Option<&str>
+) -> Result<Option<String>> {
+    let (namespace, name) = extension_id.split_once('.')
+        .ok_or_else(|| anyhow!("publisher.name 형식이 아닌 ID: {}", extension_id))?;
+
+    let url = match target {
+        Some(target) => format!("{}/{}/{}/{}/{}", OPEN_VSX_API, namespace, name, target, version),
+        None => format!("{}/{}/{}/{}", OPEN_VSX_API, namespace, name, version),
+    };
+
+    let response = client.get(&url).await?;
+    if response.status == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status.is_success() {
+        return Err(VsixError::from_status(response.status, &url).into());
+    }
+
+    let data: serde_json::Value = response.json().await?;
+    let Some(sha256_url) = data.get("files").and_then(|f| f.get("sha256")).and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+
+    let response = client.get(sha256_url).await?;
+    if !response.status.is_success() {
+        return Err(VsixError::from_status(response.status, sha256_url).into());
+    }
+
+    // `<해시>` 또는 `<해시>  <파일 이름>` 형식
+    let content = response.text().await?;
+    Ok(content.split_whitespace().next().map(|hash| hash.to_ascii_lowercase()))
+}
@@ -0,0 +1,204 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::Serialize;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::http::HttpClient;
+use crate::marketplace;
+use crate::preflight;
+use crate::{run_sync, DaemonArgs, SyncArgs, OPEN_VSX_API};
+
+/// 준비 상태 요청마다 레지스트리에 요청을 보내지 않도록 연결 확인 결과를 재사용하는 시간
+const REACHABILITY_TTL: Duration = Duration::from_secs(60);
+
+/// 상태 확인 요청 줄을 기다리는 시간. 연결만 열어 두는 클라이언트가 작업을 붙잡지 않도록 함
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 읽을 요청 줄의 최대 길이(바이트). 줄바꿈 없이 계속 보내도 메모리가 늘지 않도록 함
+const MAX_REQUEST_LINE: u64 = 8192;
+
+/// 상태 확인 엔드포인트가 보고하는 동기화 상태
+#[derive(Debug, Default)]
+struct Health {
+    syncing: bool,
+    last_success: Option<DateTime<Utc>>,
+    last_failure: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    /// 마지막 연결 확인 시각과 연결할 수 없던 레지스트리별 이유
+    reachability: Option<(Instant, Vec<String>)>,
+}
+
+#[derive(Serialize)]
+struct HealthBody<'a> {
+    status: &'a str,
+    syncing: bool,
+    last_success: Option<String>,
+    last_failure: Option<String>,
+    last_error: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unreachable: Option<&'a [String]>,
+}
+
+/// 목록을 `--interval`마다 동기화하고, `--listen` 주소에서 컨테이너 상태 확인 요청에 응답합니다.
+pub async fn run(args: &DaemonArgs) -> Result<()> {
+    if args.sync.token_stdin.is_some() {
+        return Err(anyhow!("daemon은 동기화마다 토큰을 다시 읽으므로 --token-stdin 대신 --marketplace-token-file이나 --openvsx-token-file을 사용하세요"));
+    }
+
+    let health = Arc::new(Mutex::new(Health::default()));
+    let listener = TcpListener::bind(args.listen).await
+        .with_context(|| format!("Failed to listen on {}", args.listen))?;
+    println!("{} http://{}/healthz, /readyz", "상태 확인 엔드포인트:".blue(), args.listen);
+
+    let probe_client = HttpClient::new(args.sync.http_mode())?;
+    tokio::spawn(serve(listener, health.clone(), probe_client, args.sync.marketplace_only, args.stale_after));
+
+    // 무인 실행이므로 확인 없이 다운로드
+    let sync = SyncArgs {
+        auto_download: true,
+        ..args.sync.clone()
+    };
+
+    loop {
+        health.lock().expect("health lock poisoned").syncing = true;
+        let result = run_sync(&sync, None).await;
+
+        {
+            let mut health = health.lock().expect("health lock poisoned");
+            health.syncing = false;
+            match &result {
+                Ok(()) => health.last_success = Some(Utc::now()),
+                Err(e) => {
+                    health.last_failure = Some(Utc::now());
+                    health.last_error = Some(format!("{:#}", e));
+                },
+            }
+        }
+
+        if let Err(e) = result {
+            println!("{} {:#}", "동기화 실패 (다음 주기에 다시 시도):".red(), e);
+        }
+        println!("{}", format!("다음 동기화까지 {}초 기다립니다.", args.interval.as_secs()).blue());
+        tokio::time::sleep(args.interval).await;
+    }
+}
+
+async fn serve(
+    listener: TcpListener,
+    health: Arc<Mutex<Health>>,
+    client: HttpClient,
+    marketplace_only: bool,
+    stale_after: Option<Duration>
+) {
+    let client = Arc::new(client);
+    let started = Utc::now();
+
+    loop {
+        let Ok((stream, peer)) = listener.accept().await else {
+            continue;
+        };
+        let health = health.clone();
+        let client = client.clone();
+        tokio::spawn(async move {
+            if let Err(e) = respond(stream, &health, &client, marketplace_only, stale_after, started).await {
+                println!("{} {}: {:#}", "경고: 상태 확인 요청 처리 실패".yellow(), peer, e);
+            }
+        });
+    }
+}
+
+/// 요청 줄만 읽어 경로에 따라 응답합니다.
+/// - `/healthz`: 프로세스가 살아 있으면 200. `--stale-after`보다 오래 동기화에 성공하지 못했으면 503
+/// - `/readyz`: 한 번 이상 동기화에 성공했고 레지스트리에 연결할 수 있으면 200, 아니면 503
+async fn respond(
+    stream: TcpStream,
+    health: &Mutex<Health>,
+    client: &HttpClient,
+    marketplace_only: bool,
+    stale_after: Option<Duration>,
+    started: DateTime<Utc>
+) -> Result<()> {
+    let mut reader = BufReader::new(stream.take(MAX_REQUEST_LINE));
+    let mut request_line = String::new();
+    tokio::time::timeout(REQUEST_TIMEOUT, reader.read_line(&mut request_line))
+        .await
+        .map_err(|_| anyhow!("{}초 안에 요청을 받지 못했습니다", REQUEST_TIMEOUT.as_secs()))??;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let path = path.split('?').next().unwrap_or(path);
+
+    let (code, body) = match path {
+        "/healthz" => {
+            let health = health.lock().expect("health lock poisoned");
+            let since = health.last_success.unwrap_or(started);
+            let stale = stale_after.is_some_and(|limit| {
+                (Utc::now() - since).to_std().unwrap_or_default() > limit
+            });
+            let status = if stale { "stale" } else { "ok" };
+            (if stale { 503 } else { 200 }, body(&health, status, None))
+        },
+        "/readyz" => {
+            let unreachable = reachability(health, client, marketplace_only).await;
+            let health = health.lock().expect("health lock poisoned");
+            let (code, status) = match (health.last_success, unreachable.is_empty()) {
+                (Some(_), true) => (200, "ready"),
+                (None, _) => (503, "no_successful_sync"),
+                (Some(_), false) => (503, "upstream_unreachable"),
+            };
+            (code, body(&health, status, Some(&unreachable)))
+        },
+        _ => (404, "{\"status\":\"not_found\"}".to_string()),
+    };
+
+    let reason = match code {
+        200 => "OK",
+        404 => "Not Found",
+        _ => "Service Unavailable",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code, reason, body.len(), body
+    );
+    let mut stream = reader.into_inner().into_inner();
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn body(health: &Health, status: &str, unreachable: Option<&[String]>) -> String {
+    let body = HealthBody {
+        status,
+        syncing: health.syncing,
+        last_success: health.last_success.map(|t| t.to_rfc3339()),
+        last_failure: health.last_failure.map(|t| t.to_rfc3339()),
+        last_error: health.last_error.as_deref(),
+        unreachable,
+    };
+    serde_json::to_string(&body).unwrap_or_default()
+}
+
+/// 연결할 수 없는 레지스트리와 이유. 최근 확인 결과가 있으면 다시 요청하지 않습니다.
+async fn reachability(health: &Mutex<Health>, client: &HttpClient, marketplace_only: bool) -> Vec<String> {
+    if let Some((checked, unreachable)) = &health.lock().expect("health lock poisoned").reachability {
+        if checked.elapsed() < REACHABILITY_TTL {
+            return unreachable.clone();
+        }
+    }
+
+    let mut unreachable = Vec::new();
+    if !marketplace_only {
+        if let Some(reason) = preflight::probe(client, &format!("{}/-/search?size=1", OPEN_VSX_API)).await {
+            unreachable.push(format!("Open VSX: {}", reason));
+        }
+    }
+    if let Some(reason) = preflight::probe(client, marketplace::EXTENSION_QUERY_URL).await {
+        unreachable.push(format!("Marketplace: {}", reason));
+    }
+
+    health.lock().expect("health lock poisoned").reachability = Some((Instant::now(), unreachable.clone()));
+    unreachable
+}
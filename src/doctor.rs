@@ -0,0 +1,240 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+
+use crate::http::{HttpClient, HttpMode};
+use crate::marketplace;
+use crate::{DoctorArgs, OPEN_VSX_API};
+
+/// Marketplace VSIX를 내려주는 CDN 주소 (게시자별 `<게시자>.gallery.vsassets.io`도 같은 CDN)
+const GALLERY_CDN_URL: &str = "https://gallerycdn.vsassets.io/";
+
+/// 프록시 설정에 쓰이는 환경 변수 (reqwest가 읽는 순서와 같음)
+const PROXY_VARIABLES: [&str; 6] = ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"];
+
+/// 진단 항목 하나의 결과
+enum Outcome {
+    Ok(String),
+    Warning(String),
+    Failed { problem: String, fix: String },
+}
+
+struct Diagnosis {
+    failures: usize,
+}
+
+impl Diagnosis {
+    fn report(&mut self, name: &str, outcome: Outcome) {
+        match outcome {
+            Outcome::Ok(detail) => println!("{} {}: {}", "[OK]".green(), name, detail),
+            Outcome::Warning(detail) => println!("{} {}: {}", "[경고]".yellow(), name, detail),
+            Outcome::Failed { problem, fix } => {
+                self.failures += 1;
+                println!("{} {}: {}", "[실패]".red(), name, problem);
+                println!("       {} {}", "해결:".yellow(), fix);
+            },
+        }
+    }
+}
+
+/// 레지스트리 연결(DNS, TLS), 프록시와 CA 설정, 출력 경로 쓰기 권한을 확인하고 문제마다 해결 방법을 출력합니다.
+pub async fn run(args: &DoctorArgs) -> Result<()> {
+    let mut diagnosis = Diagnosis { failures: 0 };
+
+    println!("{}", "프록시와 인증서 설정".blue());
+    let proxy = check_proxy(&mut diagnosis);
+    check_ca(&mut diagnosis);
+
+    println!("\n{}", "레지스트리 연결".blue());
+    let client = HttpClient::new(HttpMode::Live)?;
+    let endpoints = [
+        ("Open VSX API", format!("{}/-/search?size=1", OPEN_VSX_API)),
+        ("Marketplace 조회", marketplace::EXTENSION_QUERY_URL.to_string()),
+        ("Marketplace CDN", GALLERY_CDN_URL.to_string()),
+    ];
+    for (name, url) in &endpoints {
+        let outcome = match resolve(url).await {
+            Ok(()) => reach(&client, name, url, proxy.is_some()).await,
+            // 프록시를 쓰면 이름 조회는 프록시가 하므로 로컬 DNS 실패는 경고만 함
+            Err(_) if proxy.is_some() => {
+                diagnosis.report(name, Outcome::Warning("로컬 DNS 조회에 실패했지만 프록시를 거쳐 연결을 시도합니다".to_string()));
+                reach(&client, name, url, true).await
+            },
+            Err(outcome) => outcome,
+        };
+        diagnosis.report(name, outcome);
+    }
+
+    println!("\n{}", "출력 경로 쓰기 권한".blue());
+    let results_dir = args.output.as_deref()
+        .and_then(Path::parent)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let paths = [
+        ("다운로드 디렉토리", args.output_dir.as_path()),
+        ("결과 파일 디렉토리", results_dir),
+        ("작업 상태와 실행 기록 디렉토리", Path::new(".")),
+    ];
+    for (name, path) in paths {
+        diagnosis.report(name, check_writable(path));
+    }
+
+    println!();
+    if diagnosis.failures > 0 {
+        return Err(anyhow!("진단에서 문제 {}개를 찾았습니다", diagnosis.failures));
+    }
+
+    println!("{}", "문제를 찾지 못했습니다.".green());
+    Ok(())
+}
+
+/// 설정된 프록시 주소를 확인하고, 설정되어 있으면 그 주소를 반환합니다.
+fn check_proxy(diagnosis: &mut Diagnosis) -> Option<String> {
+    let Some((variable, value)) = PROXY_VARIABLES.iter()
+        .find_map(|name| env::var(name).ok().filter(|v| !v.is_empty()).map(|v| (*name, v)))
+    else {
+        diagnosis.report("프록시", Outcome::Ok("설정되지 않음 (직접 연결)".to_string()));
+        return None;
+    };
+
+    let outcome = match reqwest::Url::parse(&value) {
+        Ok(url) if url.host_str().is_some() => {
+            let no_proxy = env::var("NO_PROXY").or_else(|_| env::var("no_proxy")).unwrap_or_default();
+            let exceptions = if no_proxy.is_empty() { String::new() } else { format!(", 제외: {}", no_proxy) };
+            Outcome::Ok(format!("{}={}{}", variable, redact(&url), exceptions))
+        },
+        _ => Outcome::Failed {
+            problem: format!("{}의 값이 올바른 URL이 아닙니다: {}", variable, value),
+            fix: format!("{}을(를) http://host:port 형식으로 설정하세요", variable),
+        },
+    };
+    diagnosis.report("프록시", outcome);
+
+    Some(value)
+}
+
+/// 프록시 URL에 들어 있는 비밀번호를 가립니다.
+fn redact(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    if url.password().is_some() {
+        let _ = url.set_password(Some("****"));
+    }
+    url.to_string()
+}
+
+/// 사용자 지정 CA 인증서 경로(SSL_CERT_FILE, SSL_CERT_DIR)가 실제로 있는지 확인합니다.
+fn check_ca(diagnosis: &mut Diagnosis) {
+    let mut configured = false;
+
+    if let Ok(file) = env::var("SSL_CERT_FILE") {
+        configured = true;
+        let outcome = match fs::read(&file) {
+            Ok(content) if String::from_utf8_lossy(&content).contains("BEGIN CERTIFICATE") => {
+                Outcome::Ok(format!("SSL_CERT_FILE={}", file))
+            },
+            Ok(_) => Outcome::Failed {
+                problem: format!("SSL_CERT_FILE({})에 PEM 인증서가 없습니다", file),
+                fix: "회사 루트 CA를 PEM(-----BEGIN CERTIFICATE-----) 형식으로 내보내 그 파일을 지정하세요".to_string(),
+            },
+            Err(e) => Outcome::Failed {
+                problem: format!("SSL_CERT_FILE({})을 읽을 수 없습니다: {}", file, e),
+                fix: "경로를 고치거나 SSL_CERT_FILE 설정을 지우세요".to_string(),
+            },
+        };
+        diagnosis.report("CA 인증서 파일", outcome);
+    }
+
+    if let Ok(dir) = env::var("SSL_CERT_DIR") {
+        configured = true;
+        let outcome = if Path::new(&dir).is_dir() {
+            Outcome::Ok(format!("SSL_CERT_DIR={}", dir))
+        } else {
+            Outcome::Failed {
+                problem: format!("SSL_CERT_DIR({})이 디렉토리가 아닙니다", dir),
+                fix: "경로를 고치거나 SSL_CERT_DIR 설정을 지우세요".to_string(),
+            }
+        };
+        diagnosis.report("CA 인증서 디렉토리", outcome);
+    }
+
+    if !configured {
+        diagnosis.report("CA 인증서", Outcome::Ok("시스템 기본 인증서 저장소 사용".to_string()));
+    }
+}
+
+/// URL의 호스트 이름을 DNS로 찾을 수 있는지 확인합니다.
+async fn resolve(url: &str) -> Result<(), Outcome> {
+    let host = reqwest::Url::parse(url).ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+
+    let addresses = tokio::net::lookup_host(format!("{}:443", host)).await
+        .map(|addresses| addresses.count());
+    match addresses {
+        Ok(0) => Err(dns_failure(&host, "주소가 없습니다".to_string())),
+        Ok(_) => Ok(()),
+        Err(e) => Err(dns_failure(&host, e.to_string())),
+    }
+}
+
+fn dns_failure(host: &str, reason: String) -> Outcome {
+    Outcome::Failed {
+        problem: format!("{}의 DNS 조회에 실패했습니다: {}", host, reason),
+        fix: format!("DNS 설정을 확인하거나, 프록시를 거쳐야 하는 네트워크라면 HTTPS_PROXY를 설정하세요 (허용 목록에 {} 추가 필요)", host),
+    }
+}
+
+/// 엔드포인트에 실제로 요청을 보내 TLS 연결과 응답을 확인합니다.
+async fn reach(client: &HttpClient, name: &str, url: &str, proxied: bool) -> Outcome {
+    let result = if url == marketplace::EXTENSION_QUERY_URL {
+        marketplace::top_extensions(client, 1, None).await.map(|_| "조회 응답 정상".to_string())
+    } else {
+        match client.get(url).await {
+            // CDN 루트는 404를 돌려주지만 연결과 TLS가 정상이라는 뜻
+            Ok(response) if response.status.is_success() || name == "Marketplace CDN" => {
+                Ok(format!("HTTP {}", response.status.as_u16()))
+            },
+            Ok(response) => Err(anyhow!("HTTP {}", response.status)),
+            Err(e) => Err(e.into()),
+        }
+    };
+
+    match result {
+        Ok(detail) => Outcome::Ok(detail),
+        Err(e) => {
+            // 오류 체인이 길어 마지막 원인만 덧붙여 보여줌
+            let cause = e.root_cause().to_string();
+            let fix = if format!("{:#}", e).to_lowercase().contains("certificate") {
+                "TLS 인증서를 확인할 수 없습니다. TLS 검사 프록시를 쓰는 네트워크라면 회사 루트 CA를 SSL_CERT_FILE로 지정하세요".to_string()
+            } else if proxied {
+                "프록시가 이 호스트로의 연결을 허용하는지, 프록시 인증 정보가 맞는지 확인하세요".to_string()
+            } else {
+                "방화벽이 이 호스트로의 HTTPS(443) 연결을 막고 있는지 확인하고, 필요하면 HTTPS_PROXY를 설정하세요".to_string()
+            };
+            Outcome::Failed { problem: format!("{} ({})", e, cause), fix }
+        },
+    }
+}
+
+/// 디렉토리를 만들고 임시 파일을 써서 지워 봅니다.
+fn check_writable(dir: &Path) -> Outcome {
+    let probe = dir.join(".vsix-downloader-doctor");
+    let result = fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&probe, b"doctor"))
+        .and_then(|_| fs::remove_file(&probe));
+
+    match result {
+        Ok(()) => Outcome::Ok(format!("{} 쓰기 가능", dir.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => Outcome::Failed {
+            problem: format!("{}에 쓸 권한이 없습니다", dir.display()),
+            fix: "디렉토리 소유자와 권한을 확인하거나 다른 경로를 지정하세요".to_string(),
+        },
+        Err(e) => Outcome::Failed {
+            problem: format!("{}에 쓸 수 없습니다: {}", dir.display(), e),
+            fix: "경로가 파일이 아닌 디렉토리인지, 디스크에 여유 공간이 있는지 확인하세요".to_string(),
+        },
+    }
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+/// `30s`, `15m`, `6h`, `1d` 같은 기간 문자열을 변환합니다. 단위가 없으면 초로 간주합니다.
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let split_at = input.find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split_at);
+
+    let number: u64 = number.parse()
+        .map_err(|_| format!("유효하지 않은 기간: {}", input))?;
+
+    let seconds: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        other => return Err(format!("알 수 없는 기간 단위: {}", other)),
+    };
+
+    Ok(Duration::from_secs(number * seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_units() {
+        assert_eq!(parse_duration("30"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("15m"), Ok(Duration::from_secs(15 * 60)));
+        assert_eq!(parse_duration("6H"), Ok(Duration::from_secs(6 * 60 * 60)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::from_secs(24 * 60 * 60)));
+        assert_eq!(parse_duration("2w"), Ok(Duration::from_secs(14 * 24 * 60 * 60)));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert!(parse_duration("1.5h").is_err());
+        assert!(parse_duration("10y").is_err());
+        assert!(parse_duration("h").is_err());
+    }
+}
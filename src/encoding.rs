@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+/// 텍스트 파일 인코딩 (BOM이나 NUL 바이트 배치로 감지)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16 LE",
+            Encoding::Utf16Be => "UTF-16 BE",
+            Encoding::Utf32Le => "UTF-32 LE",
+            Encoding::Utf32Be => "UTF-32 BE",
+        }
+    }
+}
+
+/// 파일을 읽어 [`decode`]로 문자열로 바꿉니다.
+pub fn read_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    decode(&bytes, &path.display().to_string())
+}
+
+/// Windows 도구가 내보낸 파일처럼 BOM이 붙었거나 UTF-16으로 저장된 텍스트를 UTF-8 문자열로 바꿉니다.
+/// BOM은 떼어 내고, 읽을 수 없는 인코딩이면 감지한 인코딩 이름과 함께 오류를 돌려줍니다.
+pub fn decode(bytes: &[u8], origin: &str) -> Result<String> {
+    let (encoding, body) = detect(bytes);
+
+    let text = match encoding {
+        Encoding::Utf8 => String::from_utf8(body.to_vec()).map_err(|e| anyhow!(
+            "{}을(를) UTF-8로 읽을 수 없습니다 ({}번째 바이트). CP949 같은 다른 인코딩으로 저장된 파일이면 UTF-8로 다시 저장하세요",
+            origin, e.utf8_error().valid_up_to() + 1
+        ))?,
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            if body.len() % 2 != 0 {
+                return Err(anyhow!("{}이(가) {}로 보이지만 길이가 맞지 않습니다 (잘린 파일일 수 있음)", origin, encoding.name()));
+            }
+            let units: Vec<u16> = body.chunks_exact(2)
+                .map(|pair| match encoding {
+                    Encoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                    _ => u16::from_be_bytes([pair[0], pair[1]]),
+                })
+                .collect();
+            String::from_utf16(&units)
+                .map_err(|_| anyhow!("{}이(가) {}로 보이지만 올바른 {} 텍스트가 아닙니다", origin, encoding.name(), encoding.name()))?
+        },
+        Encoding::Utf32Le | Encoding::Utf32Be => {
+            return Err(anyhow!("{}은(는) {}로 저장되어 있어 읽을 수 없습니다. UTF-8로 다시 저장하세요", origin, encoding.name()));
+        },
+    };
+
+    // BOM 없이 감지한 UTF-16에도 BOM 문자가 남아 있을 수 있음
+    Ok(text.strip_prefix('\u{feff}').map(str::to_string).unwrap_or(text))
+}
+
+/// 인코딩과 BOM을 뺀 본문
+fn detect(bytes: &[u8]) -> (Encoding, &[u8]) {
+    // UTF-32 LE BOM(FF FE 00 00)은 UTF-16 LE BOM으로 시작하므로 먼저 확인
+    if let Some(body) = bytes.strip_prefix(&[0xFF, 0xFE, 0x00, 0x00]) {
+        return (Encoding::Utf32Le, body);
+    }
+    if let Some(body) = bytes.strip_prefix(&[0x00, 0x00, 0xFE, 0xFF]) {
+        return (Encoding::Utf32Be, body);
+    }
+    if let Some(body) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return (Encoding::Utf8, body);
+    }
+    if let Some(body) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return (Encoding::Utf16Le, body);
+    }
+    if let Some(body) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return (Encoding::Utf16Be, body);
+    }
+
+    // BOM 없는 UTF-16: ASCII 문자의 한쪽 바이트가 모두 0
+    let head = &bytes[..bytes.len().min(64) / 2 * 2];
+    let pairs = head.len() / 2;
+    if pairs >= 2 {
+        let zero_even = head.iter().step_by(2).filter(|b| **b == 0).count();
+        let zero_odd = head.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+        if zero_odd * 2 >= pairs && zero_even == 0 {
+            return (Encoding::Utf16Le, bytes);
+        }
+        if zero_even * 2 >= pairs && zero_odd == 0 {
+            return (Encoding::Utf16Be, bytes);
+        }
+    }
+
+    (Encoding::Utf8, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_boms() {
+        assert_eq!(detect(b"\xEF\xBB\xBFa.b"), (Encoding::Utf8, &b"a.b"[..]));
+        assert_eq!(detect(b"\xFF\xFEa\x00"), (Encoding::Utf16Le, &b"a\x00"[..]));
+        assert_eq!(detect(b"\xFE\xFF\x00a"), (Encoding::Utf16Be, &b"\x00a"[..]));
+        assert_eq!(detect(b"\xFF\xFE\x00\x00a\x00\x00\x00"), (Encoding::Utf32Le, &b"a\x00\x00\x00"[..]));
+        assert_eq!(detect(b"\x00\x00\xFE\xFF"), (Encoding::Utf32Be, &b""[..]));
+    }
+
+    #[test]
+    fn detects_utf16_without_bom() {
+        assert_eq!(detect(b"a\x00.\x00b\x00").0, Encoding::Utf16Le);
+        assert_eq!(detect(b"\x00a\x00.\x00b").0, Encoding::Utf16Be);
+        assert_eq!(detect("확장.txt".as_bytes()).0, Encoding::Utf8);
+    }
+
+    #[test]
+    fn decodes_utf16_and_strips_bom() {
+        assert_eq!(decode(b"\xFF\xFEa\x00.\x00b\x00", "list").unwrap(), "a.b");
+        assert_eq!(decode(b"\xEF\xBB\xBFa.b", "list").unwrap(), "a.b");
+        assert!(decode(b"\xFF\xFEa\x00.", "list").is_err());
+        assert!(decode(b"\xB0\xA1", "list").is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use std::path::PathBuf;
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// 자동화 도구가 실패 종류에 따라 분기할 수 있도록 결과 파일에 기록되는 안정적인 오류 코드
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    NotFound,
+    RateLimited,
+    NetworkTimeout,
+    Network,
+    HttpStatus,
+    InvalidExtensionId,
+    Io,
+    FixtureMissing,
+    SizeLimitExceeded,
+    InvalidArchive,
+    IdentityMismatch,
+    HashMismatch,
+    HookFailed,
+    Infected,
+    Vetoed,
+    PolicySkipped,
+    SourcePinned,
+    SignatureInvalid,
+    Other,
+}
+
+impl ErrorCode {
+    /// 결과 파일에 기록되는 것과 같은 이름 (예: `not_found`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::NetworkTimeout => "network_timeout",
+            ErrorCode::Network => "network",
+            ErrorCode::HttpStatus => "http_status",
+            ErrorCode::InvalidExtensionId => "invalid_extension_id",
+            ErrorCode::Io => "io",
+            ErrorCode::FixtureMissing => "fixture_missing",
+            ErrorCode::SizeLimitExceeded => "size_limit_exceeded",
+            ErrorCode::InvalidArchive => "invalid_archive",
+            ErrorCode::IdentityMismatch => "identity_mismatch",
+            ErrorCode::HashMismatch => "hash_mismatch",
+            ErrorCode::HookFailed => "hook_failed",
+            ErrorCode::Infected => "infected",
+            ErrorCode::Vetoed => "vetoed",
+            ErrorCode::PolicySkipped => "policy_skipped",
+            ErrorCode::SourcePinned => "source_pinned",
+            ErrorCode::SignatureInvalid => "signature_invalid",
+            ErrorCode::Other => "other",
+        }
+    }
+
+    /// 정책이나 설정에 따라 일부러 받지 않은 항목인지 (실패 목록에서는 빠짐)
+    pub fn is_skip(self) -> bool {
+        matches!(self, ErrorCode::Vetoed | ErrorCode::PolicySkipped | ErrorCode::SizeLimitExceeded)
+    }
+
+    /// 다시 시도하면 성공할 수 있는 실패인지 (`retry-failed`가 다시 받는 항목).
+    /// HTTP 상태 오류는 서버 오류(5xx)이거나 상태를 모를 때만 해당합니다.
+    pub fn is_retryable(self, status: Option<u16>) -> bool {
+        match self {
+            ErrorCode::HttpStatus => status.is_none_or(|status| status >= 500),
+            code => matches!(
+                code,
+                ErrorCode::RateLimited
+                    | ErrorCode::NetworkTimeout
+                    | ErrorCode::Network
+                    | ErrorCode::InvalidArchive
+                    | ErrorCode::IdentityMismatch
+                    | ErrorCode::Io
+                    | ErrorCode::FixtureMissing
+                    | ErrorCode::HookFailed
+                    | ErrorCode::SourcePinned
+                    | ErrorCode::Other
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum VsixError {
+    #[error("확장 프로그램을 찾을 수 없습니다: {url}")]
+    NotFound { url: String },
+
+    #[error("요청 한도를 초과했습니다: {url}")]
+    RateLimited { url: String },
+
+    #[error("요청 시간이 초과되었습니다: {url}")]
+    NetworkTimeout { url: String },
+
+    #[error("네트워크 오류: {url}")]
+    Network {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("서버 오류: {status} ({url})")]
+    HttpStatus { status: StatusCode, url: String },
+
+    #[error("유효하지 않은 확장 프로그램 ID 형식: {0}")]
+    InvalidExtensionId(String),
+
+    #[error("최대 다운로드 크기를 초과했습니다: {size} > {limit} 바이트 ({url})")]
+    SizeLimitExceeded { url: String, size: u64, limit: u64 },
+
+    #[error("VSIX 파일이 아닌 응답을 받았습니다 (Content-Type: {content_type}): {message}")]
+    InvalidArchive { url: String, content_type: String, message: String },
+
+    #[error("받은 VSIX가 요청한 확장 프로그램과 다릅니다: 요청 {expected}, 실제 {actual}")]
+    IdentityMismatch { expected: String, actual: String },
+
+    #[error("같은 버전의 VSIX 내용이 잠금 파일에 기록된 해시와 다릅니다: {id} (기록 {expected}, 실제 {actual})")]
+    HashMismatch { id: String, expected: String, actual: String },
+
+    #[error("{message}")]
+    HookFailed { message: String },
+
+    #[error("악성 코드 검사에서 발견되었습니다: {id} ({signature}, {scanner})")]
+    Infected { id: String, scanner: String, signature: String },
+
+    #[error("before_download 훅이 다운로드를 거부했습니다: {reason}")]
+    Vetoed { reason: String },
+
+    #[error("게시자 정책에 따라 받지 않습니다: {reason}")]
+    PolicySkipped { reason: String },
+
+    #[error("source: openvsx로 고정되어 있지만 Open VSX에서 받을 수 없습니다: {id}")]
+    SourcePinned { id: String },
+
+    #[error("Marketplace 서명이 올바르지 않습니다: {id} ({reason})")]
+    SignatureInvalid { id: String, reason: String },
+
+    #[error("재생할 기록된 응답이 없습니다: {url}")]
+    FixtureMissing { url: String },
+
+    #[error("파일 입출력 오류: {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+impl VsixError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            VsixError::NotFound { .. } => ErrorCode::NotFound,
+            VsixError::RateLimited { .. } => ErrorCode::RateLimited,
+            VsixError::NetworkTimeout { .. } => ErrorCode::NetworkTimeout,
+            VsixError::Network { .. } => ErrorCode::Network,
+            VsixError::HttpStatus { .. } => ErrorCode::HttpStatus,
+            VsixError::InvalidExtensionId(_) => ErrorCode::InvalidExtensionId,
+            VsixError::Io { .. } => ErrorCode::Io,
+            VsixError::FixtureMissing { .. } => ErrorCode::FixtureMissing,
+            VsixError::SizeLimitExceeded { .. } => ErrorCode::SizeLimitExceeded,
+            VsixError::InvalidArchive { .. } => ErrorCode::InvalidArchive,
+            VsixError::IdentityMismatch { .. } => ErrorCode::IdentityMismatch,
+            VsixError::HashMismatch { .. } => ErrorCode::HashMismatch,
+            VsixError::HookFailed { .. } => ErrorCode::HookFailed,
+            VsixError::Infected { .. } => ErrorCode::Infected,
+            VsixError::Vetoed { .. } => ErrorCode::Vetoed,
+            VsixError::PolicySkipped { .. } => ErrorCode::PolicySkipped,
+            VsixError::SourcePinned { .. } => ErrorCode::SourcePinned,
+            VsixError::SignatureInvalid { .. } => ErrorCode::SignatureInvalid,
+        }
+    }
+
+    /// 오류가 된 HTTP 응답 상태 코드 (요청 자체가 실패했거나 HTTP와 무관한 오류면 None)
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            VsixError::NotFound { .. } => Some(StatusCode::NOT_FOUND.as_u16()),
+            VsixError::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS.as_u16()),
+            VsixError::HttpStatus { status, .. } => Some(status.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// 성공이 아닌 HTTP 응답 상태를 오류 종류로 분류합니다.
+    pub fn from_status(status: StatusCode, url: &str) -> Self {
+        let url = url.to_string();
+        match status {
+            StatusCode::NOT_FOUND => VsixError::NotFound { url },
+            StatusCode::TOO_MANY_REQUESTS => VsixError::RateLimited { url },
+            status => VsixError::HttpStatus { status, url },
+        }
+    }
+
+    /// 요청 자체가 실패한 경우를 오류 종류로 분류합니다.
+    pub fn from_request(source: reqwest::Error, url: &str) -> Self {
+        let url = url.to_string();
+        if source.is_timeout() {
+            VsixError::NetworkTimeout { url }
+        } else {
+            VsixError::Network { url, source }
+        }
+    }
+}
+
+/// anyhow 오류 체인에서 VsixError를 찾아 오류 코드를 반환합니다.
+pub fn error_code(err: &anyhow::Error) -> ErrorCode {
+    err.chain()
+        .find_map(|e| e.downcast_ref::<VsixError>())
+        .map(VsixError::code)
+        .unwrap_or(ErrorCode::Other)
+}
+
+/// anyhow 오류 체인에서 VsixError를 찾아 HTTP 응답 상태 코드를 반환합니다.
+pub fn http_status(err: &anyhow::Error) -> Option<u16> {
+    err.chain()
+        .find_map(|e| e.downcast_ref::<VsixError>())
+        .and_then(VsixError::http_status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialized_codes_match_as_str() {
+        for code in [ErrorCode::NotFound, ErrorCode::HttpStatus, ErrorCode::SourcePinned, ErrorCode::SignatureInvalid, ErrorCode::Other] {
+            assert_eq!(serde_json::to_value(code).unwrap(), code.as_str());
+        }
+    }
+
+    #[test]
+    fn retries_only_transient_failures() {
+        assert!(ErrorCode::HttpStatus.is_retryable(Some(503)));
+        assert!(ErrorCode::HttpStatus.is_retryable(None));
+        assert!(!ErrorCode::HttpStatus.is_retryable(Some(403)));
+        assert!(ErrorCode::NetworkTimeout.is_retryable(None));
+        assert!(!ErrorCode::NotFound.is_retryable(Some(404)));
+        assert!(!ErrorCode::Vetoed.is_retryable(None));
+        assert!(ErrorCode::PolicySkipped.is_skip());
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::filename;
+use crate::output;
+use crate::paths;
+use crate::version;
+use crate::vsix;
+use crate::CodeServerGalleryArgs;
+
+/// 갤러리 서비스가 `POST <serviceUrl>/extensionquery`로 받는 응답을 담은 색인 파일
+const QUERY_FILE: &str = "extensionquery";
+
+/// 확장 프로그램별 파일을 두는 디렉토리 (`files/<게시자>/<이름>/<버전>/<플랫폼>/<자산 종류>`)
+const FILES_DIR: &str = "files";
+
+/// openvscode-server가 읽는 제품 설정 파일 (`extensionsGallery`만 담음)
+const PRODUCT_FILE: &str = "product.json";
+
+/// VSIX 파일 자체의 자산 종류
+const VSIX_ASSET: &str = "Microsoft.VisualStudio.Services.VSIXPackage";
+
+/// 플랫폼별 빌드가 아닌 VSIX를 두는 디렉토리 이름
+const UNIVERSAL: &str = "universal";
+
+/// code-server의 `EXTENSIONS_GALLERY`와 product.json의 `extensionsGallery`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GalleryConfig {
+    service_url: String,
+    item_url: String,
+}
+
+/// 색인에 넣을 VSIX 하나
+struct Package {
+    publisher: String,
+    name: String,
+    version: String,
+    target_platform: Option<String>,
+    path: PathBuf,
+    manifest: Value,
+    last_updated: String,
+}
+
+impl Package {
+    fn id(&self) -> String {
+        format!("{}.{}", self.publisher, self.name)
+    }
+
+    /// 갤러리 디렉토리 안에서 이 VSIX의 자산을 두는 상대 경로
+    fn relative_dir(&self) -> String {
+        [
+            FILES_DIR,
+            &filename::sanitize_file_name(&self.publisher),
+            &filename::sanitize_file_name(&self.name),
+            &filename::sanitize_file_name(&self.version),
+            &filename::sanitize_file_name(self.target_platform.as_deref().unwrap_or(UNIVERSAL)),
+        ].join("/")
+    }
+}
+
+/// 다운로드 디렉토리의 VSIX를 code-server와 openvscode-server가 갤러리로 쓸 수 있는 정적 파일로 내보냅니다.
+/// 확장 프로그램 파일과 자산(매니페스트, README, 아이콘 등)을 `files/` 아래에 배치하고,
+/// 갤러리 검색 응답 형식의 색인(`extensionquery`)과 갤러리 설정(`product.json`)을 기록합니다.
+pub fn code_server_gallery(args: &CodeServerGalleryArgs) -> Result<()> {
+    let base_url = args.base_url.trim_end_matches('/');
+    if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+        return Err(anyhow!("--base-url은 http:// 또는 https://로 시작해야 합니다: {}", args.base_url));
+    }
+    let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+    if paths::absolute(&cwd, &args.dest).starts_with(paths::absolute(&cwd, &args.output_dir)) {
+        return Err(anyhow!("갤러리 디렉토리 {}는 다운로드 디렉토리 밖에 있어야 합니다", args.dest.display()));
+    }
+
+    let packages = read_packages(&args.output_dir)?;
+    if packages.is_empty() {
+        return Err(anyhow!("{} 디렉토리에 VSIX 파일이 없습니다", args.output_dir.display()));
+    }
+
+    // 이전에 내보낸 파일은 지우고 다시 배치 (이 명령이 만든 갤러리 디렉토리에서만)
+    let files_dir = args.dest.join(FILES_DIR);
+    if args.dest.join(QUERY_FILE).exists() && files_dir.exists() {
+        fs::remove_dir_all(&files_dir)
+            .with_context(|| format!("Failed to remove {}", files_dir.display()))?;
+    }
+
+    let mut by_id: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+    let mut latest: BTreeMap<String, &Package> = BTreeMap::new();
+    for package in &packages {
+        let asset_uri = format!("{}/{}", base_url, package.relative_dir());
+        let files = place_assets(package, &args.dest.join(package.relative_dir()), &asset_uri)?;
+        by_id.entry(package.id().to_lowercase()).or_default().push(version_entry(package, &asset_uri, files));
+        latest.entry(package.id().to_lowercase())
+            .and_modify(|current| {
+                if version::compare_versions(&package.version, &current.version).is_gt() {
+                    *current = package;
+                }
+            })
+            .or_insert(package);
+    }
+
+    let extensions: Vec<Value> = by_id.into_iter()
+        .map(|(id, versions)| extension_entry(latest[&id], versions))
+        .collect();
+    let count = extensions.len();
+    let query = json!({
+        "results": [{
+            "extensions": extensions,
+            "pagingToken": null,
+            "resultMetadata": [{
+                "metadataType": "ResultCount",
+                "metadataItems": [{ "name": "TotalCount", "count": count }],
+            }],
+        }],
+    });
+    write_json(&args.dest.join(QUERY_FILE), &query)?;
+
+    let config = GalleryConfig {
+        service_url: base_url.to_string(),
+        item_url: format!("{}/item", base_url),
+    };
+    write_json(&args.dest.join(PRODUCT_FILE), &json!({ "extensionsGallery": config }))?;
+
+    println!(
+        "{} {} ({}개 확장 프로그램, VSIX {}개)",
+        "갤러리를 내보냈습니다:".green(),
+        args.dest.display(),
+        count,
+        packages.len()
+    );
+    println!("{}", "code-server:".blue());
+    println!("  EXTENSIONS_GALLERY='{}' code-server", serde_json::to_string(&config).with_context(|| "Failed to serialize gallery config")?);
+    println!("{} {}", "openvscode-server: product.json에 합칠 설정:".blue(), args.dest.join(PRODUCT_FILE).display());
+    println!("{}", format!("갤러리 검색은 POST {}/{} 요청으로 오므로 웹 서버가 POST에도 이 파일을 돌려주도록 설정하세요.", base_url, QUERY_FILE).yellow());
+    Ok(())
+}
+
+/// 다운로드 디렉토리의 VSIX를 읽어 ID, 버전, 플랫폼 순으로 정렬합니다. 같은 빌드가 여러 파일에 있으면 하나만 씁니다.
+fn read_packages(dir: &Path) -> Result<Vec<Package>> {
+    let mut packages: Vec<Package> = Vec::new();
+    for local in vsix::scan_directory(dir)? {
+        let identity = match vsix::read_identity(&local.path) {
+            Ok(identity) => identity,
+            Err(e) => {
+                println!("{} {}: {}", "건너뜀".yellow(), local.path.display(), e);
+                continue;
+            },
+        };
+        let manifest: Value = serde_json::from_str(&vsix::read_text_entry(&local.path, vsix::PACKAGE_JSON_PATH)?)
+            .with_context(|| format!("Failed to parse {} in {}", vsix::PACKAGE_JSON_PATH, local.path.display()))?;
+        let last_updated = fs::metadata(&local.path)
+            .and_then(|metadata| metadata.modified())
+            .map(|modified| DateTime::<Utc>::from(modified).to_rfc3339())
+            .unwrap_or_else(|_| Utc::now().to_rfc3339());
+
+        let package = Package {
+            publisher: identity.publisher,
+            name: identity.name,
+            version: identity.version,
+            target_platform: identity.target_platform,
+            path: local.path,
+            manifest,
+            last_updated,
+        };
+        let duplicate = packages.iter().any(|p| {
+            p.id().eq_ignore_ascii_case(&package.id()) && p.version == package.version && p.target_platform == package.target_platform
+        });
+        if duplicate {
+            println!("{} {}: 같은 버전과 플랫폼의 VSIX가 이미 있습니다", "건너뜀".yellow(), package.path.display());
+            continue;
+        }
+        packages.push(package);
+    }
+
+    packages.sort_by(|a, b| {
+        a.id().to_lowercase().cmp(&b.id().to_lowercase())
+            .then_with(|| version::compare_versions(&b.version, &a.version))
+            .then_with(|| a.target_platform.cmp(&b.target_platform))
+    });
+    Ok(packages)
+}
+
+/// VSIX와 `extension.vsixmanifest`에 선언된 자산을 자산 종류 이름의 파일로 배치하고 색인의 `files` 항목을 만듭니다.
+fn place_assets(package: &Package, dir: &Path, asset_uri: &str) -> Result<Vec<Value>> {
+    fs::create_dir_all(filename::long_path(dir))
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    link_or_copy(&package.path, &dir.join(VSIX_ASSET))?;
+    let mut files = vec![json!({ "assetType": VSIX_ASSET, "source": format!("{}/{}", asset_uri, VSIX_ASSET) })];
+
+    for (asset_type, entry) in vsix::read_assets(&package.path).unwrap_or_default() {
+        if asset_type == VSIX_ASSET || files.iter().any(|f| f["assetType"] == asset_type.as_str()) {
+            continue;
+        }
+        // 선언만 되어 있고 VSIX에 없는 자산은 건너뜀
+        let Ok(content) = vsix::read_entry(&package.path, &entry) else {
+            continue;
+        };
+        let path = dir.join(filename::sanitize_file_name(&asset_type));
+        fs::write(filename::long_path(&path), content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        files.push(json!({ "assetType": asset_type, "source": format!("{}/{}", asset_uri, asset_type) }));
+    }
+
+    Ok(files)
+}
+
+/// 미러의 파일은 원자적으로 교체되므로 하드 링크로 공간을 아끼고, 다른 파일 시스템이면 복사합니다.
+fn link_or_copy(source: &Path, dest: &Path) -> Result<()> {
+    let (source_path, dest_path) = (filename::long_path(source), filename::long_path(dest));
+    if fs::hard_link(&source_path, &dest_path).is_ok() {
+        return Ok(());
+    }
+    fs::copy(&source_path, &dest_path)
+        .with_context(|| format!("Failed to copy {} to {}", source.display(), dest.display()))?;
+    Ok(())
+}
+
+/// 색인의 `versions` 항목 하나
+fn version_entry(package: &Package, asset_uri: &str, files: Vec<Value>) -> Value {
+    let manifest = &package.manifest;
+    let mut properties = Vec::new();
+    if let Some(engine) = manifest.pointer("/engines/vscode").and_then(Value::as_str) {
+        properties.push(json!({ "key": "Microsoft.VisualStudio.Code.Engine", "value": engine }));
+    }
+    for (key, field) in [
+        ("Microsoft.VisualStudio.Code.ExtensionDependencies", "extensionDependencies"),
+        ("Microsoft.VisualStudio.Code.ExtensionPack", "extensionPack"),
+        ("Microsoft.VisualStudio.Code.ExtensionKind", "extensionKind"),
+    ] {
+        let values = string_list(manifest, field);
+        if !values.is_empty() {
+            properties.push(json!({ "key": key, "value": values.join(",") }));
+        }
+    }
+    if manifest.get("preview").and_then(Value::as_bool) == Some(true) {
+        properties.push(json!({ "key": "Microsoft.VisualStudio.Code.PreRelease", "value": "true" }));
+    }
+
+    let mut entry = json!({
+        "version": package.version,
+        "flags": "validated",
+        "lastUpdated": package.last_updated,
+        "assetUri": asset_uri,
+        "fallbackAssetUri": asset_uri,
+        "files": files,
+        "properties": properties,
+    });
+    if let Some(target) = &package.target_platform {
+        entry["targetPlatform"] = json!(target);
+    }
+    entry
+}
+
+/// 색인의 `extensions` 항목 하나. 표시 정보는 최신 버전의 매니페스트에서 가져옵니다.
+fn extension_entry(latest: &Package, versions: Vec<Value>) -> Value {
+    let manifest = &latest.manifest;
+    let display_name = manifest.get("displayName").and_then(Value::as_str).unwrap_or(&latest.name);
+    let publisher_display_name = manifest.pointer("/author/name").and_then(Value::as_str).unwrap_or(&latest.publisher);
+
+    json!({
+        "extensionId": stable_uuid(&latest.id()),
+        "extensionName": latest.name,
+        "displayName": display_name,
+        "shortDescription": manifest.get("description").and_then(Value::as_str).unwrap_or_default(),
+        "flags": "validated, public",
+        "lastUpdated": latest.last_updated,
+        "publishedDate": latest.last_updated,
+        "releaseDate": latest.last_updated,
+        "publisher": {
+            "publisherId": stable_uuid(&latest.publisher),
+            "publisherName": latest.publisher,
+            "displayName": publisher_display_name,
+        },
+        "categories": string_list(manifest, "categories"),
+        "tags": string_list(manifest, "keywords"),
+        "installationTargets": [{ "target": "Microsoft.VisualStudio.Code" }],
+        "statistics": [],
+        "deploymentType": 0,
+        "versions": versions,
+    })
+}
+
+/// 매니페스트의 문자열 배열 필드 (없거나 배열이 아니면 빈 목록)
+fn string_list(manifest: &Value, field: &str) -> Vec<String> {
+    manifest.get(field)
+        .and_then(Value::as_array)
+        .map(|values| values.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// VSIX에는 갤러리 UUID가 없으므로 ID(대소문자 무시)에서 항상 같은 UUID 형식 값을 만듭니다.
+fn stable_uuid(id: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(id.to_lowercase().as_bytes()));
+    format!("{}-{}-{}-{}-{}", &digest[..8], &digest[8..12], &digest[12..16], &digest[16..20], &digest[20..32])
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<()> {
+    let content = serde_json::to_string_pretty(value)
+        .with_context(|| format!("Failed to serialize {}", path.display()))?;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(filename::long_path(dir))
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    output::write_atomically(path, content.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,121 @@
+use std::path::{Path, PathBuf};
+
+/// Windows에서 파일 이름에 쓸 수 없는 문자
+const INVALID_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Windows에서 확장자와 관계없이 파일 이름으로 쓸 수 없는 장치 이름
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 생성하는 파일 이름 한 개의 최대 길이(바이트).
+/// 번들을 Windows의 깊은 경로에 풀어도 MAX_PATH(260자)를 넘지 않도록 여유를 둠
+const MAX_FILE_NAME_LEN: usize = 120;
+
+/// Windows의 MAX_PATH. 이보다 긴 경로는 `\\?\` 접두사를 붙여야 열 수 있음
+#[cfg_attr(not(windows), allow(dead_code))]
+const MAX_PATH: usize = 260;
+
+/// 어느 플랫폼에서도 그대로 쓸 수 있는 파일 이름으로 바꿉니다.
+///
+/// - Windows에서 쓸 수 없는 문자와 제어 문자는 `_`로 바꿈
+/// - 끝의 점과 공백은 제거 (Windows가 조용히 잘라내 이름이 달라짐)
+/// - `CON`, `NUL` 같은 장치 이름은 앞에 `_`를 붙임
+/// - 너무 긴 이름은 확장자를 유지한 채 자름
+pub fn sanitize_file_name(name: &str) -> String {
+    let replaced: String = name.chars()
+        .map(|c| if INVALID_CHARS.contains(&c) || c.is_control() { '_' } else { c })
+        .collect();
+
+    let trimmed = replaced.trim_end_matches(['.', ' ']);
+    let mut sanitized = if trimmed.is_empty() { "_".to_string() } else { trimmed.to_string() };
+
+    let stem = sanitized.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        sanitized.insert(0, '_');
+    }
+
+    truncate_keeping_extension(&sanitized, MAX_FILE_NAME_LEN)
+}
+
+/// 확장 프로그램 ID로 VSIX 파일 이름을 만듭니다 (`publisher.name` → `publisher-name.vsix`).
+pub fn vsix_file_name(extension_id: &str) -> String {
+    sanitize_file_name(&format!("{}.vsix", extension_id.replace('.', "-")))
+}
+
+fn truncate_keeping_extension(name: &str, max_len: usize) -> String {
+    if name.len() <= max_len {
+        return name.to_string();
+    }
+
+    let (stem, extension) = match name.rfind('.') {
+        Some(index) if index > 0 && name.len() - index <= 16 => name.split_at(index),
+        _ => (name, ""),
+    };
+
+    let mut end = max_len.saturating_sub(extension.len());
+    while !stem.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}{}", &stem[..end], extension)
+}
+
+/// Windows에서 MAX_PATH를 넘는 경로를 열 수 있도록 `\\?\` 접두사를 붙인 절대 경로로 바꿉니다.
+/// 다른 플랫폼이나 짧은 경로는 그대로 반환합니다.
+pub fn long_path(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        let text = absolute.as_os_str().to_string_lossy();
+        if text.len() >= MAX_PATH && !text.starts_with(r"\\?\") {
+            return PathBuf::from(format!(r"\\?\{}", text));
+        }
+    }
+
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_reserved_device_names() {
+        assert_eq!(sanitize_file_name("con"), "_con");
+        assert_eq!(sanitize_file_name("NUL.txt"), "_NUL.txt");
+        assert_eq!(sanitize_file_name("com1.tar.gz"), "_com1.tar.gz");
+        assert_eq!(sanitize_file_name("console.log"), "console.log");
+    }
+
+    #[test]
+    fn trims_trailing_dots_and_spaces() {
+        assert_eq!(sanitize_file_name("readme. ."), "readme");
+        assert_eq!(sanitize_file_name("..."), "_");
+        assert_eq!(sanitize_file_name("a<b>:c?.md"), "a_b__c_.md");
+    }
+
+    #[test]
+    fn truncates_multibyte_names_on_char_boundary() {
+        let name = format!("{}.vsix", "확".repeat(50));
+        let truncated = sanitize_file_name(&name);
+        assert!(truncated.len() <= MAX_FILE_NAME_LEN);
+        assert!(truncated.ends_with(".vsix"));
+        assert_eq!(truncated, format!("{}.vsix", "확".repeat(38)));
+    }
+
+    #[test]
+    fn keeps_extension_when_truncating() {
+        assert_eq!(truncate_keeping_extension("abcdefgh.vsix", 10), "abcde.vsix");
+        assert_eq!(truncate_keeping_extension("short.vsix", 10), "short.vsix");
+        // 확장자로 보기에 너무 긴 꼬리는 이름의 일부로 보고 그냥 자름
+        assert_eq!(truncate_keeping_extension(&format!("a.{}", "b".repeat(20)), 10), "a.bbbbbbbb");
+    }
+
+    #[test]
+    fn vsix_file_name_replaces_dots() {
+        assert_eq!(vsix_file_name("golang.go"), "golang-go.vsix");
+    }
+}
@@ -0,0 +1,130 @@
+use colored::Colorize;
+use futures_util::StreamExt;
+use serde::Deserialize;
+
+use crate::error::{self, ErrorCode, VsixError};
+use crate::http::HttpClient;
+use crate::marketplace;
+use crate::{open_vsx_url, Extension, SyncArgs};
+
+/// 필터 판단에 쓰는 확장 프로그램 메타데이터
+#[derive(Debug, Default)]
+struct Metadata {
+    categories: Vec<String>,
+    /// Marketplace 태그 또는 Open VSX 키워드
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenVsxMetadata {
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+fn is_active(args: &SyncArgs) -> bool {
+    !args.category.is_empty()
+        || !args.exclude_category.is_empty()
+        || !args.keyword.is_empty()
+        || !args.exclude_keyword.is_empty()
+}
+
+/// 카테고리와 키워드 조건(`--category`, `--exclude-category`, `--keyword`, `--exclude-keyword`)에 맞는 확장 프로그램만 남깁니다.
+/// 메타데이터는 Marketplace에서 조회하고, Marketplace에 없으면 Open VSX에서 조회합니다.
+/// 조회에 실패한 항목은 카테고리와 키워드가 없는 것으로 보고 판단합니다.
+pub async fn apply(client: &HttpClient, extensions: Vec<Extension>, args: &SyncArgs) -> Vec<Extension> {
+    if !is_active(args) {
+        return extensions;
+    }
+
+    println!("{}", "카테고리와 키워드 조건을 확인하는 중...".blue());
+
+    let metadata: Vec<Metadata> = futures_util::stream::iter(&extensions)
+        .map(|extension| fetch_metadata(client, extension))
+        .buffered(args.concurrency.max(1))
+        .collect()
+        .await;
+
+    let total = extensions.len();
+    let mut kept = Vec::new();
+    for (extension, metadata) in extensions.into_iter().zip(metadata) {
+        match rejection(&metadata, args) {
+            Some(reason) => println!("{} {}: {}", "필터 제외".yellow(), extension.id, reason),
+            None => kept.push(extension),
+        }
+    }
+
+    println!(
+        "{}",
+        format!("조건에 맞는 {}개 확장 프로그램을 처리합니다 ({}개 제외).", kept.len(), total - kept.len()).blue()
+    );
+
+    kept
+}
+
+async fn fetch_metadata(client: &HttpClient, extension: &Extension) -> Metadata {
+    let error = match marketplace::query_metadata(client, &extension.id).await {
+        Ok(gallery) => return Metadata { categories: gallery.categories, tags: gallery.tags },
+        Err(e) => e,
+    };
+
+    // Open VSX에만 있는 확장 프로그램
+    if error::error_code(&error) == ErrorCode::NotFound {
+        if let Ok(metadata) = fetch_open_vsx_metadata(client, extension).await {
+            return metadata;
+        }
+    }
+
+    println!("{} {}: 메타데이터를 가져오지 못했습니다 ({:#})", "경고:".yellow(), extension.id, error);
+    Metadata::default()
+}
+
+async fn fetch_open_vsx_metadata(client: &HttpClient, extension: &Extension) -> anyhow::Result<Metadata> {
+    let url = open_vsx_url(extension);
+    let response = client.get(&url).await?;
+    if !response.status.is_success() {
+        return Err(VsixError::from_status(response.status, &url).into());
+    }
+
+    let metadata: OpenVsxMetadata = response.json().await?;
+    Ok(Metadata { categories: metadata.categories, tags: metadata.tags })
+}
+
+/// 조건에 맞지 않으면 제외 이유
+fn rejection(metadata: &Metadata, args: &SyncArgs) -> Option<String> {
+    if !args.category.is_empty() && !matches_any(&metadata.categories, &args.category) {
+        return Some(format!("카테고리 {} 중 어디에도 속하지 않음 ({})", args.category.join(", "), describe(&metadata.categories)));
+    }
+
+    if let Some(category) = first_match(&metadata.categories, &args.exclude_category) {
+        return Some(format!("제외한 카테고리 {}에 속함", category));
+    }
+
+    if !args.keyword.is_empty() && !matches_any(&metadata.tags, &args.keyword) {
+        return Some(format!("키워드 {} 중 어느 것도 없음", args.keyword.join(", ")));
+    }
+
+    if let Some(keyword) = first_match(&metadata.tags, &args.exclude_keyword) {
+        return Some(format!("제외한 키워드 {}가 있음", keyword));
+    }
+
+    None
+}
+
+fn matches_any(values: &[String], wanted: &[String]) -> bool {
+    first_match(values, wanted).is_some()
+}
+
+/// 대소문자를 무시하고 `wanted`와 처음으로 일치하는 값
+fn first_match<'a>(values: &'a [String], wanted: &[String]) -> Option<&'a String> {
+    values.iter().find(|value| wanted.iter().any(|w| w.eq_ignore_ascii_case(value)))
+}
+
+fn describe(values: &[String]) -> String {
+    if values.is_empty() {
+        "-".to_string()
+    } else {
+        values.join(", ")
+    }
+}
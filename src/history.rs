@@ -0,0 +1,272 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::run_meta::RunMetadata;
+use crate::size;
+use crate::StatsArgs;
+
+/// 동기화 실행 기록 파일 (현재 디렉토리, 한 줄에 실행 하나인 JSON Lines)
+pub const HISTORY_FILE: &str = "sync-history.jsonl";
+
+/// 동기화 실행 하나의 기록
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub run: RunMetadata,
+    /// 실행이 끝난 시각
+    pub finished: String,
+    pub duration_ms: u64,
+    /// Marketplace에서 실제로 받은 바이트 수
+    #[serde(default)]
+    pub bytes_downloaded: u64,
+    #[serde(default)]
+    pub extensions: Vec<RunEntry>,
+}
+
+/// 실행에서 확인하거나 받은 확장 프로그램 하나
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunEntry {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub failed: bool,
+}
+
+impl RunRecord {
+    /// 실행 정보의 시작 시각부터 지금까지를 실행 시간으로 기록합니다.
+    pub fn finish(run: &RunMetadata, bytes_downloaded: u64, extensions: Vec<RunEntry>) -> Self {
+        let now = Utc::now();
+        let duration_ms = DateTime::parse_from_rfc3339(&run.timestamp)
+            .map(|started| (now - started.with_timezone(&Utc)).num_milliseconds().max(0) as u64)
+            .unwrap_or(0);
+
+        RunRecord {
+            run: run.clone(),
+            finished: now.to_rfc3339(),
+            duration_ms,
+            bytes_downloaded,
+            extensions,
+        }
+    }
+}
+
+/// 실행 기록 파일 끝에 한 줄을 덧붙입니다.
+pub fn append(record: &RunRecord) -> Result<()> {
+    let line = serde_json::to_string(record)
+        .with_context(|| "Failed to serialize run record")?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(HISTORY_FILE)
+        .with_context(|| format!("Failed to open {}", HISTORY_FILE))?;
+
+    writeln!(file, "{}", line)
+        .with_context(|| format!("Failed to write {}", HISTORY_FILE))
+}
+
+/// 실행 기록을 시작 시각 순으로 읽습니다.
+pub fn load(path: &Path) -> Result<Vec<RunRecord>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("실행 기록을 읽을 수 없습니다: {}", path.display()))?;
+
+    let mut records = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: RunRecord = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse {} line {}", path.display(), number + 1))?;
+        records.push(record);
+    }
+
+    records.sort_by(|a, b| a.run.timestamp.cmp(&b.run.timestamp));
+    Ok(records)
+}
+
+/// `stats` 명령 출력 (--json)
+#[derive(Debug, Serialize)]
+struct Stats {
+    runs: usize,
+    first_run: Option<String>,
+    last_run: Option<String>,
+    average_duration_ms: u64,
+    longest_duration_ms: u64,
+    /// 월(YYYY-MM)별 다운로드량
+    monthly: Vec<MonthlyStats>,
+    /// 버전이 바뀐 횟수가 많은 순
+    most_updated: Vec<UpdateStats>,
+    /// 실패한 실행 수가 많은 순
+    most_failed: Vec<FailureStats>,
+}
+
+#[derive(Debug, Serialize)]
+struct MonthlyStats {
+    month: String,
+    runs: usize,
+    bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct UpdateStats {
+    id: String,
+    updates: usize,
+    first_version: String,
+    last_version: String,
+}
+
+#[derive(Debug, Serialize)]
+struct FailureStats {
+    id: String,
+    failures: usize,
+    runs: usize,
+}
+
+/// 실행 기록으로 확장 프로그램별 업데이트 빈도, 평균 실행 시간, 월별 다운로드량, 실패가 잦은 확장 프로그램을 출력합니다.
+pub fn stats(args: &StatsArgs) -> Result<()> {
+    let records = load(&args.history)?;
+    let stats = aggregate(&records, args.top);
+
+    if args.json {
+        let output = serde_json::to_string_pretty(&stats)
+            .with_context(|| "Failed to serialize stats")?;
+        println!("{}", output);
+        return Ok(());
+    }
+
+    print(&stats);
+    Ok(())
+}
+
+fn aggregate(records: &[RunRecord], top: usize) -> Stats {
+    let durations: Vec<u64> = records.iter().map(|r| r.duration_ms).collect();
+    let average_duration_ms = match durations.len() {
+        0 => 0,
+        n => durations.iter().sum::<u64>() / n as u64,
+    };
+
+    let mut monthly: BTreeMap<String, MonthlyStats> = BTreeMap::new();
+    for record in records {
+        let month = record.run.timestamp.get(..7).unwrap_or("-").to_string();
+        let entry = monthly.entry(month.clone())
+            .or_insert(MonthlyStats { month, runs: 0, bytes: 0 });
+        entry.runs += 1;
+        entry.bytes += record.bytes_downloaded;
+    }
+
+    // ID(소문자)별 버전 변경 횟수와 실패 횟수
+    let mut updates: BTreeMap<String, UpdateStats> = BTreeMap::new();
+    let mut failures: BTreeMap<String, FailureStats> = BTreeMap::new();
+    for record in records {
+        for entry in &record.extensions {
+            let key = entry.id.to_lowercase();
+
+            let failure = failures.entry(key.clone())
+                .or_insert(FailureStats { id: entry.id.clone(), failures: 0, runs: 0 });
+            failure.runs += 1;
+            if entry.failed {
+                failure.failures += 1;
+                continue;
+            }
+
+            let Some(version) = &entry.version else { continue };
+            let update = updates.entry(key)
+                .or_insert(UpdateStats {
+                    id: entry.id.clone(),
+                    updates: 0,
+                    first_version: version.clone(),
+                    last_version: version.clone(),
+                });
+            if &update.last_version != version {
+                update.updates += 1;
+                update.last_version = version.clone();
+            }
+        }
+    }
+
+    let mut most_updated: Vec<UpdateStats> = updates.into_values().filter(|u| u.updates > 0).collect();
+    most_updated.sort_by_key(|u| std::cmp::Reverse(u.updates));
+    most_updated.truncate(top);
+
+    let mut most_failed: Vec<FailureStats> = failures.into_values().filter(|f| f.failures > 0).collect();
+    most_failed.sort_by(|a, b| b.failures.cmp(&a.failures).then(b.runs.cmp(&a.runs)));
+    most_failed.truncate(top);
+
+    Stats {
+        runs: records.len(),
+        first_run: records.first().map(|r| r.run.timestamp.clone()),
+        last_run: records.last().map(|r| r.run.timestamp.clone()),
+        average_duration_ms,
+        longest_duration_ms: durations.iter().copied().max().unwrap_or(0),
+        monthly: monthly.into_values().collect(),
+        most_updated,
+        most_failed,
+    }
+}
+
+fn print(stats: &Stats) {
+    if stats.runs == 0 {
+        println!("{}", "기록된 실행이 없습니다.".yellow());
+        return;
+    }
+
+    println!(
+        "{}",
+        format!("실행 {}회 ({} ~ {})",
+            stats.runs,
+            stats.first_run.as_deref().unwrap_or("-"),
+            stats.last_run.as_deref().unwrap_or("-")
+        ).blue()
+    );
+    println!(
+        "평균 실행 시간: {:.1}초 (가장 긴 실행 {:.1}초)",
+        stats.average_duration_ms as f64 / 1000.0,
+        stats.longest_duration_ms as f64 / 1000.0
+    );
+
+    println!("\n{}", "월별 다운로드량:".blue());
+    let mut cumulative = 0;
+    for month in &stats.monthly {
+        cumulative += month.bytes;
+        println!(
+            "  {}  {:>10}  (누적 {:>10}, 실행 {}회)",
+            month.month,
+            size::format_size(month.bytes),
+            size::format_size(cumulative),
+            month.runs
+        );
+    }
+
+    println!("\n{}", "업데이트가 잦은 확장 프로그램:".blue());
+    if stats.most_updated.is_empty() {
+        println!("  -");
+    }
+    let id_width = stats.most_updated.iter().map(|u| u.id.len()).max().unwrap_or(0);
+    for update in &stats.most_updated {
+        println!(
+            "  {:<id_width$}  {}회 ({} -> {})",
+            update.id, update.updates, update.first_version, update.last_version,
+            id_width = id_width
+        );
+    }
+
+    println!("\n{}", "실패가 잦은 확장 프로그램:".blue());
+    if stats.most_failed.is_empty() {
+        println!("  -");
+    }
+    let id_width = stats.most_failed.iter().map(|f| f.id.len()).max().unwrap_or(0);
+    for failure in &stats.most_failed {
+        println!(
+            "  {:<id_width$}  {}회 실행 중 {}회 실패",
+            failure.id, failure.runs, failure.failures,
+            id_width = id_width
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::process::Stdio;
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::error::VsixError;
+
+/// 설정 파일의 `hooks`에 정의하는 외부 명령 (인자의 `{이름}`은 실행 시점의 값으로 바뀜)
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    /// 다운로드 정보를 정한 뒤 받기 전에 실행 (`{id}`, `{version}`, `{publisher}`, `{size}`, `{license}`,
+    /// 표준 입력으로 같은 정보의 JSON). 0이 아닌 코드로 끝나면 해당 확장 프로그램을 받지 않음
+    pub before_download: Option<String>,
+    /// 확장 프로그램 하나를 받을 때마다 실행 (`{file}`, `{id}`, `{version}`, `{sha256}`).
+    /// 실패하면 받은 파일을 격리하고 다운로드 실패로 처리
+    pub on_download: Option<String>,
+    /// 동기화가 끝난 뒤 한 번 실행 (`{results}`, `{downloads}`, `{output_dir}`, `{run_id}`).
+    /// 실패하면 실행을 실패로 종료
+    pub on_complete: Option<String>,
+}
+
+/// `on_download` 훅을 실행합니다. 바이러스 검사처럼 파일을 거부하는 훅이 실패하면 `hook_failed` 오류입니다.
+pub async fn on_download(hooks: &Hooks, vars: &[(&str, &str)]) -> Result<()> {
+    let Some(template) = &hooks.on_download else {
+        return Ok(());
+    };
+
+    run("on_download", template, vars).await
+        .map_err(|e| VsixError::HookFailed { message: format!("{:#}", e) }.into())
+}
+
+/// `before_download` 훅을 실행해 다운로드를 허용하는지 묻습니다.
+/// 훅이 0이 아닌 코드로 끝나면 거부한 것으로 보고, 표준 출력(없으면 종료 코드)을 거부 이유로 돌려줍니다.
+/// 훅을 실행하지 못하면 `hook_failed` 오류입니다.
+pub async fn before_download<T: Serialize>(hooks: &Hooks, vars: &[(&str, &str)], metadata: &T) -> Result<Option<String>> {
+    let Some(template) = &hooks.before_download else {
+        return Ok(None);
+    };
+
+    let argv = expand("before_download", template, vars)?;
+    let (program, args) = argv.split_first().expect("expand rejects empty commands");
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| VsixError::HookFailed { message: format!("before_download 훅을 실행하지 못했습니다 ({}): {}", program, e) })?;
+
+    let body = serde_json::to_vec(metadata)
+        .with_context(|| "Failed to serialize before_download metadata")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // 표준 입력을 읽지 않고 끝나는 훅도 있으므로 쓰기 실패는 무시
+        let _ = stdin.write_all(&body).await;
+    }
+
+    let output = child.wait_with_output().await
+        .map_err(|e| VsixError::HookFailed { message: format!("before_download 훅을 실행하지 못했습니다 ({}): {}", program, e) })?;
+    if output.status.success() {
+        return Ok(None);
+    }
+
+    let reason = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(if reason.is_empty() { exit_description(output.status) } else { reason }))
+}
+
+/// `on_complete` 훅을 실행합니다.
+pub async fn on_complete(hooks: &Hooks, vars: &[(&str, &str)]) -> Result<()> {
+    let Some(template) = &hooks.on_complete else {
+        return Ok(());
+    };
+
+    run("on_complete", template, vars).await
+}
+
+/// 명령 템플릿을 인자로 나눈 뒤 인자마다 `{이름}`을 바꿔 셸 없이 실행합니다.
+/// 값에 공백이 있어도 인자 하나로 전달되며, 값이 셸 문법으로 해석되지 않습니다.
+async fn run(hook: &str, template: &str, vars: &[(&str, &str)]) -> Result<()> {
+    let argv = expand(hook, template, vars)?;
+    let (program, args) = argv.split_first().expect("expand rejects empty commands");
+
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .await
+        .with_context(|| format!("Failed to run {} hook: {}", hook, program))?;
+
+    if !status.success() {
+        return Err(anyhow!(
+            "{} 훅이 실패했습니다 ({}): {}",
+            hook,
+            exit_description(status),
+            argv.join(" ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// 명령 템플릿을 인자로 나누고 `{이름}`을 바꾼 뒤 실행할 명령을 출력합니다.
+fn expand(hook: &str, template: &str, vars: &[(&str, &str)]) -> Result<Vec<String>> {
+    let argv: Vec<String> = split_command(template)?
+        .into_iter()
+        .map(|arg| vars.iter().fold(arg, |arg, (name, value)| arg.replace(&format!("{{{}}}", name), value)))
+        .collect();
+    if argv.is_empty() {
+        return Err(anyhow!("{} 훅 명령이 비어 있습니다", hook));
+    }
+
+    println!("{} {}", format!("{} 훅 실행:", hook).blue(), argv.join(" "));
+    Ok(argv)
+}
+
+fn exit_description(status: std::process::ExitStatus) -> String {
+    status.code().map_or_else(|| "시그널로 종료".to_string(), |code| format!("종료 코드 {}", code))
+}
+
+/// 공백으로 인자를 나눕니다. 작은따옴표나 큰따옴표로 감싼 부분은 공백을 포함한 인자 하나입니다.
+pub fn split_command(command: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_arg = true;
+            },
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            },
+            (None, c) => {
+                current.push(c);
+                in_arg = true;
+            },
+        }
+    }
+
+    if quote.is_some() {
+        return Err(anyhow!("훅 명령의 따옴표가 닫히지 않았습니다: {}", command));
+    }
+    if in_arg {
+        args.push(current);
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_whitespace_and_keeps_quoted_args() {
+        assert_eq!(split_command("./policy.sh {id}  {size}").unwrap(), ["./policy.sh", "{id}", "{size}"]);
+        assert_eq!(split_command("notify-send 'sync done' \"{count} items\"").unwrap(), ["notify-send", "sync done", "{count} items"]);
+        assert_eq!(split_command("echo '' x").unwrap(), ["echo", "", "x"]);
+        assert!(split_command("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_unclosed_quote() {
+        assert!(split_command("echo 'unterminated").is_err());
+    }
+}
@@ -0,0 +1,426 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Context, Result};
+use futures_util::stream::{self, BoxStream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_LENGTH, CONTENT_TYPE, RANGE, USER_AGENT};
+use reqwest::{Client, ClientBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::OnceCell;
+
+use crate::auth::{Credentials, Registry};
+use crate::error::VsixError;
+
+/// 모든 요청에 붙일 헤더 (`--user-agent`, `--header`)
+static DEFAULT_HEADERS: OnceLock<HeaderMap> = OnceLock::new();
+
+/// DNS 대신 쓸 호스트별 IP 주소 (`--resolve`)
+static RESOLVE_OVERRIDES: OnceLock<Vec<(String, IpAddr)>> = OnceLock::new();
+
+/// 레지스트리 응답을 실제로 요청할지, 디스크에 기록할지, 디스크에서 재생할지 결정합니다.
+#[derive(Debug, Clone)]
+pub enum HttpMode {
+    Live,
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// 모든 레지스트리 요청이 거치는 공용 HTTP 클라이언트
+pub struct HttpClient {
+    client: Client,
+    mode: HttpMode,
+    /// 파일이나 표준 입력으로 받은 토큰 (키링보다 우선)
+    tokens: Credentials,
+    /// OS 키링의 레지스트리 토큰 (첫 실제 요청 때 읽음)
+    credentials: OnceCell<Credentials>,
+}
+
+pub struct HttpResponse {
+    /// 리디렉션을 따라간 최종 URL
+    pub url: String,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    body: Body,
+}
+
+enum Body {
+    Live(reqwest::Response),
+    Buffered(bytes::Bytes),
+}
+
+/// 기록된 응답의 메타데이터 (`<key>.json`), 본문은 `<key>.body`에 저장됩니다.
+#[derive(Debug, Serialize, Deserialize)]
+struct Fixture {
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request_body: Option<String>,
+    /// 리디렉션을 따라간 최종 URL (요청 URL과 같으면 없음)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    final_url: Option<String>,
+    status: u16,
+    headers: BTreeMap<String, String>,
+}
+
+impl HttpClient {
+    pub fn new(mode: HttpMode) -> Result<Self> {
+        if let HttpMode::Record(dir) = &mode {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create fixture directory: {}", dir.display()))?;
+        }
+
+        let client = configure(Client::builder())
+            .build()
+            .with_context(|| "Failed to build HTTP client")?;
+
+        Ok(HttpClient {
+            client,
+            mode,
+            tokens: Credentials::default(),
+            credentials: OnceCell::new(),
+        })
+    }
+
+    /// 키링 대신 사용할 토큰을 지정합니다. 지정하지 않은 레지스트리는 키링의 토큰을 사용합니다.
+    pub fn with_tokens(mut self, tokens: Credentials) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    pub async fn get(&self, url: &str) -> Result<HttpResponse, VsixError> {
+        self.execute(&Request { url, accept: None, body: None, headers: &[], range: None, head: false }).await
+    }
+
+    /// 본문 없이 응답 헤더만 받는 HEAD 요청을 보냅니다 (크기 확인용).
+    pub async fn head(&self, url: &str) -> Result<HttpResponse, VsixError> {
+        self.execute(&Request { url, accept: None, body: None, headers: &[], range: None, head: true }).await
+    }
+
+    /// `start..=end` 바이트 범위만 요청합니다. 범위는 기록 파일의 키에 포함됩니다.
+    pub async fn get_range(&self, url: &str, start: u64, end: u64) -> Result<HttpResponse, VsixError> {
+        let range = format!("bytes={}-{}", start, end);
+        self.execute(&Request { url, accept: None, body: None, headers: &[], range: Some(&range), head: false }).await
+    }
+
+    /// 인증 헤더 등 추가 헤더와 함께 GET 요청을 보냅니다.
+    /// 헤더 값은 기록 파일의 키에 포함되지 않으므로 비밀 값이 파일 이름으로 새지 않습니다.
+    pub async fn get_with_headers(&self, url: &str, headers: &[(String, String)]) -> Result<HttpResponse, VsixError> {
+        self.execute(&Request { url, accept: None, body: None, headers, range: None, head: false }).await
+    }
+
+    /// JSON 본문으로 POST 요청을 보냅니다. `accept`는 API 버전 지정 등에 사용됩니다.
+    pub async fn post_json(&self, url: &str, accept: &str, body: &serde_json::Value) -> Result<HttpResponse, VsixError> {
+        let body = body.to_string();
+        self.execute(&Request { url, accept: Some(accept), body: Some(&body), headers: &[], range: None, head: false }).await
+    }
+
+    async fn execute(&self, request: &Request<'_>) -> Result<HttpResponse, VsixError> {
+        let url = request.url;
+        match &self.mode {
+            HttpMode::Live => {
+                let response = self.send(request).await?;
+                Ok(HttpResponse {
+                    url: response.url().to_string(),
+                    status: response.status(),
+                    headers: response.headers().clone(),
+                    body: Body::Live(response),
+                })
+            },
+            HttpMode::Record(dir) => {
+                let response = self.send(request).await?;
+                let final_url = response.url().to_string();
+                let status = response.status();
+                let headers = response.headers().clone();
+                let body = response.bytes()
+                    .await
+                    .map_err(|e| VsixError::from_request(e, url))?;
+
+                save_fixture(dir, request, &final_url, status, &headers, &body)?;
+
+                Ok(HttpResponse {
+                    url: final_url,
+                    status,
+                    headers,
+                    body: Body::Buffered(body),
+                })
+            },
+            HttpMode::Replay(dir) => load_fixture(dir, request),
+        }
+    }
+
+    async fn send(&self, request: &Request<'_>) -> Result<reqwest::Response, VsixError> {
+        let mut builder = match request.body {
+            Some(body) => self.client.post(request.url)
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.to_string()),
+            None if request.head => self.client.head(request.url),
+            None => self.client.get(request.url),
+        };
+
+        if let Some(accept) = request.accept {
+            builder = builder.header(ACCEPT, accept);
+        }
+
+        if let Some(range) = request.range {
+            builder = builder.header(RANGE, range);
+        }
+
+        for (name, value) in request.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+
+        // 키링 호출은 런타임 스레드를 막을 수 있으므로 별도 스레드에서 읽음
+        let credentials = self.credentials
+            .get_or_init(|| async {
+                tokio::task::spawn_blocking(Credentials::from_keyring).await.unwrap_or_default()
+            })
+            .await;
+        match self.tokens.token_for(request.url).or_else(|| credentials.token_for(request.url)) {
+            // Azure DevOps 개인 액세스 토큰은 사용자 이름 없이 Basic 인증으로 보냄
+            Some((Registry::Marketplace, token)) => builder = builder.basic_auth("", Some(token)),
+            Some((Registry::OpenVsx, token)) => builder = builder.bearer_auth(token),
+            None => {},
+        }
+
+        builder.send()
+            .await
+            .map_err(|e| VsixError::from_request(e, request.url))
+    }
+}
+
+struct Request<'a> {
+    url: &'a str,
+    accept: Option<&'a str>,
+    body: Option<&'a str>,
+    headers: &'a [(String, String)],
+    range: Option<&'a str>,
+    /// 본문 없이 헤더만 받는 HEAD 요청인지
+    head: bool,
+}
+
+impl Request<'_> {
+    /// 기록 파일 이름에 쓰이는 키. POST는 본문, 범위 요청은 범위, HEAD 요청은 메서드까지 포함해 구분합니다.
+    fn fixture_key(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.url.as_bytes());
+        if let Some(body) = self.body {
+            hasher.update(b"\n");
+            hasher.update(body.as_bytes());
+        }
+        if let Some(range) = self.range {
+            hasher.update(b"\nrange=");
+            hasher.update(range.as_bytes());
+        }
+        if self.head {
+            hasher.update(b"\nmethod=HEAD");
+        }
+        let digest = format!("{:x}", hasher.finalize());
+        digest[..16].to_string()
+    }
+}
+
+impl HttpResponse {
+    pub fn content_length(&self) -> Option<u64> {
+        self.headers.get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+    }
+
+    pub async fn json<T: DeserializeOwned>(self) -> Result<T> {
+        match self.body {
+            Body::Live(response) => response.json().await
+                .with_context(|| "Failed to parse JSON response"),
+            Body::Buffered(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| "Failed to parse JSON response"),
+        }
+    }
+
+    pub async fn text(self) -> Result<String> {
+        match self.body {
+            Body::Live(response) => response.text().await
+                .with_context(|| "Failed to read response body"),
+            Body::Buffered(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+        }
+    }
+
+    pub async fn bytes(self) -> Result<bytes::Bytes> {
+        match self.body {
+            Body::Live(response) => response.bytes().await
+                .with_context(|| "Failed to read response body"),
+            Body::Buffered(bytes) => Ok(bytes),
+        }
+    }
+
+    pub fn bytes_stream(self) -> BoxStream<'static, reqwest::Result<bytes::Bytes>> {
+        match self.body {
+            Body::Live(response) => response.bytes_stream().boxed(),
+            Body::Buffered(bytes) => stream::once(async move { Ok(bytes) }).boxed(),
+        }
+    }
+}
+
+fn fixture_paths(dir: &Path, request: &Request) -> (PathBuf, PathBuf) {
+    let key = request.fixture_key();
+    (dir.join(format!("{}.json", key)), dir.join(format!("{}.body", key)))
+}
+
+fn save_fixture(dir: &Path, request: &Request, final_url: &str, status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Result<(), VsixError> {
+    let (meta_path, body_path) = fixture_paths(dir, request);
+
+    let fixture = Fixture {
+        url: request.url.to_string(),
+        request_body: request.body.map(|b| b.to_string()),
+        final_url: Some(final_url.to_string()).filter(|url| url != request.url),
+        status: status.as_u16(),
+        headers: headers.iter()
+            .filter_map(|(name, value)| {
+                value.to_str().ok().map(|v| (name.to_string(), v.to_string()))
+            })
+            .collect(),
+    };
+
+    let meta = serde_json::to_string_pretty(&fixture)
+        .expect("fixture metadata is always serializable");
+
+    fs::write(&meta_path, meta)
+        .map_err(|source| VsixError::Io { path: meta_path, source })?;
+    fs::write(&body_path, body)
+        .map_err(|source| VsixError::Io { path: body_path, source })?;
+
+    Ok(())
+}
+
+fn load_fixture(dir: &Path, request: &Request) -> Result<HttpResponse, VsixError> {
+    let (meta_path, body_path) = fixture_paths(dir, request);
+
+    if !meta_path.exists() {
+        return Err(VsixError::FixtureMissing { url: request.url.to_string() });
+    }
+
+    let meta = fs::read_to_string(&meta_path)
+        .map_err(|source| VsixError::Io { path: meta_path.clone(), source })?;
+    let fixture: Fixture = serde_json::from_str(&meta)
+        .map_err(|e| VsixError::Io { path: meta_path, source: e.into() })?;
+    let body = fs::read(&body_path)
+        .map_err(|source| VsixError::Io { path: body_path, source })?;
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in &fixture.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::from_str(value)) {
+            headers.insert(name, value);
+        }
+    }
+
+    Ok(HttpResponse {
+        url: fixture.final_url.unwrap_or_else(|| request.url.to_string()),
+        status: StatusCode::from_u16(fixture.status).unwrap_or(StatusCode::OK),
+        headers,
+        body: Body::Buffered(body.into()),
+    })
+}
+
+/// 이후에 만드는 모든 HTTP 클라이언트가 요청마다 붙일 User-Agent와 헤더를 정합니다.
+/// 식별용 User-Agent나 사용자 지정 헤더를 요구하는 프록시를 거칠 때 씁니다. 처음 정한 값만 적용됩니다.
+pub fn set_default_headers(user_agent: Option<&str>, headers: &[(String, String)]) -> Result<()> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let header_name = HeaderName::try_from(name.as_str())
+            .map_err(|_| anyhow!("올바른 헤더 이름이 아닙니다: {}", name))?;
+        let header_value = HeaderValue::from_str(value)
+            .map_err(|_| anyhow!("{} 헤더의 값에 쓸 수 없는 문자가 있습니다", name))?;
+        map.append(header_name, header_value);
+    }
+    if let Some(user_agent) = user_agent {
+        let value = HeaderValue::from_str(user_agent)
+            .map_err(|_| anyhow!("User-Agent에 쓸 수 없는 문자가 있습니다: {}", user_agent))?;
+        map.insert(USER_AGENT, value);
+    }
+
+    let _ = DEFAULT_HEADERS.set(map);
+    Ok(())
+}
+
+/// 이후에 만드는 모든 HTTP 클라이언트가 `host`를 DNS 대신 주어진 IP로 연결하게 합니다.
+/// 같은 호스트를 여러 번 지정하면 모든 주소를 후보로 씁니다. 처음 정한 값만 적용됩니다.
+pub fn set_resolve_overrides(overrides: &[(String, IpAddr)]) {
+    let _ = RESOLVE_OVERRIDES.set(overrides.to_vec());
+}
+
+/// [`set_default_headers`]와 [`set_resolve_overrides`]로 정한 설정을 클라이언트에 적용합니다.
+pub fn configure(mut builder: ClientBuilder) -> ClientBuilder {
+    if let Some(headers) = DEFAULT_HEADERS.get() {
+        builder = builder.default_headers(headers.clone());
+    }
+
+    let overrides = RESOLVE_OVERRIDES.get().map(Vec::as_slice).unwrap_or_default();
+    let mut hosts: Vec<&str> = overrides.iter().map(|(host, _)| host.as_str()).collect();
+    hosts.sort_unstable();
+    hosts.dedup();
+    for host in hosts {
+        // DNS에는 포트가 없으므로 URL의 포트로 연결됨
+        let addrs: Vec<SocketAddr> = overrides.iter()
+            .filter(|(h, _)| h == host)
+            .map(|(_, ip)| SocketAddr::new(*ip, 0))
+            .collect();
+        builder = builder.resolve_to_addrs(host, &addrs);
+    }
+    builder
+}
+
+/// curl의 `--resolve`처럼 `host:ip` 형식의 문자열을 호스트와 IP 주소로 나눕니다.
+/// IPv6 주소는 `host:[::1]`처럼 대괄호로 감싸거나 그대로 씁니다.
+pub fn parse_resolve(input: &str) -> Result<(String, IpAddr), String> {
+    let (host, ip) = input.split_once(':')
+        .ok_or_else(|| format!("'host:ip' 형식이어야 합니다: {}", input))?;
+
+    let host = host.trim().to_ascii_lowercase();
+    if host.is_empty() {
+        return Err(format!("호스트 이름이 비어 있습니다: {}", input));
+    }
+    let ip = ip.trim();
+    let ip = ip.strip_prefix('[').and_then(|ip| ip.strip_suffix(']')).unwrap_or(ip);
+    let ip: IpAddr = ip.parse()
+        .map_err(|_| format!("올바른 IP 주소가 아닙니다: {}", ip))?;
+
+    Ok((host, ip))
+}
+
+/// `Name: value` 형식의 헤더 문자열을 이름과 값으로 나눕니다.
+pub fn parse_header(input: &str) -> Result<(String, String), String> {
+    let (name, value) = input.split_once(':')
+        .ok_or_else(|| format!("헤더는 'Name: value' 형식이어야 합니다: {}", input))?;
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("헤더 이름이 비어 있습니다: {}", input));
+    }
+
+    Ok((name.to_string(), value.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_resolve() {
+        assert_eq!(parse_resolve("Marketplace.VisualStudio.com:10.0.0.5"), Ok(("marketplace.visualstudio.com".to_string(), "10.0.0.5".parse().unwrap())));
+        assert_eq!(parse_resolve("open-vsx.org:[::1]"), Ok(("open-vsx.org".to_string(), "::1".parse().unwrap())));
+        assert_eq!(parse_resolve("open-vsx.org:::1"), Ok(("open-vsx.org".to_string(), "::1".parse().unwrap())));
+        assert!(parse_resolve("open-vsx.org").is_err());
+        assert!(parse_resolve(":10.0.0.5").is_err());
+        assert!(parse_resolve("open-vsx.org:not-an-ip").is_err());
+    }
+
+    #[test]
+    fn parses_header() {
+        assert_eq!(parse_header("X-Proxy-Token: abc:def "), Ok(("X-Proxy-Token".to_string(), "abc:def".to_string())));
+        assert_eq!(parse_header("X-Empty:"), Ok(("X-Empty".to_string(), String::new())));
+        assert!(parse_header("no-colon").is_err());
+        assert!(parse_header(" : value").is_err());
+    }
+}
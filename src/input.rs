@@ -0,0 +1,445 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use reqwest::Url;
+
+use crate::encoding;
+use crate::error::VsixError;
+use crate::http::HttpClient;
+use crate::{Extension, Extensions};
+
+const MARKETPLACE_HOST: &str = "marketplace.visualstudio.com";
+const OPEN_VSX_HOST: &str = "open-vsx.org";
+
+/// VSCode에 포함되어 배포되는 내장 확장 프로그램의 게시자
+const BUILTIN_PUBLISHER: &str = "vscode";
+
+/// 목록 파일을 읽습니다. 경로가 `http://` 또는 `https://`로 시작하면 원격에서 가져옵니다.
+pub async fn read_list_source(client: &HttpClient, source: &Path, headers: &[(String, String)]) -> Result<String> {
+    let location = source.to_string_lossy();
+
+    if !is_remote(&location) {
+        return encoding::read_file(source);
+    }
+
+    let response = client.get_with_headers(&location, headers).await?;
+    if !response.status.is_success() {
+        return Err(VsixError::from_status(response.status, &location).into());
+    }
+
+    let bytes = response.bytes().await
+        .with_context(|| format!("Failed to read remote file: {}", location))?;
+    encoding::decode(&bytes, &location)
+}
+
+/// `--file 'profiles/*.yml'`처럼 와일드카드가 들어간 경로를 맞는 파일들로 펼칩니다 (이름순).
+/// `*`, `?`, `[abc]`, `[!a-z]`와 하위 디렉토리 전체를 뜻하는 `**`를 지원하며, 셸처럼 `*`는 `.`으로 시작하는 이름과 맞지 않습니다.
+/// 와일드카드가 없는 경로와 URL은 그대로 둡니다.
+pub fn expand_globs(files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut expanded = Vec::new();
+    for file in files {
+        let location = file.to_string_lossy();
+        if is_remote(&location) || !location.contains(['*', '?', '[']) {
+            expanded.push(file.clone());
+            continue;
+        }
+
+        let mut matches = Vec::new();
+        let components: Vec<Component> = file.components().collect();
+        let start = components.iter().position(|c| c.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+            .unwrap_or(components.len());
+        let base: PathBuf = components[..start].iter().collect();
+        let patterns: Vec<String> = components[start..].iter().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+        walk_glob(&base, &patterns, &mut matches);
+
+        if matches.is_empty() {
+            return Err(anyhow!("{} 패턴에 맞는 목록 파일이 없습니다", location));
+        }
+        matches.sort();
+        println!("{} {} -> {}개 파일", "목록 패턴:".blue(), location, matches.len());
+        expanded.extend(matches);
+    }
+    Ok(expanded)
+}
+
+fn walk_glob(dir: &Path, patterns: &[String], matches: &mut Vec<PathBuf>) {
+    let Some((pattern, rest)) = patterns.split_first() else {
+        if dir.is_file() {
+            matches.push(dir.to_path_buf());
+        }
+        return;
+    };
+
+    let list_dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    let Ok(entries) = fs::read_dir(list_dir) else {
+        return;
+    };
+    let mut names: Vec<String> = entries.flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+
+    if pattern == "**" {
+        // 0개 이상의 디렉토리
+        walk_glob(dir, rest, matches);
+        for name in names.iter().filter(|name| !name.starts_with('.')) {
+            let child = dir.join(name);
+            if child.is_dir() {
+                walk_glob(&child, patterns, matches);
+            }
+        }
+        return;
+    }
+
+    for name in names.iter().filter(|name| wildcard_match(pattern, name)) {
+        let child = dir.join(name);
+        if rest.is_empty() || child.is_dir() {
+            walk_glob(&child, rest, matches);
+        }
+    }
+}
+
+/// 경로 한 단계 이름이 와일드카드 패턴과 맞는지
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    if name.starts_with('.') && !pattern.starts_with('.') {
+        return false;
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    match_from(&pattern, &name)
+}
+
+fn match_from(pattern: &[char], name: &[char]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') => (0..=name.len()).any(|skip| match_from(&pattern[1..], &name[skip..])),
+        Some('?') => !name.is_empty() && match_from(&pattern[1..], &name[1..]),
+        Some('[') => {
+            let Some(close) = pattern.iter().skip(1).position(|c| *c == ']').map(|i| i + 1) else {
+                return name.first() == Some(&'[') && match_from(&pattern[1..], &name[1..]);
+            };
+            let Some(c) = name.first() else {
+                return false;
+            };
+            let (negated, set) = match pattern[1] {
+                '!' | '^' => (true, &pattern[2..close]),
+                _ => (false, &pattern[1..close]),
+            };
+            let mut found = false;
+            let mut i = 0;
+            while i < set.len() {
+                if i + 2 < set.len() && set[i + 1] == '-' {
+                    found |= (set[i]..=set[i + 2]).contains(c);
+                    i += 3;
+                } else {
+                    found |= set[i] == *c;
+                    i += 1;
+                }
+            }
+            found != negated && match_from(&pattern[close + 1..], &name[1..])
+        },
+        Some(literal) => name.first() == Some(literal) && match_from(&pattern[1..], &name[1..]),
+    }
+}
+
+/// 목록 파일 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListFormat {
+    Yaml,
+    /// 한 줄에 ID 하나 (`code --list-extensions` 출력 형식)
+    Text,
+    /// ID 문자열 배열
+    Json,
+}
+
+/// 확장자로 형식을 정하고, 확장자가 없거나 모르는 경우 내용으로 판단합니다.
+fn detect_format(content: &str, source: &Path) -> ListFormat {
+    let extension = source.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("yml" | "yaml") => return ListFormat::Yaml,
+        Some("json") => return ListFormat::Json,
+        Some("txt" | "list") => return ListFormat::Text,
+        _ => {},
+    }
+
+    let first_line = content.trim_start_matches('\u{feff}')
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'));
+
+    match first_line {
+        Some(line) if line.starts_with('[') => ListFormat::Json,
+        // YAML 목록은 `enabled:` 같은 매핑으로 시작하고, ID나 페이지 URL 한 줄은 `키:` 형태가 아님
+        Some(line) if !is_remote(line) && (line.ends_with(':') || line.contains(": ")) => ListFormat::Yaml,
+        Some(_) => ListFormat::Text,
+        None => ListFormat::Yaml,
+    }
+}
+
+/// 목록 파일 내용을 해석합니다. YAML 외에 한 줄에 ID 하나인 텍스트와 ID 배열 JSON도 받습니다.
+pub fn parse_extension_list(content: &str, source: &Path, allow_unknown_fields: bool) -> Result<Extensions> {
+    match detect_format(content, source) {
+        ListFormat::Yaml => parse_yaml_list(content, source, allow_unknown_fields),
+        ListFormat::Text => Ok(parse_text_list(content)),
+        ListFormat::Json => parse_json_list(content, source),
+    }
+}
+
+/// `#` 주석과 빈 줄을 건너뛰고 한 줄을 한 항목으로 읽습니다.
+fn parse_text_list(content: &str) -> Extensions {
+    let enabled = content.lines()
+        .map(|line| line.trim().trim_start_matches('\u{feff}'))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(extension_from_spec)
+        .collect();
+
+    Extensions { run: None, enabled: Some(enabled) }
+}
+
+fn parse_json_list(content: &str, source: &Path) -> Result<Extensions> {
+    let ids: Vec<String> = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .with_context(|| format!("Failed to parse JSON file: {}", source.display()))?;
+
+    let enabled = ids.iter()
+        .map(|id| extension_from_spec(id.trim()))
+        .collect();
+
+    Ok(Extensions { run: None, enabled: Some(enabled) })
+}
+
+/// `publisher.name` 또는 `publisher.name@1.2.3` 형식의 항목. 페이지 URL은 그대로 ID 자리에 둡니다.
+pub fn extension_from_spec(spec: &str) -> Extension {
+    let (id, version) = match spec.rsplit_once('@') {
+        Some((id, version)) if !is_remote(spec) && !version.is_empty() => (id, Some(version.to_string())),
+        _ => (spec, None),
+    };
+
+    Extension {
+        id: id.to_string(),
+        uuid: None,
+        version,
+        source: None,
+        source_file: None,
+    }
+}
+
+/// YAML 목록을 해석합니다.
+/// 알 수 없는 필드(`enabeld:` 같은 오타)는 위치와 함께 오류로 보고하며, `allow_unknown_fields`면 경고만 출력합니다.
+fn parse_yaml_list(content: &str, source: &Path, allow_unknown_fields: bool) -> Result<Extensions> {
+    let mut unknown = Vec::new();
+    let deserializer = serde_yaml::Deserializer::from_str(content);
+    let extensions: Extensions = serde_ignored::deserialize(deserializer, |path| unknown.push(field_path(&path.to_string())))
+        .with_context(|| format!("Failed to parse YAML file: {}", source.display()))?;
+
+    if unknown.is_empty() {
+        return Ok(extensions);
+    }
+
+    let problems: Vec<String> = unknown.iter()
+        .map(|path| match locate_key(content, path) {
+            Some((line, column)) => format!("{}:{}:{}: 알 수 없는 필드 `{}`", source.display(), line, column, path),
+            None => format!("{}: 알 수 없는 필드 `{}`", source.display(), path),
+        })
+        .collect();
+
+    if !allow_unknown_fields {
+        return Err(anyhow!(
+            "목록 파일에 알 수 없는 필드가 있습니다 (--allow-unknown-fields로 경고만 출력할 수 있음):\n{}",
+            problems.join("\n")
+        ));
+    }
+
+    for problem in &problems {
+        println!("{} {}", "경고:".yellow(), problem);
+    }
+
+    Ok(extensions)
+}
+
+/// serde_ignored의 `enabled.?.0.verison` 형식 경로를 `enabled[0].verison`으로 바꿉니다.
+fn field_path(raw: &str) -> String {
+    let mut path = String::new();
+    for segment in raw.split('.').filter(|s| *s != "?") {
+        if segment.chars().all(|c| c.is_ascii_digit()) {
+            path.push_str(&format!("[{}]", segment));
+        } else {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(segment);
+        }
+    }
+    path
+}
+
+/// `enabled[0].verison` 같은 필드 경로의 마지막 키가 처음 나오는 줄과 열 (1부터 시작)
+fn locate_key(content: &str, path: &str) -> Option<(usize, usize)> {
+    let key = path.rsplit('.').next()?;
+    let key = key.split('[').next()?;
+
+    content.lines().enumerate().find_map(|(index, line)| {
+        let trimmed = line.trim_start_matches([' ', '-']);
+        let rest = trimmed.strip_prefix(key)?;
+        if !rest.trim_start().starts_with(':') {
+            return None;
+        }
+        Some((index + 1, line.len() - trimmed.len() + 1))
+    })
+}
+
+/// `vscode.git`처럼 VSCode에 포함된 내장 확장 프로그램 ID인지 확인합니다.
+pub fn is_builtin(extension_id: &str) -> bool {
+    extension_id.split_once('.')
+        .is_some_and(|(publisher, _)| publisher.eq_ignore_ascii_case(BUILTIN_PUBLISHER))
+}
+
+pub fn is_remote(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+/// 확장 프로그램 항목의 ID 자리에 들어온 값을 `publisher.name` ID와 버전으로 정규화합니다.
+///
+/// 다음 형식을 받습니다:
+/// - `publisher.name`
+/// - `https://marketplace.visualstudio.com/items?itemName=publisher.name` (`&version=1.2.3` 또는 `#1.2.3` 선택)
+/// - `https://open-vsx.org/extension/publisher/name[/1.2.3]`
+pub fn parse_extension_reference(raw: &str) -> Result<(String, Option<String>)> {
+    let raw = raw.trim();
+
+    if !is_remote(raw) {
+        return Ok((raw.to_string(), None));
+    }
+
+    let url = Url::parse(raw)
+        .map_err(|e| anyhow!("유효하지 않은 확장 프로그램 URL: {} ({})", raw, e))?;
+
+    match url.host_str() {
+        Some(MARKETPLACE_HOST) => {
+            let id = url.query_pairs()
+                .find(|(key, _)| key == "itemName")
+                .map(|(_, value)| value.to_string())
+                .ok_or_else(|| anyhow!("Marketplace URL에 itemName이 없습니다: {}", raw))?;
+
+            let version = url.query_pairs()
+                .find(|(key, _)| key == "version")
+                .map(|(_, value)| value.to_string())
+                .or_else(|| version_fragment(&url));
+
+            Ok((id, version))
+        },
+        Some(OPEN_VSX_HOST) => {
+            let segments: Vec<&str> = url.path_segments()
+                .map(|s| s.filter(|s| !s.is_empty()).collect())
+                .unwrap_or_default();
+
+            match segments.as_slice() {
+                ["extension", namespace, name, rest @ ..] => {
+                    let version = rest.first()
+                        .map(|v| v.to_string())
+                        .or_else(|| version_fragment(&url));
+                    Ok((format!("{}.{}", namespace, name), version))
+                },
+                _ => Err(anyhow!("Open VSX 확장 프로그램 URL 형식이 아닙니다: {}", raw)),
+            }
+        },
+        _ => Err(anyhow!("지원하지 않는 확장 프로그램 URL입니다: {}", raw)),
+    }
+}
+
+/// `#1.2.3` 또는 `#version=1.2.3` 형식의 버전 조각
+fn version_fragment(url: &Url) -> Option<String> {
+    let fragment = url.fragment()?;
+    let version = fragment.strip_prefix("version=").unwrap_or(fragment);
+
+    if version.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        Some(version.to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 목록 항목을 `id@version` 문자열로
+    fn specs(extensions: Extensions) -> Vec<String> {
+        extensions.enabled.unwrap_or_default().into_iter()
+            .map(|e| match e.version {
+                Some(version) => format!("{}@{}", e.id, version),
+                None => e.id,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_text_list() {
+        let content = "\u{feff}# code --list-extensions\nms-python.python\n\n  golang.go@0.41.0  \n";
+        let extensions = parse_extension_list(content, Path::new("extensions"), false).unwrap();
+        assert_eq!(specs(extensions), ["ms-python.python", "golang.go@0.41.0"]);
+    }
+
+    #[test]
+    fn parses_json_list() {
+        let content = r#"["ms-python.python", " golang.go@0.41.0 "]"#;
+        let extensions = parse_extension_list(content, Path::new("extensions.json"), false).unwrap();
+        assert_eq!(specs(extensions), ["ms-python.python", "golang.go@0.41.0"]);
+    }
+
+    #[test]
+    fn parses_yaml_list() {
+        let content = "enabled:\n  - id: ms-python.python\n  - id: golang.go\n    version: 0.41.0\n";
+        let extensions = parse_extension_list(content, Path::new("extensions"), false).unwrap();
+        assert_eq!(specs(extensions), ["ms-python.python", "golang.go@0.41.0"]);
+    }
+
+    #[test]
+    fn reports_unknown_yaml_field_location() {
+        let content = "enabled:\n  - id: golang.go\n    verison: 0.41.0\n";
+        let err = parse_extension_list(content, Path::new("l.yml"), false).unwrap_err();
+        assert!(err.to_string().contains("l.yml:3:5: 알 수 없는 필드 `enabled[0].verison`"), "{}", err);
+        assert!(parse_extension_list(content, Path::new("l.yml"), true).is_ok());
+    }
+
+    #[test]
+    fn keeps_page_urls_whole() {
+        let url = "https://open-vsx.org/extension/golang/Go/0.41.0";
+        let extension = extension_from_spec(url);
+        assert_eq!((extension.id.as_str(), extension.version), (url, None));
+    }
+
+    #[test]
+    fn parses_extension_references() {
+        assert_eq!(parse_extension_reference(" golang.go ").unwrap(), ("golang.go".to_string(), None));
+        assert_eq!(
+            parse_extension_reference("https://marketplace.visualstudio.com/items?itemName=golang.Go&version=0.41.0").unwrap(),
+            ("golang.Go".to_string(), Some("0.41.0".to_string()))
+        );
+        assert_eq!(
+            parse_extension_reference("https://marketplace.visualstudio.com/items?itemName=golang.Go#version=0.40.0").unwrap(),
+            ("golang.Go".to_string(), Some("0.40.0".to_string()))
+        );
+        assert_eq!(
+            parse_extension_reference("https://open-vsx.org/extension/golang/Go/0.41.0").unwrap(),
+            ("golang.Go".to_string(), Some("0.41.0".to_string()))
+        );
+        assert!(parse_extension_reference("https://open-vsx.org/namespace/golang").is_err());
+        assert!(parse_extension_reference("https://example.com/golang.go").is_err());
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        assert!(wildcard_match("*.yml", "work.yml"));
+        assert!(wildcard_match("team-?.yml", "team-a.yml"));
+        assert!(wildcard_match("[a-c]*.yml", "base.yml"));
+        assert!(!wildcard_match("[!a-c]*.yml", "base.yml"));
+        assert!(!wildcard_match("*.yml", ".hidden.yml"));
+        assert!(!wildcard_match("*.yml", "work.yaml"));
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::version;
+use crate::vsix;
+use crate::InspectArgs;
+
+/// `extension/package.json`에서 검토에 필요한 필드
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageJson {
+    publisher: String,
+    name: String,
+    version: String,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    engines: BTreeMap<String, String>,
+    #[serde(default)]
+    extension_dependencies: Vec<String>,
+    #[serde(default)]
+    extension_pack: Vec<String>,
+    #[serde(default)]
+    activation_events: Vec<String>,
+    #[serde(default)]
+    contributes: serde_json::Map<String, serde_json::Value>,
+}
+
+/// `inspect` 출력 내용
+#[derive(Debug, Serialize)]
+struct ExtensionDetails {
+    path: PathBuf,
+    id: String,
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    engine: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_platform: Option<String>,
+    extension_dependencies: Vec<String>,
+    extension_pack: Vec<String>,
+    activation_events: Vec<String>,
+    contributes: Vec<String>,
+}
+
+/// VSIX 파일 또는 다운로드 디렉토리의 확장 프로그램 ID를 받아 매니페스트 내용을 출력합니다.
+pub fn run(args: &InspectArgs) -> Result<()> {
+    let path = resolve_target(&args.target, &args.output_dir)?;
+    let details = read_details(&path)?;
+
+    if let Some(field) = &args.field {
+        let value = serde_json::to_value(&details)
+            .with_context(|| "Failed to serialize to JSON")?;
+        let selected = select(&value, field)
+            .map_err(|reason| anyhow!("{} 필드 경로가 올바르지 않습니다: {}", field, reason))?
+            .ok_or_else(|| anyhow!("{} 필드가 없습니다 (사용할 수 있는 필드: {})", field, field_names(&value)))?;
+        print_value(selected)?;
+    } else if args.json {
+        let json = serde_json::to_string_pretty(&details)
+            .with_context(|| "Failed to serialize to JSON")?;
+        println!("{}", json);
+    } else {
+        print_details(&details);
+    }
+
+    Ok(())
+}
+
+/// 존재하는 파일 경로면 그대로 쓰고, 아니면 확장 프로그램 ID로 보고 다운로드 디렉토리에서 가장 높은 버전을 찾습니다.
+fn resolve_target(target: &str, output_dir: &Path) -> Result<PathBuf> {
+    let path = Path::new(target);
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+
+    vsix::scan_directory(output_dir)?
+        .into_iter()
+        .filter(|p| p.id.eq_ignore_ascii_case(target))
+        .max_by(|a, b| version::compare_versions(&a.version, &b.version))
+        .map(|p| p.path)
+        .ok_or_else(|| anyhow!("{} 파일이나 {} 디렉토리의 확장 프로그램을 찾을 수 없습니다", target, output_dir.display()))
+}
+
+fn read_details(path: &Path) -> Result<ExtensionDetails> {
+    let content = vsix::read_text_entry(path, vsix::PACKAGE_JSON_PATH)?;
+    let package: PackageJson = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {} in {}", vsix::PACKAGE_JSON_PATH, path.display()))?;
+
+    // 대상 플랫폼은 package.json에 없고 vsixmanifest에만 기록됨
+    let target_platform = vsix::read_identity(path).ok().and_then(|i| i.target_platform);
+
+    Ok(ExtensionDetails {
+        path: path.to_path_buf(),
+        id: format!("{}.{}", package.publisher, package.name),
+        version: package.version,
+        display_name: package.display_name,
+        engine: package.engines.get("vscode").cloned(),
+        target_platform,
+        extension_dependencies: package.extension_dependencies,
+        extension_pack: package.extension_pack,
+        activation_events: package.activation_events,
+        contributes: package.contributes.into_iter().map(|(key, _)| key).collect(),
+    })
+}
+
+fn print_details(details: &ExtensionDetails) {
+    println!("{} {}", "파일:".bold(), details.path.display());
+    println!("{} {}", "ID:".bold(), details.id);
+    println!("{} {}", "버전:".bold(), details.version);
+    if let Some(display_name) = &details.display_name {
+        println!("{} {}", "이름:".bold(), display_name);
+    }
+    println!("{} {}", "VSCode 엔진:".bold(), details.engine.as_deref().unwrap_or("-"));
+    println!("{} {}", "대상 플랫폼:".bold(), details.target_platform.as_deref().unwrap_or("범용"));
+
+    print_list("의존 확장 프로그램:", &details.extension_dependencies);
+    print_list("확장 팩 구성:", &details.extension_pack);
+    print_list("활성화 이벤트:", &details.activation_events);
+    print_list("기여 항목:", &details.contributes);
+}
+
+fn print_list(label: &str, items: &[String]) {
+    if items.is_empty() {
+        println!("{} -", label.bold());
+        return;
+    }
+
+    println!("{}", label.bold());
+    for item in items {
+        println!("  - {}", item);
+    }
+}
+
+/// `--field` 경로의 한 단계
+#[derive(Debug, PartialEq, Eq)]
+enum Step<'a> {
+    Field(&'a str),
+    Index(usize),
+}
+
+/// `version`, `contributes[0]`, `.extension_pack[1]` 같은 경로나 `/contributes/0` 같은 JSON 포인터로 값 하나를 고릅니다.
+/// 경로 형식이 잘못되었으면 Err, 형식은 맞지만 값이 없으면 Ok(None)을 반환합니다.
+fn select<'a>(value: &'a serde_json::Value, path: &str) -> Result<Option<&'a serde_json::Value>, String> {
+    if path.starts_with('/') {
+        return Ok(value.pointer(path));
+    }
+
+    let mut current = value;
+    for step in parse_path(path)? {
+        let next = match step {
+            Step::Field(name) => current.get(name),
+            Step::Index(index) => current.get(index),
+        };
+        let Some(next) = next else {
+            return Ok(None);
+        };
+        current = next;
+    }
+    Ok(Some(current))
+}
+
+/// 점으로 구분한 이름과 그 뒤에 이어지는 `[인덱스]`(`name[0][1]`)를 단계로 나눕니다.
+fn parse_path(path: &str) -> Result<Vec<Step<'_>>, String> {
+    let mut steps = Vec::new();
+    for segment in path.trim_start_matches('.').split('.').filter(|s| !s.is_empty()) {
+        let (name, mut rest) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if name.contains(']') {
+            return Err(format!("`]` 앞에 `[`가 없습니다 ({})", segment));
+        }
+        if !name.is_empty() {
+            steps.push(Step::Field(name));
+        }
+
+        while !rest.is_empty() {
+            let inner = rest.strip_prefix('[')
+                .ok_or_else(|| format!("`]` 뒤에는 `[` 또는 `.`만 올 수 있습니다 ({})", segment))?;
+            let (index, after) = inner.split_once(']')
+                .ok_or_else(|| format!("`[`가 `]`로 닫히지 않았습니다 ({})", segment))?;
+            let index = index.parse()
+                .map_err(|_| format!("배열 인덱스는 0 이상의 정수여야 합니다 ([{}])", index))?;
+            steps.push(Step::Index(index));
+            rest = after;
+        }
+    }
+    Ok(steps)
+}
+
+/// 문자열과 숫자는 따옴표 없이 그대로, 배열과 객체는 JSON으로 출력합니다 (셸 스크립트에서 바로 쓰도록).
+fn print_value(value: &serde_json::Value) -> Result<()> {
+    match value {
+        serde_json::Value::String(text) => println!("{}", text),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => println!(
+            "{}",
+            serde_json::to_string_pretty(value).with_context(|| "Failed to serialize to JSON")?
+        ),
+        other => println!("{}", other),
+    }
+    Ok(())
+}
+
+fn field_names(value: &serde_json::Value) -> String {
+    value.as_object()
+        .map(|object| object.keys().cloned().collect::<Vec<_>>().join(", "))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sample() -> serde_json::Value {
+        json!({
+            "a": { "b": ["first", "second"] },
+            "matrix": [[1], [2, 3]],
+            "version": "1.0.0",
+        })
+    }
+
+    #[test]
+    fn selects_dotted_paths_with_indexes() {
+        let value = sample();
+        assert_eq!(select(&value, "a.b[0]"), Ok(Some(&json!("first"))));
+        assert_eq!(select(&value, ".matrix[1][0]"), Ok(Some(&json!(2))));
+        assert_eq!(select(&value, "version"), Ok(Some(&json!("1.0.0"))));
+    }
+
+    #[test]
+    fn selects_json_pointers() {
+        let value = sample();
+        assert_eq!(select(&value, "/a/b/1"), Ok(Some(&json!("second"))));
+        assert_eq!(select(&value, "/matrix/0"), Ok(Some(&json!([1]))));
+        assert_eq!(select(&value, "/missing/0"), Ok(None));
+    }
+
+    #[test]
+    fn missing_fields_are_not_syntax_errors() {
+        let value = sample();
+        assert_eq!(select(&value, "contributes"), Ok(None));
+        assert_eq!(select(&value, "a.b[5]"), Ok(None));
+        assert_eq!(select(&value, "version[0]"), Ok(None));
+    }
+
+    #[test]
+    fn rejects_malformed_brackets() {
+        let value = sample();
+        for path in ["contributes[x", "a.b[0", "a.b[x]", "a.b[-1]", "a.b[]", "a.b]0[", "a.b[0]c"] {
+            assert!(select(&value, path).is_err(), "{}", path);
+        }
+    }
+}
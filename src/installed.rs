@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use tokio::process::Command;
+
+/// VSCode CLI 기본 실행 파일 이름 (Windows에서는 `code.cmd`를 지정해야 할 수 있음)
+pub const DEFAULT_CODE_BIN: &str = "code";
+
+/// 로컬 VSCode에 설치된 확장 프로그램
+#[derive(Debug, Clone)]
+pub struct InstalledExtension {
+    pub id: String,
+    pub version: String,
+}
+
+/// `code --list-extensions --show-versions`로 설치된 확장 프로그램 목록을 가져옵니다.
+pub async fn list_installed(code_bin: &str) -> Result<Vec<InstalledExtension>> {
+    let output = run_code(code_bin, &["--list-extensions", "--show-versions"]).await?;
+
+    Ok(output.lines()
+        .filter_map(|line| line.trim().split_once('@'))
+        .map(|(id, version)| InstalledExtension {
+            id: id.to_string(),
+            version: version.to_string(),
+        })
+        .collect())
+}
+
+async fn run_code(code_bin: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(code_bin)
+        .args(args)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {} (--code-bin으로 VSCode CLI 경로를 지정하세요)", code_bin))?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{} {} 실행 실패: {}",
+            code_bin,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// VSIX 파일을 설치합니다. 이미 다른 버전이 설치되어 있으면 교체합니다.
+pub async fn install_vsix(code_bin: &str, path: &Path) -> Result<()> {
+    let path = path.to_string_lossy();
+    run_code(code_bin, &["--install-extension", &path, "--force"]).await?;
+    Ok(())
+}
+
+/// code-server처럼 `--extensions-dir`을 받는 CLI로 지정한 확장 프로그램 디렉토리에 VSIX를 설치합니다.
+pub async fn install_vsix_into(code_bin: &str, path: &Path, extensions_dir: &Path) -> Result<()> {
+    let path = path.to_string_lossy();
+    let extensions_dir = extensions_dir.to_string_lossy();
+    run_code(code_bin, &["--extensions-dir", &extensions_dir, "--install-extension", &path, "--force"]).await?;
+    Ok(())
+}
+
+/// 설치된 확장 프로그램을 제거합니다.
+pub async fn uninstall(code_bin: &str, extension_id: &str) -> Result<()> {
+    run_code(code_bin, &["--uninstall-extension", extension_id]).await?;
+    Ok(())
+}
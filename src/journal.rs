@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::assets::DownloadedAsset;
+use crate::error::ErrorCode;
+use crate::output::{self, ResultsFormat};
+use crate::scan::ScanVerdict;
+use crate::{load_downloads, sort_by_id, DownloadInfo, DownloadOrigin};
+
+/// 기록과 압축이 서로 끼어들지 않게 하는 잠금
+static JOURNAL: Mutex<()> = Mutex::new(());
+
+/// 다운로드 정보 파일별로 한 번 읽어 둔 내용. 저널에 덧붙이는 변경을 함께 적용해 파일과 같게 유지하므로
+/// 결과를 기록할 때마다 파일 전체를 다시 읽고 저널을 재생하지 않아도 됩니다.
+static LOADED: Mutex<Option<HashMap<PathBuf, Vec<DownloadInfo>>>> = Mutex::new(None);
+
+/// 다운로드 정보 파일의 변경 하나. 동시에 진행하는 다운로드가 파일 전체를 읽고 다시 쓰면
+/// 서로의 변경을 덮거나 중간에 끊긴 파일이 남으므로, 변경을 한 줄씩 덧붙이고 읽을 때 차례로 적용합니다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// 다운로드 URL과 버전을 정함 (같은 ID의 이전 항목을 바꿈)
+    Resolved { info: Box<DownloadInfo> },
+    /// 파일을 받기 시작함. 받는 도중 중단되면 이전에 받은 파일의 성공 기록이 남지 않습니다.
+    Downloading { id: String, timestamp: String },
+    Done {
+        id: String,
+        timestamp: String,
+        size: u64,
+        sha256: String,
+        target_platform: Option<String>,
+        duration_ms: Option<u64>,
+        bytes_per_second: Option<u64>,
+        #[serde(default)]
+        origin: Option<DownloadOrigin>,
+    },
+    Failed {
+        id: String,
+        timestamp: String,
+        error: ErrorCode,
+        quarantine_path: Option<String>,
+    },
+    /// --cross-check-openvsx로 확인한 Open VSX 해시
+    CrossChecked { id: String, openvsx_sha256: String },
+    /// --assets로 받은 자산
+    Assets { id: String, assets: Vec<DownloadedAsset> },
+    /// --scan의 악성 코드 검사 결과 (발견되면 뒤이어 failed가 기록됨)
+    Scanned { id: String, scan: ScanVerdict },
+}
+
+impl Event {
+    pub fn downloading(id: &str) -> Self {
+        Event::Downloading { id: id.to_string(), timestamp: Utc::now().to_rfc3339() }
+    }
+
+    fn apply(self, downloads: &mut Vec<DownloadInfo>) {
+        match self {
+            Event::Resolved { mut info } => {
+                // 증분 동기화에서 다시 받지 않는 같은 버전의 파일은 처음 받았을 때의 출처를 유지
+                if let Some(previous) = downloads.iter().find(|d| d.id.eq_ignore_ascii_case(&info.id) && d.success && d.version == info.version) {
+                    info.origin = previous.origin.clone();
+                }
+                // ID는 대소문자를 구분하지 않으므로 목록의 표기가 바뀌어도 같은 항목을 바꿈
+                downloads.retain(|d| !d.id.eq_ignore_ascii_case(&info.id));
+                downloads.push(*info);
+            },
+            Event::Downloading { id, timestamp } => {
+                if let Some(download) = entry(downloads, &id) {
+                    download.success = false;
+                    download.timestamp = timestamp;
+                    download.error = None;
+                    download.origin = None;
+                }
+            },
+            Event::Done { id, timestamp, size, sha256, target_platform, duration_ms, bytes_per_second, origin } => {
+                if let Some(download) = entry(downloads, &id) {
+                    download.success = true;
+                    download.timestamp = timestamp;
+                    download.size = Some(size);
+                    download.sha256 = Some(sha256);
+                    if target_platform.is_some() {
+                        download.target_platform = target_platform;
+                    }
+                    download.duration_ms = duration_ms;
+                    download.bytes_per_second = bytes_per_second;
+                    if origin.is_some() {
+                        download.origin = origin;
+                    }
+                    download.error = None;
+                    download.quarantine_path = None;
+                }
+            },
+            Event::Failed { id, timestamp, error, quarantine_path } => {
+                if let Some(download) = entry(downloads, &id) {
+                    download.success = false;
+                    download.timestamp = timestamp;
+                    download.size = None;
+                    download.sha256 = None;
+                    download.duration_ms = None;
+                    download.bytes_per_second = None;
+                    download.origin = None;
+                    download.error = Some(error);
+                    download.quarantine_path = quarantine_path;
+                }
+            },
+            Event::CrossChecked { id, openvsx_sha256 } => {
+                if let Some(download) = entry(downloads, &id) {
+                    download.openvsx_sha256 = Some(openvsx_sha256);
+                }
+            },
+            Event::Assets { id, assets } => {
+                if let Some(download) = entry(downloads, &id) {
+                    download.assets = assets;
+                }
+            },
+            Event::Scanned { id, scan } => {
+                if let Some(download) = entry(downloads, &id) {
+                    download.scan = Some(scan);
+                }
+            },
+        }
+    }
+}
+
+fn entry<'a>(downloads: &'a mut [DownloadInfo], id: &str) -> Option<&'a mut DownloadInfo> {
+    downloads.iter_mut().find(|d| d.id.eq_ignore_ascii_case(id))
+}
+
+/// 다운로드 정보 파일 옆의 저널 (downloads.json.journal)
+pub fn journal_path(downloads_path: &Path) -> PathBuf {
+    let mut name = downloads_path.as_os_str().to_os_string();
+    name.push(".journal");
+    PathBuf::from(name)
+}
+
+/// 변경 하나를 한 줄로 덧붙입니다. 한 번의 쓰기로 기록하므로 동시에 기록해도 줄이 섞이지 않습니다.
+pub fn append(downloads_path: &Path, event: &Event) -> Result<()> {
+    let mut line = serde_json::to_string(event)
+        .with_context(|| "Failed to serialize journal event")?;
+    line.push('\n');
+
+    let path = journal_path(downloads_path);
+    let _guard = JOURNAL.lock().expect("journal lock poisoned");
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("Failed to append to {}", path.display()))?;
+
+    let mut loaded = LOADED.lock().expect("downloads cache lock poisoned");
+    if let Some(downloads) = loaded.as_mut().and_then(|loaded| loaded.get_mut(downloads_path)) {
+        event.clone().apply(downloads);
+    }
+    Ok(())
+}
+
+/// 저널까지 적용한 다운로드 정보로 `f`를 실행합니다. 파일은 처음 한 번만 읽고, 이후에는 덧붙인 변경이 적용된 내용을 씁니다.
+pub fn with_downloads<T>(
+    downloads_path: &Path,
+    results_format: ResultsFormat,
+    f: impl FnOnce(&[DownloadInfo]) -> Result<T>
+) -> Result<T> {
+    let _guard = JOURNAL.lock().expect("journal lock poisoned");
+    let mut loaded = LOADED.lock().expect("downloads cache lock poisoned");
+    let loaded = loaded.get_or_insert_with(HashMap::new);
+    if !loaded.contains_key(downloads_path) {
+        let downloads = load_downloads(downloads_path, results_format)?;
+        loaded.insert(downloads_path.to_path_buf(), downloads);
+    }
+    f(&loaded[downloads_path])
+}
+
+/// 저널의 변경을 차례로 적용합니다. 기록 도중 중단되어 마지막 줄이 끊겼으면 그 줄은 무시합니다.
+pub fn replay(downloads: &mut Vec<DownloadInfo>, downloads_path: &Path) -> Result<()> {
+    let path = journal_path(downloads_path);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let complete = content.rfind('\n').map_or("", |end| &content[..end]);
+
+    for (number, line) in complete.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event: Event = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse {} line {}", path.display(), number + 1))?;
+        event.apply(downloads);
+    }
+    Ok(())
+}
+
+/// 저널을 적용한 결과로 다운로드 정보 파일을 바꾸고 저널을 지웁니다.
+/// 파일을 바꾼 뒤 저널을 지우기 전에 중단되어도 같은 변경을 다시 적용할 뿐이므로 결과는 같습니다.
+pub fn compact(downloads_path: &Path, results_format: ResultsFormat) -> Result<()> {
+    let path = journal_path(downloads_path);
+    let _guard = JOURNAL.lock().expect("journal lock poisoned");
+    // 실행을 시작하거나 마칠 때마다 호출되므로, 그사이 디렉토리가 지워졌어도 다음 읽기에서 파일을 새로 읽음
+    if let Some(loaded) = LOADED.lock().expect("downloads cache lock poisoned").as_mut() {
+        loaded.remove(downloads_path);
+    }
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let mut downloads = load_downloads(downloads_path, results_format)?;
+    sort_by_id(&mut downloads, |d| (&d.id, d.version.as_deref()));
+    let serialized = results_format.serialize(&downloads)
+        .with_context(|| "Failed to serialize downloads")?;
+    output::write_atomically(downloads_path, serialized.as_bytes())
+        .with_context(|| format!("Failed to write downloads to {}", downloads_path.display()))?;
+
+    fs::remove_file(&path)
+        .with_context(|| format!("Failed to remove {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESOLVED: &str = r#"{"event":"resolved","info":{"id":"golang.go","marketplace_url":"https://marketplace.visualstudio.com/items?itemName=golang.go","direct_download_url":"https://example.com/golang.go.vsix","download_path":"downloads/golang.go-0.41.0.vsix","file_name":"golang.go-0.41.0.vsix","version":"0.41.0","timestamp":"2024-06-01T00:00:00Z","success":false}}"#;
+
+    /// 테스트마다 다른 임시 디렉토리의 다운로드 정보 파일 경로
+    fn downloads_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vsix-downloader-journal-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("downloads.json")
+    }
+
+    #[test]
+    fn replays_events_in_order() {
+        let path = downloads_path("order");
+        let lines = [
+            RESOLVED,
+            r#"{"event":"downloading","id":"golang.go","timestamp":"2024-06-01T00:00:01Z"}"#,
+            r#"{"event":"failed","id":"golang.go","timestamp":"2024-06-01T00:00:02Z","error":"network_timeout","quarantine_path":null}"#,
+            r#"{"event":"downloading","id":"golang.go","timestamp":"2024-06-01T00:00:03Z"}"#,
+            r#"{"event":"done","id":"golang.go","timestamp":"2024-06-01T00:00:04Z","size":542,"sha256":"abc","target_platform":null,"duration_ms":10,"bytes_per_second":54200}"#,
+        ];
+        fs::write(journal_path(&path), format!("{}\n", lines.join("\n"))).unwrap();
+
+        let mut downloads = Vec::new();
+        replay(&mut downloads, &path).unwrap();
+
+        assert_eq!(downloads.len(), 1);
+        let download = &downloads[0];
+        assert!(download.success);
+        assert_eq!(download.size, Some(542));
+        assert_eq!(download.sha256.as_deref(), Some("abc"));
+        assert_eq!(download.error, None);
+        assert_eq!(download.timestamp, "2024-06-01T00:00:04Z");
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn ignores_truncated_last_line() {
+        let path = downloads_path("truncated");
+        let failed = r#"{"event":"failed","id":"golang.go","timestamp":"2024-06-01T00:00:02Z","error":"network","quarantine_path":null}"#;
+        fs::write(journal_path(&path), format!("{}\n{}\n{{\"event\":\"done\",\"id\":\"gol", RESOLVED, failed)).unwrap();
+
+        let mut downloads = Vec::new();
+        replay(&mut downloads, &path).unwrap();
+
+        assert_eq!(downloads.len(), 1);
+        assert!(!downloads[0].success);
+        assert_eq!(downloads[0].error, Some(ErrorCode::Network));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn matches_ids_ignoring_case() {
+        let path = downloads_path("case");
+        let lines = [
+            RESOLVED,
+            r#"{"event":"done","id":"golang.go","timestamp":"2024-06-01T00:00:01Z","size":542,"sha256":"abc","target_platform":null,"duration_ms":null,"bytes_per_second":null}"#,
+            &RESOLVED.replace("\"golang.go\"", "\"GoLang.Go\""),
+            r#"{"event":"downloading","id":"GOLANG.GO","timestamp":"2024-06-01T00:00:02Z"}"#,
+            r#"{"event":"done","id":"Golang.go","timestamp":"2024-06-01T00:00:03Z","size":600,"sha256":"def","target_platform":null,"duration_ms":null,"bytes_per_second":null}"#,
+        ];
+        fs::write(journal_path(&path), format!("{}\n", lines.join("\n"))).unwrap();
+
+        let mut downloads = Vec::new();
+        replay(&mut downloads, &path).unwrap();
+
+        assert_eq!(downloads.len(), 1);
+        let download = &downloads[0];
+        assert_eq!(download.id, "GoLang.Go");
+        assert!(download.success);
+        assert_eq!(download.size, Some(600));
+        assert_eq!(download.sha256.as_deref(), Some("def"));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn cached_downloads_follow_appends_until_compacted() {
+        let path = downloads_path("cache");
+        let resolved: Event = serde_json::from_str(RESOLVED).unwrap();
+        append(&path, &resolved).unwrap();
+        let success = |downloads: &[DownloadInfo]| Ok(downloads.iter().map(|d| d.success).collect::<Vec<_>>());
+        assert_eq!(with_downloads(&path, ResultsFormat::Json, success).unwrap(), [false]);
+
+        // 한 번 읽은 뒤에는 파일이 아니라 덧붙인 변경으로 갱신됨
+        fs::remove_file(journal_path(&path)).unwrap();
+        append(&path, &Event::Done {
+            id: "golang.go".to_string(),
+            timestamp: "2024-06-01T00:00:01Z".to_string(),
+            size: 542,
+            sha256: "abc".to_string(),
+            target_platform: None,
+            duration_ms: None,
+            bytes_per_second: None,
+            origin: None,
+        }).unwrap();
+        assert_eq!(with_downloads(&path, ResultsFormat::Json, success).unwrap(), [true]);
+
+        // 압축하면 다음 읽기에서 파일을 다시 읽음
+        fs::remove_file(journal_path(&path)).unwrap();
+        compact(&path, ResultsFormat::Json).unwrap();
+        assert!(with_downloads(&path, ResultsFormat::Json, success).unwrap().is_empty());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn missing_journal_is_empty() {
+        let path = downloads_path("missing");
+        let mut downloads = Vec::new();
+        replay(&mut downloads, &path).unwrap();
+        assert!(downloads.is_empty());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
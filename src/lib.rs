@@ -0,0 +1,6 @@
+//! 결과 파일과 다운로드 정보 파일을 읽는 자동화 도구가 같은 오류 코드 정의를 쓸 수 있도록 공개하는 라이브러리 API
+//! (`vsix_downloader::error::ErrorCode`, `vsix_downloader::VsixError`)
+
+pub mod error;
+
+pub use error::{ErrorCode, VsixError};
@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::error::VsixError;
+use crate::vsix;
+use crate::{DownloadInfo, DownloadedFile};
+
+/// 잠금 파일에 기록된 확장 프로그램 버전 하나의 내용 해시
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LockEntry {
+    id: String,
+    version: String,
+    /// 플랫폼별 빌드의 대상 플랫폼 (범용 패키지는 없음)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target_platform: Option<String>,
+    sha256: String,
+    size: u64,
+    /// 이 해시를 처음 기록한 시각
+    recorded: String,
+}
+
+impl LockEntry {
+    fn matches(&self, id: &str, version: &str, target_platform: Option<&str>) -> bool {
+        self.id.eq_ignore_ascii_case(id) && self.version == version && self.target_platform.as_deref() == target_platform
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Lockfile {
+    extensions: Vec<LockEntry>,
+}
+
+/// 잠금 파일을 읽습니다. 파일이 없으면 빈 잠금 파일로 봅니다.
+fn load(path: &Path) -> Result<Lockfile> {
+    if !path.exists() {
+        return Ok(Lockfile::default());
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read lockfile {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse lockfile {}", path.display()))
+}
+
+/// 잠금 파일에 기록된 확장 프로그램별 버전과 처음 기록한 시각. 플랫폼별 항목은 하나로 합칩니다.
+pub fn locked_versions(path: &Path) -> Result<Vec<(String, String, String)>> {
+    let mut versions: Vec<(String, String, String)> = Vec::new();
+    for entry in load(path)?.extensions {
+        if !versions.iter().any(|(id, version, _)| id.eq_ignore_ascii_case(&entry.id) && *version == entry.version) {
+            versions.push((entry.id, entry.version, entry.recorded));
+        }
+    }
+    Ok(versions)
+}
+
+/// 받은 VSIX의 ID, 버전, 대상 플랫폼 (vsixmanifest를 읽을 수 없는 오래된 패키지는 다운로드 정보의 값)
+fn identity(download_info: &DownloadInfo) -> Option<(String, String, Option<String>)> {
+    match vsix::read_identity(Path::new(&download_info.download_path)) {
+        Ok(identity) => Some((identity.id(), identity.version, identity.target_platform)),
+        Err(_) => Some((download_info.id.clone(), download_info.version.clone()?, download_info.target_platform.clone())),
+    }
+}
+
+/// 같은 버전을 다시 받았으면 잠금 파일에 기록된 해시와 같은지 확인합니다.
+/// 버전이 같은데 내용이 바뀌었다면 레지스트리나 CDN이 변조됐을 수 있으므로 오류로 처리합니다.
+pub fn verify(path: &Path, download_info: &DownloadInfo, downloaded: &DownloadedFile) -> Result<()> {
+    let lockfile = load(path)?;
+    let Some((id, version, target_platform)) = identity(download_info) else {
+        return Ok(());
+    };
+
+    match lockfile.extensions.iter().find(|e| e.matches(&id, &version, target_platform.as_deref())) {
+        Some(entry) if entry.sha256 != downloaded.sha256 => Err(VsixError::HashMismatch {
+            id: format!("{}@{}", id, version),
+            expected: entry.sha256.clone(),
+            actual: downloaded.sha256.clone(),
+        }.into()),
+        _ => Ok(()),
+    }
+}
+
+/// 이번 실행에서 받은 확장 프로그램의 해시를 잠금 파일에 더합니다.
+/// 이미 기록된 버전의 해시는 바꾸지 않고, 같은 확장 프로그램의 이전 버전 항목은 새 버전으로 교체합니다.
+pub fn update(path: &Path, downloads: &[&DownloadInfo]) -> Result<()> {
+    let mut lockfile = load(path)?;
+    let mut added = 0;
+
+    for download in downloads.iter().filter(|d| d.success) {
+        let (Some(sha256), Some(size)) = (&download.sha256, download.size) else {
+            continue;
+        };
+        let Some((id, version, target_platform)) = identity(download) else {
+            continue;
+        };
+
+        if lockfile.extensions.iter().any(|e| e.matches(&id, &version, target_platform.as_deref())) {
+            continue;
+        }
+
+        lockfile.extensions.retain(|e| !(e.id.eq_ignore_ascii_case(&id) && e.target_platform == target_platform));
+        lockfile.extensions.push(LockEntry {
+            id,
+            version,
+            target_platform,
+            sha256: sha256.clone(),
+            size,
+            recorded: Utc::now().to_rfc3339(),
+        });
+        added += 1;
+    }
+
+    if added == 0 && path.exists() {
+        return Ok(());
+    }
+
+    lockfile.extensions.sort_by(|a, b| a.id.to_lowercase().cmp(&b.id.to_lowercase()).then(a.target_platform.cmp(&b.target_platform)));
+
+    let serialized = serde_json::to_string_pretty(&lockfile)
+        .with_context(|| "Failed to serialize lockfile")?;
+    fs::write(path, serialized)
+        .with_context(|| format!("Failed to write lockfile {}", path.display()))?;
+
+    println!("{}", format!("잠금 파일에 {}개 버전의 해시를 기록했습니다: {}", added, path.display()).green());
+    Ok(())
+}
@@ -1,464 +1,3792 @@
+use std::cmp::Ordering;
+use std::future::Future;
+use std::sync::OnceLock;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use std::fs::{self, create_dir_all, File};
 use std::io::Write;
 
 use anyhow::{Result, Context, anyhow};
 use chrono::Utc;
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
 use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::Client;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget};
 use serde::{Deserialize, Serialize};
-use futures_util::StreamExt;
+use futures_util::{Stream, StreamExt};
+use sha2::{Digest, Sha256};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+mod assets;
+mod auth;
+mod bundle;
+mod check_cache;
+mod config;
+mod crosscheck;
+mod daemon;
+mod doctor;
+mod duration;
+mod encoding;
+mod export;
+mod filename;
+mod filter;
+mod history;
+mod hooks;
+mod http;
+mod input;
+mod inspect;
+mod installed;
+mod journal;
+mod lockfile;
+mod marketplace;
+mod mirror;
+mod notify;
+mod output;
+mod paths;
+mod plain;
+mod platform;
+mod preflight;
+mod profile;
+mod provision;
+mod publisher;
+mod quarantine;
+mod resolver;
+mod report;
+mod restore;
+mod resume;
+mod run_dir;
+mod run_meta;
+mod scan;
+mod search;
+mod segmented;
+mod self_update;
+mod service;
+mod shard;
+mod size;
+mod snapshot;
+mod status;
+mod status_file;
+mod suggest;
+mod validation;
+mod version;
+mod vscode_server;
+mod vsix;
+mod warnings;
+
+// 오류 코드는 라이브러리 API로도 공개 (src/lib.rs)
+use vsix_downloader::error::{self, ErrorCode, VsixError};
+use http::{HttpClient, HttpMode};
+use output::ResultsFormat;
+use preflight::PreflightPolicy;
+use publisher::PublisherInfo;
+use resume::{SyncState, WorkStatus, WorkTracker};
+use run_meta::RunMetadata;
+use warnings::WarningKind;
 
 const OPEN_VSX_API: &str = "https://open-vsx.org/api";
 const VSCODE_MARKETPLACE_URL: &str = "https://marketplace.visualstudio.com/items";
 
-#[derive(Parser)]
-#[command(author, version, about = "VSCode 확장 프로그램을 Open VSX에서 검색하고 VSCode Marketplace에서 다운로드하는 도구")]
-struct Cli {
-    #[command(subcommand)]
-    command: Commands,
+#[derive(Parser)]
+#[command(author, version, about = "VSCode 확장 프로그램을 Open VSX에서 검색하고 VSCode Marketplace에서 다운로드하는 도구")]
+#[command(disable_help_subcommand = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+    
+    /// 진행률 표시바와 색 없이 다운로드 결과를 한 줄씩 출력 (화면 낭독기, 단순 터미널용)
+    #[arg(long, global = true)]
+    plain: bool,
+    
+    /// 진행률 표시바의 스피너 등을 ASCII 문자로만 출력 (유니코드가 깨지는 Windows 터미널, 시리얼 콘솔용)
+    #[arg(long, global = true)]
+    ascii: bool,
+    
+    /// 모든 요청에 보낼 User-Agent (식별용 User-Agent를 요구하는 프록시용)
+    #[arg(long, global = true, value_name = "AGENT")]
+    user_agent: Option<String>,
+    
+    /// 모든 요청에 붙일 헤더 (예: "X-Proxy-Client: build-01", 여러 번 지정 가능)
+    #[arg(long = "header", global = true, value_name = "HEADER", value_parser = http::parse_header)]
+    headers: Vec<(String, String)>,
+    
+    /// 호스트를 DNS 대신 지정한 IP로 연결 (curl 형식 "host:ip", 여러 번 지정 가능)
+    #[arg(long, global = true, value_name = "HOST:IP", value_parser = http::parse_resolve)]
+    resolve: Vec<(String, std::net::IpAddr)>,
+}
+
+impl Cli {
+    /// `--user-agent`, `--header`, `--resolve`를 이후에 만드는 모든 HTTP 클라이언트에 적용합니다.
+    fn apply_client_settings(&self) -> Result<()> {
+        if !self.resolve.is_empty() {
+            http::set_resolve_overrides(&self.resolve);
+        }
+        if self.user_agent.is_none() && self.headers.is_empty() {
+            return Ok(());
+        }
+        http::set_default_headers(self.user_agent.as_deref(), &self.headers)
+    }
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// YAML 파일에서 확장 프로그램을 확인하고 VSCode Marketplace에서 다운로드합니다
+    #[command(group(ArgGroup::new("list").args(["file", "profile", "all_profiles"])))]
+    #[command(mut_arg("ids", |arg| arg.required_unless_present_any(["file", "profile", "all_profiles"])))]
+    Sync(SyncArgs),
+    
+    /// 중단된 동기화를 같은 옵션으로 이어서 실행합니다 (확인과 다운로드가 끝난 항목은 건너뜀)
+    Resume(ResumeArgs),
+    
+    /// 이전 결과 파일에서 다운로드하지 못한 항목만 다시 확인하고 다운로드해 결과에 합칩니다
+    #[command(mut_arg("file", |arg| arg.required(false).hide(true)))]
+    #[command(mut_arg("ids", |arg| arg.hide(true)))]
+    RetryFailed(SyncArgs),
+    
+    /// 여러 확장 프로그램 목록을 중복 없는 하나의 YAML 목록으로 합칩니다
+    Merge(MergeArgs),
+    
+    /// --shard로 나눠 실행한 결과 파일과 다운로드 정보 파일을 하나로 합칩니다
+    MergeResults(MergeResultsArgs),
+    
+    /// 로컬 VSCode에 설치된 확장 프로그램 목록을 시각별 YAML 스냅샷으로 저장합니다
+    Snapshot(SnapshotArgs),
+    
+    /// 스냅샷 목록과 다운로드 디렉토리의 VSIX로 설치된 확장 프로그램 구성을 복원합니다
+    Restore(RestoreArgs),
+    
+    /// 게시자가 Marketplace에 올린 모든 확장 프로그램의 최신 버전을 다운로드합니다
+    #[command(mut_arg("file", |arg| arg.required(false).hide(true)))]
+    MirrorPublisher(MirrorPublisherArgs),
+    
+    /// 설치 수가 많은 확장 프로그램을 최신 버전으로 다운로드합니다 (카테고리 지정 가능)
+    #[command(mut_arg("file", |arg| arg.required(false).hide(true)))]
+    MirrorTop(MirrorTopArgs),
+    
+    /// 컨테이너에서 미러를 주기적으로 동기화하고 상태 확인 엔드포인트(/healthz, /readyz)를 제공합니다
+    #[command(group(ArgGroup::new("list").required(true).args(["file", "profile", "all_profiles"])))]
+    #[command(subcommand_precedence_over_arg = true)]
+    Daemon(DaemonArgs),
+    
+    /// 다운로드한 확장 프로그램을 원격 개발 서버에 설치합니다
+    Provision {
+        #[command(subcommand)]
+        target: ProvisionTarget,
+    },
+    
+    /// 목록의 확장 프로그램별 설치 상태, 버전, VSIX 유무를 출력합니다
+    Status(StatusArgs),
+    
+    /// 비공개 갤러리와 Open VSX 토큰을 OS 키링에 저장하거나 지웁니다
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+    
+    /// 레지스트리 연결(DNS, TLS), 프록시와 CA 설정, 출력 경로 쓰기 권한을 진단하고 해결 방법을 출력합니다
+    Doctor(DoctorArgs),
+    
+    /// 동기화 실행 기록으로 업데이트 빈도, 실행 시간, 월별 다운로드량, 실패가 잦은 확장 프로그램을 출력합니다
+    Stats(StatsArgs),
+    
+    /// 목록 전체에 대한 보고서를 출력합니다
+    Report {
+        #[command(subcommand)]
+        kind: ReportKind,
+    },
+    
+    /// 다운로드 디렉토리의 VSIX를 망 분리 환경으로 옮길 tar.gz 번들로 묶습니다 (--since로 변경분만)
+    Bundle(BundleArgs),
+    
+    /// bundle로 만든 번들(전체 또는 변경분)을 기존 다운로드 디렉토리에 덧씌웁니다
+    Import(ImportArgs),
+    
+    /// 다운로드 디렉토리를 다른 도구가 바로 쓸 수 있는 형식으로 내보냅니다
+    Export {
+        #[command(subcommand)]
+        format: ExportFormat,
+    },
+    
+    /// VSIX 파일을 디렉토리 구조 그대로 풉니다
+    Extract {
+        /// 풀 VSIX 파일
+        file: PathBuf,
+        
+        /// 풀 디렉토리 (기본값: VSIX 파일 이름에서 확장자를 뺀 디렉토리)
+        #[arg(short, long)]
+        output_dir: Option<PathBuf>,
+    },
+    
+    /// VSIX의 매니페스트(ID, 버전, 엔진 범위, 대상 플랫폼, 의존성, 활성화 이벤트, 기여 항목)를 출력합니다
+    Inspect(InspectArgs),
+    
+    /// VSCode Marketplace에서 검색어로 확장 프로그램을 찾습니다 (정렬과 페이지 지정 가능)
+    Search(SearchArgs),
+    
+    /// GitHub 릴리스에서 최신 버전을 받아 현재 실행 파일을 교체합니다
+    SelfUpdate {
+        /// 새 버전이 있는지만 확인하고 설치하지 않음
+        #[arg(long, default_value_t = false)]
+        check: bool,
+    },
+    
+    /// 셸 자동 완성 스크립트를 표준 출력으로 생성합니다
+    Completions {
+        /// 대상 셸 (bash, zsh, fish, powershell, elvish)
+        shell: Shell,
+    },
+    
+    /// man 페이지를 생성합니다 (기본: 표준 출력)
+    Manpage {
+        /// 지정하면 모든 하위 명령의 man 페이지를 이 디렉토리에 생성
+        #[arg(short, long)]
+        out_dir: Option<PathBuf>,
+    },
+    
+    /// 명령 도움말을 출력합니다
+    Help {
+        /// 모든 하위 명령의 도움말을 트리 형태로 출력
+        #[arg(long, default_value_t = false)]
+        all: bool,
+        
+        /// 도움말을 볼 하위 명령 경로 (예: sync)
+        command: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProvisionTarget {
+    /// 다운로드 디렉토리의 VSIX(확장 프로그램마다 가장 높은 버전)를 code-server 확장 프로그램 디렉토리에 설치합니다
+    CodeServer(CodeServerArgs),
+}
+
+#[derive(Subcommand)]
+enum AuthAction {
+    /// 표준 입력으로 받은 토큰을 OS 키링에 저장합니다 (이후 레지스트리 요청에 자동으로 사용)
+    Login {
+        /// 토큰을 사용할 레지스트리
+        registry: auth::Registry,
+    },
+    
+    /// OS 키링에서 토큰을 지웁니다
+    Logout {
+        /// 토큰을 지울 레지스트리
+        registry: auth::Registry,
+    },
+    
+    /// 레지스트리마다 OS 키링에 토큰이 저장되어 있는지 출력합니다
+    Status,
+}
+
+#[derive(Subcommand)]
+enum ReportKind {
+    /// 확장 프로그램별 최소 요구 VSCode 버전을 출력하고 지원 대상 버전에서 동작하지 않는 항목을 강조합니다
+    Engines(EngineReportArgs),
+    
+    /// 다운로드 디렉토리의 VSIX를 크기가 큰 순으로 누적 합계와 함께 출력합니다
+    Sizes(SizeReportArgs),
+    
+    /// 미러한 확장 프로그램이 레지스트리의 최신 버전보다 며칠째 뒤처져 있는지 오래된 순으로 출력합니다
+    Freshness(FreshnessReportArgs),
+}
+
+#[derive(Subcommand)]
+enum ExportFormat {
+    /// code-server와 openvscode-server가 확장 프로그램 갤러리로 쓸 수 있는 정적 색인과 파일 배치를 만듭니다
+    CodeServerGallery(CodeServerGalleryArgs),
+}
+
+#[derive(Args, Clone)]
+struct SyncArgs {
+    /// 확장 프로그램 목록 파일 경로 또는 http(s) URL (YAML, 한 줄에 ID 하나인 텍스트, ID 배열 JSON).
+    /// VSCode 원격 서버의 확장 프로그램 디렉토리(`~/.vscode-server/extensions`)나 그 tar(.gz) 파일도 받습니다.
+    /// 여러 번 지정하거나 와일드카드(`teams/*.yml`, `**`)로 지정하면 합쳐지며, 같은 ID는 나중에 읽은 파일의 항목이 우선합니다
+    #[arg(short, long)]
+    file: Vec<PathBuf>,
+    
+    /// 목록 파일에 더할 확장 프로그램 (`publisher.name`, `publisher.name@버전` 또는 페이지 URL).
+    /// 목록 파일을 고치지 않고 한 번만 더 받을 때 쓰며, 목록에 같은 ID가 있으면 이 값이 우선합니다
+    #[arg(value_name = "ID")]
+    ids: Vec<String>,
+    
+    /// 합친 목록에서 뺄 확장 프로그램 목록 파일 (형식은 --file과 같고 버전은 무시, 여러 번 지정 가능).
+    /// 공용 목록을 쓰면서 이 컴퓨터에서만 무거운 확장 프로그램을 뺄 때 사용
+    #[arg(long, value_name = "PATH")]
+    exclude_file: Vec<PathBuf>,
+    
+    /// 프로필을 정의한 설정 파일
+    #[arg(long, value_name = "PATH", default_value = profile::CONFIG_FILE)]
+    config: PathBuf,
+    
+    /// 설정 파일에 정의된 이 프로필의 목록 파일, 다운로드 디렉토리, 소스, 필터로 동기화
+    #[arg(long, value_name = "NAME", conflicts_with = "all_profiles")]
+    profile: Option<String>,
+    
+    /// 설정 파일에 정의된 모든 프로필을 차례로 동기화 (실패한 프로필이 있어도 나머지를 계속 진행)
+    #[arg(long, default_value_t = false)]
+    all_profiles: bool,
+    
+    /// 적용된 프로필 이름 (--profile, --all-profiles를 풀어 설정)
+    #[arg(skip)]
+    active_profile: Option<String>,
+    
+    /// 설정 파일의 `hooks`에 정의된 외부 명령
+    #[arg(skip)]
+    hooks: hooks::Hooks,
+    
+    /// 설정 파일의 `resolvers`에 등록된 외부 레지스트리 (Open VSX에 없는 항목을 Marketplace보다 먼저 확인)
+    #[arg(skip)]
+    resolvers: Vec<resolver::ResolverPlugin>,
+    
+    /// 원격 목록 파일을 가져올 때 붙일 헤더 (예: "Authorization: token ...")
+    #[arg(long, value_name = "HEADER", value_parser = http::parse_header)]
+    file_header: Option<(String, String)>,
+    
+    /// 목록 파일의 알 수 없는 필드를 오류 대신 경고로 처리
+    #[arg(long, default_value_t = false)]
+    allow_unknown_fields: bool,
+    
+    /// VSCode 내장 확장 프로그램(vscode.git 같은 vscode.*)도 확인 (기본값은 건너뜀)
+    #[arg(long, default_value_t = false)]
+    include_builtin: bool,
+    
+    /// 결과를 저장할 파일 경로 (기본값: results.<형식 확장자>)
+    #[arg(short = 'r', long, visible_alias = "results")]
+    output: Option<PathBuf>,
+    
+    /// 기존 결과 파일을 지우지 않고 이번 실행 결과를 ID 기준으로 합침
+    /// (마지막 성공 시각과 버전 변경 기록 유지, CSV 형식에서는 사용 불가)
+    #[arg(long, default_value_t = false)]
+    append_results: bool,
+    
+    /// 결과 파일과 다운로드 정보 파일 형식 (csv는 결과 파일에만 적용되고 다운로드 정보는 JSON으로 저장)
+    #[arg(long, value_enum, default_value_t = ResultsFormat::Json)]
+    results_format: ResultsFormat,
+    
+    /// 다운로드 디렉토리 (기본값: ./downloads)
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// 실행마다 다운로드 디렉토리 아래 시각별 디렉토리(예: 2024-06-01T03-00)에 결과와 다운로드를 저장하고
+    /// `latest` 링크가 마지막으로 성공한 실행을 가리키게 함
+    #[arg(long, default_value_t = false)]
+    run_dirs: bool,
+    
+    /// 다운로드 정보 파일을 둘 디렉토리 (--run-dirs에서 실행 디렉토리로 설정, 없으면 현재 디렉토리)
+    #[arg(skip)]
+    state_dir: Option<PathBuf>,
+    
+    /// 목록 파일 대신 처리할 확장 프로그램 (retry-failed의 실패 항목, mirror-* 명령으로 찾은 확장 프로그램)
+    #[arg(skip)]
+    listed_extensions: Option<Vec<Extension>>,
+    
+    /// Open VSX 확인 없이 모든 항목을 Marketplace에서 다운로드
+    /// (mirror-publisher, mirror-top과 Open VSX에 연결할 수 없을 때의 --preflight fallback에서 설정)
+    #[arg(skip)]
+    marketplace_only: bool,
+    
+    /// Marketplace에 연결할 수 없어 Open VSX 확인만 함 (--preflight fallback에서 설정)
+    #[arg(skip)]
+    marketplace_unreachable: bool,
+    
+    /// 시작할 때 Open VSX와 Marketplace에 연결할 수 없으면 어떻게 할지
+    #[arg(long, value_enum, default_value_t = PreflightPolicy::Fail)]
+    preflight: PreflightPolicy,
+    
+    /// Marketplace 토큰을 읽을 파일 (OS 키링에 저장된 토큰보다 우선)
+    #[arg(long, value_name = "PATH")]
+    marketplace_token_file: Option<PathBuf>,
+    
+    /// Open VSX 토큰을 읽을 파일 (OS 키링에 저장된 토큰보다 우선)
+    #[arg(long, value_name = "PATH")]
+    openvsx_token_file: Option<PathBuf>,
+    
+    /// 표준 입력 첫 줄에서 이 레지스트리의 토큰을 읽음 (명령줄 인자에 토큰이 드러나지 않도록 CI에서 사용)
+    #[arg(long, value_enum, value_name = "REGISTRY")]
+    token_stdin: Option<auth::Registry>,
+    
+    /// 확인 없이 자동으로 다운로드 실행
+    #[arg(short, long, default_value_t = false)]
+    auto_download: bool,
+    
+    /// 확인과 다운로드를 합쳐 동시에 진행할 최대 작업 수
+    #[arg(long, value_name = "N", default_value_t = 8, visible_alias = "check-concurrency")]
+    concurrency: usize,
+    
+    /// 받은 VSIX의 압축, 매니페스트, 해시를 동시에 검사할 최대 파일 수 (기본값: CPU 수)
+    #[arg(long, value_name = "N", default_value_t = validation::default_jobs())]
+    verify_jobs: usize,
+    
+    /// 확인이 끝난 확장 프로그램을 기다리지 않고 바로 다운로드 (-a 필요)
+    #[arg(long, default_value_t = false, requires = "auto_download")]
+    pipeline: bool,
+    
+    /// 목록을 N개씩 나눠 묶음마다 확인과 다운로드를 마치고 결과 파일을 기록 (-a 필요, 수천 개 규모의 미러용)
+    #[arg(long, value_name = "N", requires = "auto_download", conflicts_with = "pipeline", value_parser = clap::value_parser!(u64).range(1..))]
+    batch_size: Option<u64>,
+    
+    /// 목록을 ID 해시로 나눠 이 작업이 맡은 부분만 처리 (예: 2/5, 여러 CI 작업에서 나눠 실행)
+    #[arg(long, value_name = "K/N")]
+    shard: Option<shard::Shard>,
+    
+    /// 동기화가 끝나거나 실패하면 데스크톱 알림 표시
+    #[arg(long, default_value_t = false)]
+    notify_desktop: bool,
+    
+    /// 레지스트리 응답을 이 디렉토리에 기록
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+    
+    /// 네트워크 대신 이 디렉토리에 기록된 응답을 재생
+    #[arg(long, value_name = "DIR")]
+    replay: Option<PathBuf>,
+    
+    /// 확장 프로그램 하나의 최대 다운로드 크기 (예: 300MiB, 1GB)
+    #[arg(long, value_name = "SIZE", value_parser = size::parse_size)]
+    max_size: Option<u64>,
+    
+    /// 다운로드 디렉토리를 지우지 않고, 이미 있는 VSIX의 extension.vsixmanifest 버전이
+    /// 받을 버전과 같으면 다시 받지 않음
+    #[arg(long, default_value_t = false)]
+    incremental: bool,
+    
+    /// 이 도구가 만든 표시 파일(.vsix-downloader-output)이 없는 다운로드 디렉토리도 지우고 다시 받음
+    #[arg(long, default_value_t = false)]
+    force: bool,
+    
+    /// Open VSX 확인 결과를 이 기간 동안 캐시(check-cache.jsonl)해 다시 확인하지 않음 (예: 12h, 1d)
+    #[arg(long, value_name = "DURATION", value_parser = duration::parse_duration)]
+    check_ttl: Option<Duration>,
+    
+    /// 큰 VSIX(8MiB 이상)를 이 개수의 바이트 범위로 나눠 동시에 받음 (서버가 범위 요청을 지원하지 않으면 단일 연결)
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    segments: usize,
+    
+    /// 최대 크기를 넘는 다운로드 처리 방식
+    #[arg(long, value_enum, default_value_t = SizeLimitPolicy::Skip)]
+    max_size_policy: SizeLimitPolicy,
+    
+    /// 플랫폼별 빌드가 있을 때 받을 대상 플랫폼 (예: linux-x64, darwin-arm64, win32-x64).
+    /// 생략하거나 host를 지정하면 현재 실행 중인 OS/아키텍처의 빌드를 받음
+    #[arg(long, value_name = "PLATFORM")]
+    target: Option<String>,
+    
+    /// 확장 프로그램 하나라도 확인이나 다운로드에 실패하면 즉시 중단
+    /// (기본값은 실패를 기록하고 나머지를 계속 처리)
+    #[arg(long, default_value_t = false)]
+    strict: bool,
+    
+    /// --strict처럼 첫 실패에서 중단하되, 진행 중인 확인과 다운로드를 모두 취소하고
+    /// 작업 상태를 정리한 뒤 종료 (resume으로 이어서 실행 가능)
+    #[arg(long, default_value_t = false)]
+    fail_fast: bool,
+    
+    /// 대기열, 진행 중인 다운로드와 진행률, 완료 수를 몇 초마다 기록할 JSON 파일 (외부 대시보드용)
+    #[arg(long, value_name = "PATH")]
+    status_file: Option<PathBuf>,
+    
+    /// 중단 오류가 나면 진행 중인 작업을 취소하는 신호 (프로필 동기화마다 새로 만듦)
+    #[arg(skip)]
+    cancel: CancellationToken,
+    
+    /// Open VSX에서는 확인된(verified) 네임스페이스의 확장 프로그램만 받고, 나머지는 Marketplace에서 받음
+    #[arg(long, default_value_t = false)]
+    openvsx_verified_only: bool,
+    
+    /// 확인된 게시자(Open VSX 네임스페이스 확인, Marketplace 도메인 확인)의 확장 프로그램만 받고
+    /// 나머지는 policy_skipped로 기록
+    #[arg(long, default_value_t = false)]
+    verified_publishers_only: bool,
+    
+    /// --verified-publishers-only에서 확인 여부와 관계없이 받을 게시자 (여러 번 지정 가능, 쉼표로 구분)
+    #[arg(long, value_name = "PUBLISHER", value_delimiter = ',', requires = "verified_publishers_only")]
+    trusted_publisher: Vec<String>,
+    
+    /// Marketplace에서 받은 VSIX의 SHA-256을 Open VSX에 게시된 같은 버전의 해시와 비교
+    #[arg(long, default_value_t = false)]
+    cross_check_openvsx: bool,
+    
+    /// VSIX와 함께 받을 자산 종류 (쉼표로 구분, all이면 전부). VSIX 외의 자산은
+    /// 다운로드 디렉토리의 assets/<ID>/에 저장
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "KINDS", default_value = "vsix")]
+    assets: Vec<assets::AssetKind>,
+    
+    /// 받은 버전의 SHA-256을 기록하는 잠금 파일. 이미 기록된 버전을 다시 받았는데 해시가 다르면
+    /// 파일을 격리하고 실패로 종료
+    #[arg(long, value_name = "PATH")]
+    lockfile: Option<PathBuf>,
+    
+    /// 받은 VSIX를 보내 악성 코드를 검사할 서버 (clamd://host:3310, clamd:///run/clamav/clamd.ctl,
+    /// icap://host:1344/avscan). 발견되거나 검사하지 못한 파일은 격리
+    #[arg(long, value_name = "URL")]
+    scan: Option<scan::Scanner>,
+    
+    /// 이전 실행에서 받은 확장 프로그램이 두 레지스트리 모두에서 사라졌으면 실패로 종료
+    #[arg(long, default_value_t = false)]
+    fail_on_removed: bool,
+    
+    /// 이 Marketplace 카테고리 중 하나에 속한 확장 프로그램만 처리 (여러 번 지정 가능, 예: Themes)
+    #[arg(long, value_name = "CATEGORY")]
+    category: Vec<String>,
+    
+    /// 이 카테고리에 속한 확장 프로그램은 제외 (여러 번 지정 가능)
+    #[arg(long, value_name = "CATEGORY")]
+    exclude_category: Vec<String>,
+    
+    /// 이 키워드(태그) 중 하나가 있는 확장 프로그램만 처리 (여러 번 지정 가능)
+    #[arg(long, value_name = "KEYWORD")]
+    keyword: Vec<String>,
+    
+    /// 이 키워드가 있는 확장 프로그램은 제외 (여러 번 지정 가능)
+    #[arg(long, value_name = "KEYWORD")]
+    exclude_keyword: Vec<String>,
+}
+
+#[derive(Args)]
+struct MirrorPublisherArgs {
+    /// 미러링할 게시자 이름 (예: ms-vscode)
+    publisher: String,
+    
+    #[command(flatten)]
+    sync: SyncArgs,
+}
+
+#[derive(Args)]
+struct MirrorTopArgs {
+    /// 다운로드할 확장 프로그램 수 (설치 수가 많은 순, --category를 지정하면 카테고리마다)
+    #[arg(long, value_name = "N", default_value_t = 100)]
+    count: usize,
+    
+    #[command(flatten)]
+    sync: SyncArgs,
+}
+
+#[derive(Args)]
+struct DaemonArgs {
+    /// 동기화 간격 (예: 6h, 1d)
+    #[arg(long, value_name = "DURATION", value_parser = duration::parse_duration, default_value = "1d")]
+    interval: Duration,
+    
+    /// 상태 확인 엔드포인트를 열 주소
+    #[arg(long, value_name = "ADDR", default_value = "0.0.0.0:8080")]
+    listen: std::net::SocketAddr,
+    
+    /// 마지막 동기화 성공(없으면 시작)이 이보다 오래되면 /healthz가 503을 반환 (예: 2d)
+    #[arg(long, value_name = "DURATION", value_parser = duration::parse_duration)]
+    stale_after: Option<Duration>,
+    
+    #[command(flatten)]
+    sync: SyncArgs,
+    
+    #[command(subcommand)]
+    action: Option<DaemonAction>,
+}
+
+#[derive(Subcommand)]
+enum DaemonAction {
+    /// 앞에 지정한 daemon 옵션으로 실행하는 systemd 유닛(Windows는 예약 작업)을 등록하고 시작합니다
+    InstallService(InstallServiceArgs),
+}
+
+#[derive(Args)]
+struct InstallServiceArgs {
+    /// 서비스(예약 작업) 이름
+    #[arg(long, default_value = "vsix-downloader")]
+    name: String,
+    
+    /// 시스템 서비스 대신 현재 사용자의 서비스로 등록 (systemd --user, Windows는 로그온 시 시작)
+    #[arg(long)]
+    user: bool,
+    
+    /// 서비스를 실행할 계정 (systemd User=, Windows /RU)
+    #[arg(long, value_name = "USER", conflicts_with = "user")]
+    run_as: Option<String>,
+    
+    /// 등록하지 않고 유닛 파일(Windows는 schtasks 명령)만 출력
+    #[arg(long)]
+    print: bool,
+}
+
+#[derive(Args)]
+struct ResumeArgs {
+    /// --profile이나 --all-profiles로 실행하다 중단된 이 프로필의 동기화를 이어서 실행 (profiles/<NAME>/sync-state.json)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+}
+
+#[derive(Args)]
+struct MergeArgs {
+    /// 합칠 목록 파일 경로 또는 http(s) URL (지정한 순서대로 합침)
+    #[arg(short, long, required = true)]
+    file: Vec<PathBuf>,
+    
+    /// 원격 목록 파일을 가져올 때 붙일 헤더 (예: "Authorization: token ...")
+    #[arg(long, value_name = "HEADER", value_parser = http::parse_header)]
+    file_header: Option<(String, String)>,
+    
+    /// 목록 파일의 알 수 없는 필드를 오류 대신 경고로 처리
+    #[arg(long, default_value_t = false)]
+    allow_unknown_fields: bool,
+    
+    /// 합친 목록을 저장할 YAML 파일 경로
+    #[arg(short, long)]
+    output: PathBuf,
+    
+    /// 같은 확장 프로그램이 서로 다른 버전으로 고정되어 있을 때의 처리 방식
+    #[arg(long, value_enum, default_value_t = ConflictPolicy::Error)]
+    on_conflict: ConflictPolicy,
+}
+
+#[derive(Args)]
+struct MergeResultsArgs {
+    /// 합칠 샤드별 결과 파일 (같은 디렉토리의 다운로드 정보 파일도 함께 합침)
+    #[arg(short, long = "results", required = true)]
+    results: Vec<PathBuf>,
+    
+    /// 합친 결과를 저장할 파일 경로 (다운로드 정보 파일은 같은 디렉토리에 저장)
+    #[arg(short, long)]
+    output: PathBuf,
+    
+    /// 결과 파일과 다운로드 정보 파일의 형식
+    #[arg(long, value_enum, default_value_t = ResultsFormat::Json)]
+    results_format: ResultsFormat,
+}
+
+#[derive(Args)]
+struct SnapshotArgs {
+    /// 스냅샷을 저장할 디렉토리
+    #[arg(short, long, default_value = "snapshots")]
+    dir: PathBuf,
+    
+    /// 보관할 최근 스냅샷 개수 (0이면 모두 보관)
+    #[arg(long, default_value_t = 30)]
+    keep: usize,
+    
+    /// 지정하면 종료하지 않고 이 간격마다 스냅샷을 저장 (예: 6h, 1d)
+    #[arg(long, value_name = "DURATION", value_parser = duration::parse_duration)]
+    interval: Option<Duration>,
+    
+    /// VSCode CLI 실행 파일 (Windows에서는 code.cmd)
+    #[arg(long, default_value = installed::DEFAULT_CODE_BIN)]
+    code_bin: String,
+}
+
+#[derive(Args)]
+struct RestoreArgs {
+    /// 복원할 스냅샷(목록) YAML 파일
+    #[arg(short, long)]
+    file: PathBuf,
+    
+    /// VSIX 파일이 있는 다운로드 디렉토리
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// 목록에 없는 설치된 확장 프로그램을 제거하지 않음
+    #[arg(long, default_value_t = false)]
+    keep_extras: bool,
+    
+    /// 실제로 설치하거나 제거하지 않고 할 일만 출력
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+    
+    /// VSCode CLI 실행 파일 (Windows에서는 code.cmd)
+    #[arg(long, default_value = installed::DEFAULT_CODE_BIN)]
+    code_bin: String,
+}
+
+#[derive(Args)]
+struct CodeServerArgs {
+    /// VSIX 파일이 있는 다운로드 디렉토리
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// code-server 확장 프로그램 디렉토리 (예: /srv/code-server/extensions)
+    #[arg(long)]
+    extensions_dir: PathBuf,
+    
+    /// 설치 방법 (extract: VSIX를 직접 풀어 설치, cli: code-server CLI로 설치)
+    #[arg(long, value_enum, default_value_t = provision::ProvisionMethod::Extract)]
+    method: provision::ProvisionMethod,
+    
+    /// code-server 실행 파일 (--method cli)
+    #[arg(long, default_value = "code-server")]
+    code_server_bin: String,
+    
+    /// 실제로 설치하지 않고 할 일만 출력
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+}
+
+#[derive(Args)]
+struct StatusArgs {
+    /// 확장 프로그램 목록 파일 경로 또는 http(s) URL (여러 번 지정 가능)
+    #[arg(short, long, required = true)]
+    file: Vec<PathBuf>,
+    
+    /// 원격 목록 파일을 가져올 때 붙일 헤더 (예: "Authorization: token ...")
+    #[arg(long, value_name = "HEADER", value_parser = http::parse_header)]
+    file_header: Option<(String, String)>,
+    
+    /// 목록 파일의 알 수 없는 필드를 오류 대신 경고로 처리
+    #[arg(long, default_value_t = false)]
+    allow_unknown_fields: bool,
+    
+    /// VSIX 파일이 있는 다운로드 디렉토리
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// VSCode CLI 실행 파일 (Windows에서는 code.cmd)
+    #[arg(long, default_value = installed::DEFAULT_CODE_BIN)]
+    code_bin: String,
+}
+
+#[derive(Args)]
+struct DoctorArgs {
+    /// 쓰기 권한을 확인할 다운로드 디렉토리
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// 쓰기 권한을 확인할 결과 파일 경로
+    #[arg(short = 'r', long, visible_alias = "results")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct StatsArgs {
+    /// 동기화 실행 기록 파일
+    #[arg(long, default_value = history::HISTORY_FILE)]
+    history: PathBuf,
+    
+    /// 업데이트와 실패 순위에 보여줄 확장 프로그램 수
+    #[arg(long, default_value_t = 10)]
+    top: usize,
+    
+    /// 사람이 읽는 형식 대신 JSON으로 출력
+    #[arg(long, default_value_t = false)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct EngineReportArgs {
+    /// 확장 프로그램 목록 파일 경로 또는 http(s) URL (여러 번 지정 가능)
+    #[arg(short, long, required = true)]
+    file: Vec<PathBuf>,
+    
+    /// 원격 목록 파일을 가져올 때 붙일 헤더 (예: "Authorization: token ...")
+    #[arg(long, value_name = "HEADER", value_parser = http::parse_header)]
+    file_header: Option<(String, String)>,
+    
+    /// 목록 파일의 알 수 없는 필드를 오류 대신 경고로 처리
+    #[arg(long, default_value_t = false)]
+    allow_unknown_fields: bool,
+    
+    /// VSIX 파일이 있는 다운로드 디렉토리
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// 지원하는 가장 오래된 VSCode 버전. 이보다 높은 버전을 요구하는 확장 프로그램이 있으면 실패로 종료
+    #[arg(long, value_name = "VERSION")]
+    oldest_vscode: Option<String>,
+}
+
+#[derive(Args)]
+struct SizeReportArgs {
+    /// VSIX 파일이 있는 다운로드 디렉토리
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// 전송 번들의 크기 예산 (예: 2GiB). 누적 합계가 넘는 지점부터 강조하고 전체가 넘으면 실패로 종료
+    #[arg(long, value_name = "SIZE", value_parser = size::parse_size)]
+    budget: Option<u64>,
+}
+
+#[derive(Args)]
+struct FreshnessReportArgs {
+    /// VSIX 파일이 있는 다운로드 디렉토리 (--lockfile이 없을 때 사용)
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// 미러한 버전과 기록 시각을 읽을 잠금 파일 (--lockfile로 기록한 파일)
+    #[arg(long, value_name = "PATH")]
+    lockfile: Option<PathBuf>,
+    
+    /// 새 버전이 나온 지 이 일수보다 오래된 항목을 강조하고 실패로 종료
+    #[arg(long, value_name = "DAYS")]
+    max_behind: Option<i64>,
+}
+
+#[derive(Args)]
+struct BundleArgs {
+    /// 만들 번들 파일 (tar.gz)
+    bundle: PathBuf,
+    
+    /// 묶을 VSIX 파일이 있는 다운로드 디렉토리
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// 이전 번들의 매니페스트(또는 번들 파일). 그 이후 추가되었거나 버전이 바뀐 VSIX만 담음
+    #[arg(long, value_name = "MANIFEST")]
+    since: Option<PathBuf>,
+    
+    /// 매니페스트를 기록할 경로 (기본값: <번들>.manifest.json). 다음 --since에 지정
+    #[arg(long, value_name = "PATH")]
+    manifest: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    /// 가져올 번들 파일 (tar.gz)
+    bundle: PathBuf,
+    
+    /// VSIX를 풀 다운로드 디렉토리 (미러)
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// 원본 미러에서 사라진 VSIX를 이 디렉토리에서도 지움
+    #[arg(long, default_value_t = false)]
+    prune: bool,
+}
+
+#[derive(Args)]
+struct CodeServerGalleryArgs {
+    /// 갤러리를 만들 디렉토리 (웹 서버로 제공할 디렉토리, 다운로드 디렉토리 밖)
+    dest: PathBuf,
+    
+    /// 내보낼 VSIX 파일이 있는 다운로드 디렉토리
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// 갤러리 디렉토리를 제공할 주소 (예: https://mirror.internal/gallery). 색인의 파일 주소와 갤러리 설정에 쓰임
+    #[arg(long, value_name = "URL")]
+    base_url: String,
+}
+
+#[derive(Args)]
+struct InspectArgs {
+    /// VSIX 파일 경로 또는 다운로드 디렉토리에 있는 확장 프로그램 ID
+    target: String,
+    
+    /// ID로 찾을 때 사용할 다운로드 디렉토리
+    #[arg(short, long, default_value = "downloads")]
+    output_dir: PathBuf,
+    
+    /// 사람이 읽는 형식 대신 JSON으로 출력
+    #[arg(long, default_value_t = false)]
+    json: bool,
+    
+    /// JSON 출력에서 이 필드 값만 출력 (예: version, contributes[0], /extension_pack/0)
+    #[arg(long, value_name = "PATH", conflicts_with = "json")]
+    field: Option<String>,
+}
+
+#[derive(Args)]
+struct SearchArgs {
+    /// 검색어
+    query: String,
+    
+    /// 정렬 기준
+    #[arg(long, value_enum, default_value_t = marketplace::SearchSort::Relevance)]
+    sort: marketplace::SearchSort,
+    
+    /// 한 페이지에 출력할 확장 프로그램 수
+    #[arg(long, value_name = "N", default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..=1000))]
+    limit: u32,
+    
+    /// 출력할 페이지 (1부터)
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    page: u32,
+    
+    /// 표 대신 JSON으로 출력
+    #[arg(long, default_value_t = false)]
+    json: bool,
+}
+
+impl SyncArgs {
+    fn results_path(&self) -> PathBuf {
+        self.output.clone()
+            .unwrap_or_else(|| PathBuf::from(format!("results.{}", self.results_format.extension())))
+    }
+    
+    /// Open VSX에서 확인된 네임스페이스의 확장 프로그램만 받을지 (아니면 Marketplace에서 게시자를 다시 확인)
+    fn openvsx_only_verified(&self, id: &str) -> bool {
+        self.openvsx_verified_only
+            || (self.verified_publishers_only && !publisher::is_trusted(id, &self.trusted_publisher))
+    }
+    
+    fn downloads_path(&self) -> PathBuf {
+        self.results_format.downloads_path(self.state_dir.as_deref())
+    }
+    
+    /// resume으로 이어서 실행할 작업 상태 파일 (프로필마다 따로 둠)
+    fn work_state_path(&self) -> PathBuf {
+        resume::state_path(self.active_profile.as_deref())
+    }
+    
+    /// 확장 프로그램 하나의 실패를 동기화 전체의 중단으로 처리할지 (--strict, --fail-fast)
+    fn stops_on_failure(&self) -> bool {
+        self.strict || self.fail_fast
+    }
+    
+    /// 중단 메시지에 표시할 옵션 이름
+    fn stop_flag(&self) -> &'static str {
+        if self.fail_fast { "--fail-fast" } else { "--strict" }
+    }
+    
+    /// 결과 파일, 다운로드 정보 파일, 다운로드 디렉토리를 모두 실행 디렉토리 안으로 옮긴 설정
+    fn for_run_directory(&self, run_dir: &Path) -> SyncArgs {
+        let results_name = self.results_path()
+            .file_name()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(format!("results.{}", self.results_format.extension())));
+        
+        SyncArgs {
+            output: Some(run_dir.join(results_name)),
+            output_dir: run_dir.to_path_buf(),
+            state_dir: Some(run_dir.to_path_buf()),
+            ..self.clone()
+        }
+    }
+    
+    /// 기록/재생 모드와 파일이나 표준 입력으로 받은 토큰을 적용한 HTTP 클라이언트
+    fn http_client(&self) -> Result<HttpClient> {
+        let mut tokens = auth::Credentials::default();
+        if let Some(path) = &self.marketplace_token_file {
+            tokens.set(auth::Registry::Marketplace, auth::read_token_file(path)?);
+        }
+        if let Some(path) = &self.openvsx_token_file {
+            tokens.set(auth::Registry::OpenVsx, auth::read_token_file(path)?);
+        }
+        if let Some(registry) = self.token_stdin {
+            tokens.set(registry, auth::read_token_stdin()?);
+        }
+        
+        Ok(HttpClient::new(self.http_mode())?.with_tokens(tokens))
+    }
+    
+    fn http_mode(&self) -> HttpMode {
+        match (&self.record, &self.replay) {
+            (Some(dir), _) => HttpMode::Record(dir.clone()),
+            (_, Some(dir)) => HttpMode::Replay(dir.clone()),
+            _ => HttpMode::Live,
+        }
+    }
+}
+
+/// 최대 크기를 넘는 다운로드를 만났을 때의 처리 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SizeLimitPolicy {
+    /// 해당 확장 프로그램만 건너뛰고 계속 진행
+    Skip,
+    /// 동기화 전체를 중단
+    Abort,
+}
+
+/// 여러 목록에서 같은 확장 프로그램의 고정 버전이 다를 때의 처리 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ConflictPolicy {
+    /// 충돌을 오류로 처리
+    Error,
+    /// 먼저 나온 목록의 항목을 유지
+    First,
+    /// 나중에 나온 목록의 항목으로 덮어씀
+    Last,
+    /// 더 높은 버전을 선택 (버전 고정이 없으면 최신 버전이므로 가장 높음)
+    Highest,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Extensions {
+    /// 이 목록을 만든 실행 정보 (merge, snapshot 출력에 기록)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run: Option<RunMetadata>,
+    enabled: Option<Vec<Extension>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Extension {
+    /// `publisher.name` ID 또는 Marketplace/Open VSX 확장 프로그램 페이지 URL
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uuid: Option<String>,
+    /// 고정할 버전 (없으면 최신 버전)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    /// 전역 설정과 관계없이 이 항목을 받을 레지스트리 (라이선스 등으로 고정할 때)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<auth::Registry>,
+    /// 이 항목을 제공한 목록 파일 (여러 파일을 합칠 때 기록)
+    #[serde(skip)]
+    source_file: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Results {
+    /// 이 결과를 만든 실행 정보
+    #[serde(default)]
+    run: Option<RunMetadata>,
+    available: Vec<AvailableExtension>,
+    unavailable: Vec<UnavailableExtension>,
+    /// 이전 실행에서 받았지만 이번에는 Open VSX와 Marketplace 모두에 없는 확장 프로그램 (악성 코드로 삭제되었을 수 있음)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    removed_upstream: Vec<String>,
+    /// 이번 실행의 Marketplace 다운로드 전송량과 속도 통계
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    download_stats: Option<DownloadStats>,
+    /// 실패는 아니지만 확인해 볼 만한 항목 (사용 중단, ID 대소문자, 웹 전용, 크기 제한)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<warnings::Warning>,
+}
+
+impl Results {
+    fn sort(&mut self) {
+        sort_by_id(&mut self.available, |e| (&e.id, e.version.as_deref()));
+        sort_by_id(&mut self.unavailable, |e| (&e.id, e.version.as_deref()));
+    }
+    
+    /// 목록에 함께 있는 확장 팩과 그 구성 요소를 찾아 구성 요소의 `included_in`에 팩 ID를 기록합니다.
+    fn link_packs(&mut self) {
+        let packs: Vec<(String, Vec<String>)> = self.available.iter()
+            .map(|e| (e.id.clone(), e.extension_pack.clone()))
+            .chain(self.unavailable.iter().map(|e| (e.id.clone(), e.extension_pack.clone())))
+            .filter(|(_, members)| !members.is_empty())
+            .collect();
+        
+        let packs_including = |id: &str| -> Vec<String> {
+            packs.iter()
+                .filter(|(pack, members)| !pack.eq_ignore_ascii_case(id) && members.iter().any(|m| m.eq_ignore_ascii_case(id)))
+                .map(|(pack, _)| pack.clone())
+                .collect()
+        };
+        
+        for extension in &mut self.available {
+            extension.included_in = packs_including(&extension.id);
+        }
+        for extension in &mut self.unavailable {
+            extension.included_in = packs_including(&extension.id);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AvailableExtension {
+    id: String,
+    uuid: Option<String>,
+    url: String,
+    version: Option<String>,
+    /// 이 항목을 제공한 목록 파일
+    source_file: Option<String>,
+    /// Open VSX에서 게시자가 네임스페이스 소유자로 확인되었는지 여부 (응답에 없으면 None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    verified: Option<bool>,
+    /// 마지막으로 확인(Open VSX) 또는 다운로드(Marketplace)에 성공한 시각 (--append-results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_success: Option<String>,
+    /// 실행 사이에 바뀐 버전 기록 (--append-results)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    version_history: Vec<VersionChange>,
+    /// 확장 팩이면 팩에 포함된 확장 프로그램 ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extension_pack: Vec<String>,
+    /// 이 항목을 포함하는 확장 팩 중 목록에 함께 있는 팩 ID (직접 항목으로 한 번만 처리)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    included_in: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UnavailableExtension {
+    id: String,
+    uuid: Option<String>,
+    /// 목록에 고정된 버전 (없으면 최신 버전을 다운로드)
+    version: Option<String>,
+    /// 이 항목을 제공한 목록 파일
+    source_file: Option<String>,
+    /// 목록 항목의 `source`로 고정한 레지스트리 (`openvsx`면 Marketplace에서 받지 않음)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<auth::Registry>,
+    /// Open VSX 확인 중 발생한 오류 코드 (단순히 다운로드 URL이 없는 경우 None)
+    error: Option<ErrorCode>,
+    /// 마지막으로 확인(Open VSX) 또는 다운로드(Marketplace)에 성공한 시각 (--append-results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_success: Option<String>,
+    /// 실행 사이에 바뀐 버전 기록 (--append-results)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    version_history: Vec<VersionChange>,
+    /// 실패한 확장 프로그램의 요청 기록 (일시적인 네트워크 문제와 실제로 없는 경우를 구분하는 데 사용)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attempts: Vec<Attempt>,
+    /// 어느 레지스트리에서도 찾지 못한 ID와 철자가 가까운 확장 프로그램 ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    suggestions: Vec<String>,
+    /// 확장 팩이면 팩에 포함된 확장 프로그램 ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extension_pack: Vec<String>,
+    /// 이 항목을 포함하는 확장 팩 중 목록에 함께 있는 팩 ID (직접 항목으로 한 번만 처리)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    included_in: Vec<String>,
+}
+
+/// 실패한 항목 하나의 오류 코드와 마지막 실패 시도
+struct Failure<'a> {
+    code: Option<ErrorCode>,
+    attempt: Option<&'a Attempt>,
+}
+
+impl Failure<'_> {
+    /// 정책이나 설정에 따라 일부러 받지 않은 항목인지
+    fn is_skip(&self) -> bool {
+        self.code.is_some_and(ErrorCode::is_skip)
+    }
+    
+    /// 다시 시도하면 성공할 수 있는 실패인지
+    fn is_retryable(&self) -> bool {
+        self.code.is_some_and(|code| code.is_retryable(self.attempt.and_then(|attempt| attempt.status)))
+    }
+}
+
+impl UnavailableExtension {
+    /// `run_id` 실행에서 이 항목이 실패한 이유. 다운로드에 성공했거나 실패한 요청이 없으면 None
+    fn failure<'a>(&'a self, downloads: &[DownloadInfo], run_id: Option<&str>) -> Option<Failure<'a>> {
+        let download = downloads.iter()
+            .find(|d| d.id.eq_ignore_ascii_case(&self.id) && run_id.is_none_or(|run_id| d.run.as_ref().is_some_and(|r| r.run_id == run_id)));
+        let attempt = self.attempts.iter().rev().find(|attempt| attempt.error.is_some());
+        
+        // 다운로드 정보가 없으면 조회 단계에서 실패했거나 다운로드하지 않은 항목
+        let code = match download {
+            Some(download) if download.success => return None,
+            Some(download) => download.error.or(attempt.and_then(|attempt| attempt.error)),
+            None => attempt?.error,
+        };
+        Some(Failure { code, attempt })
+    }
+}
+
+/// 다운로드 단계에서 결과 파일에 덧붙일 정보
+#[derive(Debug, Default)]
+struct DownloadReport {
+    attempts: Vec<Attempt>,
+    suggestions: Vec<String>,
+    /// 받은 VSIX가 확장 팩이면 팩에 포함된 확장 프로그램 ID
+    extension_pack: Vec<String>,
+    /// 받으려던 버전과 받은 파일 크기 (--plain 진행 줄에 표시)
+    version: Option<String>,
+    size: Option<u64>,
+}
+
+/// 확인 또는 다운로드 요청 한 번의 결과
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Attempt {
+    stage: AttemptStage,
+    url: String,
+    /// 오류가 된 HTTP 응답 상태 코드
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    /// 성공한 요청은 None
+    error: Option<ErrorCode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    started_at: String,
+    duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AttemptStage {
+    /// Open VSX 확인
+    Check,
+    /// Marketplace extensionquery 조회
+    Query,
+    /// VSIX 다운로드
+    Download,
+}
+
+impl Attempt {
+    /// `started`에 시작한 요청의 결과를 기록합니다.
+    fn record(stage: AttemptStage, url: &str, started: (chrono::DateTime<Utc>, Instant), error: Option<&anyhow::Error>) -> Self {
+        let (started_at, instant) = started;
+        Attempt {
+            stage,
+            url: url.to_string(),
+            status: error.and_then(error::http_status),
+            error: error.map(error::error_code),
+            message: error.map(|e| format!("{:#}", e)),
+            started_at: started_at.to_rfc3339(),
+            duration_ms: instant.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// 요청 기록에 쓸 시작 시각
+fn attempt_start() -> (chrono::DateTime<Utc>, Instant) {
+    (Utc::now(), Instant::now())
+}
+
+/// 버전이 처음 확인된 시각
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VersionChange {
+    version: String,
+    timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct DownloadInfo {
+    id: String,
+    marketplace_url: String,
+    direct_download_url: String,
+    /// extensionquery의 fallbackAssetUri로 만든 대체 다운로드 URL
+    fallback_download_url: Option<String>,
+    download_path: String,
+    file_name: String,
+    version: Option<String>,
+    /// 플랫폼별 빌드의 대상 플랫폼 (범용 패키지는 없음, 받은 뒤에는 VSIX 매니페스트의 값)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target_platform: Option<String>,
+    timestamp: String,
+    success: bool,
+    size: Option<u64>,
+    sha256: Option<String>,
+    error: Option<ErrorCode>,
+    /// 실패한 파일이 격리된 경로 (다운로드 디렉토리의 정상 파일 목록에서는 제외됨)
+    quarantine_path: Option<String>,
+    /// 이 항목을 기록한 실행 정보
+    #[serde(default)]
+    run: Option<RunMetadata>,
+    /// Marketplace 게시자 정보 (다음 실행에서 소유권 변경을 감지하는 데 사용)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    publisher: Option<PublisherInfo>,
+    /// 이전 실행과 비교해 게시자 소유권이 바뀐 이유 (바뀌지 않았으면 없음)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    publisher_change: Option<String>,
+    /// Open VSX에 게시된 같은 버전의 SHA-256 (--cross-check-openvsx, `sha256`과 다르면 불일치)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    openvsx_sha256: Option<String>,
+    /// 성공한 다운로드 요청에 걸린 시간 (밀리초)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    /// 성공한 다운로드의 평균 속도 (초당 바이트)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bytes_per_second: Option<u64>,
+    /// VSIX와 함께 받은 자산 (--assets)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    assets: Vec<assets::DownloadedAsset>,
+    /// 악성 코드 검사 결과 (--scan)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scan: Option<scan::ScanVerdict>,
+    /// 파일을 실제로 내려준 레지스트리, 최종 URL, HTTP 상태와 Content-Length
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    origin: Option<DownloadOrigin>,
+    /// 받을 자산의 URL 후보 (다운로드 정보 파일에는 기록하지 않음)
+    #[serde(skip)]
+    asset_sources: Vec<assets::AssetSource>,
+    /// before_download 훅에 라이선스를 알려주려고 읽는 package.json의 URL 후보
+    #[serde(skip)]
+    manifest_urls: Vec<String>,
+}
+
+/// 다운로드가 끝난 파일의 크기와 SHA-256 해시
+struct DownloadedFile {
+    size: u64,
+    sha256: String,
+    /// 파일을 받는 데 걸린 시간 (이미 있던 파일이면 없음)
+    duration_ms: Option<u64>,
+    /// 파일을 실제로 내려준 곳 (이미 있던 파일이면 없음)
+    origin: Option<DownloadOrigin>,
+}
+
+/// 파일을 실제로 내려준 레지스트리와 응답 정보 (미러 감사에서 출처를 증명하는 데 사용)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DownloadOrigin {
+    /// 파일을 내려준 레지스트리 (리졸버나 다른 호스트에서 받았으면 없음)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    registry: Option<auth::Registry>,
+    /// 파일을 내려준 리졸버 이름
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    resolver: Option<String>,
+    /// 리디렉션을 따라간 최종 URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    final_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    http_status: Option<u16>,
+    /// 응답의 Content-Length
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_length: Option<u64>,
+}
+
+impl DownloadOrigin {
+    fn from_response(response: &http::HttpResponse) -> Self {
+        DownloadOrigin {
+            registry: auth::Registry::for_url(&response.url),
+            resolver: None,
+            final_url: Some(response.url.clone()),
+            http_status: Some(response.status.as_u16()),
+            content_length: response.content_length(),
+        }
+    }
+}
+
+/// 실패한 다운로드의 오류 코드와 격리된 파일 위치
+struct DownloadFailure {
+    code: ErrorCode,
+    quarantine_path: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    if cli.plain {
+        plain::enable();
+    }
+    if cli.ascii {
+        plain::enable_ascii();
+    }
+    cli.apply_client_settings()?;
+
+    match &cli.command {
+        Commands::Sync(args) => {
+            run_sync(args, None).await?
+        },
+        Commands::Resume(args) => {
+            let state = resume::load_state(&resume::state_path(args.profile.as_deref()))?;
+            let resumed = Cli::try_parse_from(&state.argv)?;
+            // 이번에 지정하지 않았으면 중단된 실행의 --user-agent, --header, --resolve를 그대로 사용
+            resumed.apply_client_settings()?;
+            let command = resumed.command;
+            
+            println!("{} {}", "중단된 동기화를 이어서 실행합니다. 실행 ID:".blue(), state.run.run_id);
+            match command {
+                Commands::Sync(args) => run_sync(&args, Some(state)).await?,
+                Commands::RetryFailed(args) => retry_failed(&args, Some(state)).await?,
+                Commands::MirrorPublisher(args) => mirror::publisher(&args, Some(state)).await?,
+                Commands::MirrorTop(args) => mirror::top(&args, Some(state)).await?,
+                _ => return Err(anyhow!("{}에 저장된 명령이 다시 실행할 수 있는 동기화 명령이 아닙니다", resume::STATE_FILE)),
+            }
+        },
+        Commands::RetryFailed(args) => {
+            retry_failed(args, None).await?
+        },
+        Commands::MirrorPublisher(args) => {
+            mirror::publisher(args, None).await?
+        },
+        Commands::MirrorTop(args) => {
+            mirror::top(args, None).await?
+        },
+        Commands::Daemon(args) => match &args.action {
+            Some(DaemonAction::InstallService(install)) => service::install(install).await?,
+            None => daemon::run(args).await?,
+        },
+        Commands::Merge(args) => {
+            let client = HttpClient::new(HttpMode::Live)?;
+            merge_extension_lists(&client, args).await?
+        },
+        Commands::MergeResults(args) => {
+            shard::merge_results(args)?
+        },
+        Commands::Snapshot(args) => {
+            snapshot::run(args).await?
+        },
+        Commands::Restore(args) => {
+            restore::run(args).await?
+        },
+        Commands::Provision { target } => match target {
+            ProvisionTarget::CodeServer(args) => provision::code_server(args).await?,
+        },
+        Commands::Status(args) => {
+            status::run(args).await?
+        },
+        Commands::Auth { action } => match action {
+            AuthAction::Login { registry } => auth::login(*registry).await?,
+            AuthAction::Logout { registry } => auth::logout(*registry).await?,
+            AuthAction::Status => auth::status().await?,
+        },
+        Commands::Doctor(args) => {
+            doctor::run(args).await?
+        },
+        Commands::Stats(args) => {
+            history::stats(args)?
+        },
+        Commands::Report { kind } => match kind {
+            ReportKind::Engines(args) => report::engines(args).await?,
+            ReportKind::Sizes(args) => report::sizes(args)?,
+            ReportKind::Freshness(args) => report::freshness(args).await?,
+        },
+        Commands::Bundle(args) => {
+            bundle::create(args)?
+        },
+        Commands::Import(args) => {
+            bundle::import(args)?
+        },
+        Commands::Export { format } => match format {
+            ExportFormat::CodeServerGallery(args) => export::code_server_gallery(args)?,
+        },
+        Commands::Extract { file, output_dir } => {
+            extract_vsix(file, output_dir.as_deref())?
+        },
+        Commands::Inspect(args) => {
+            inspect::run(args)?
+        },
+        Commands::Search(args) => {
+            search::run(args).await?
+        },
+        Commands::SelfUpdate { check } => {
+            self_update::self_update(*check).await?
+        },
+        Commands::Completions { shell } => {
+            let mut command = Cli::command();
+            let bin_name = command.get_name().to_string();
+            clap_complete::generate(*shell, &mut command, bin_name, &mut std::io::stdout());
+        },
+        Commands::Manpage { out_dir } => {
+            generate_manpages(out_dir.as_deref())?
+        },
+        Commands::Help { all, command } => {
+            print_help(*all, command)?
+        },
+    }
+
+    Ok(())
+}
+
+fn extract_vsix(file: &Path, output_dir: Option<&Path>) -> Result<()> {
+    let output_dir = match output_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => file.file_stem()
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow!("VSIX 파일 경로가 올바르지 않습니다: {}", file.display()))?,
+    };
+    
+    // 검토 중인 다른 패키지의 파일과 섞이지 않도록 비어 있는 디렉토리에만 풂
+    let is_empty = fs::read_dir(&output_dir).map_or(true, |mut entries| entries.next().is_none());
+    if !is_empty {
+        return Err(anyhow!("출력 디렉토리가 비어 있지 않습니다: {}", output_dir.display()));
+    }
+    
+    let (extracted, skipped) = vsix::extract(file, &output_dir)?;
+    
+    for name in &skipped {
+        println!("{} {}", "안전하지 않은 경로라 건너뜀:".yellow(), name);
+    }
+    
+    println!(
+        "{} {}",
+        format!("{}개 파일을 풀었습니다:", extracted).green(),
+        output_dir.display()
+    );
+    
+    Ok(())
+}
+
+fn generate_manpages(out_dir: Option<&Path>) -> Result<()> {
+    let command = Cli::command();
+    
+    match out_dir {
+        Some(dir) => {
+            create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+            clap_mangen::generate_to(command, dir)
+                .with_context(|| format!("Failed to write man pages to {}", dir.display()))?;
+            println!("{} {}", "man 페이지가 생성되었습니다:".green(), dir.display());
+        },
+        None => {
+            clap_mangen::Man::new(command)
+                .render(&mut std::io::stdout())
+                .with_context(|| "Failed to render man page")?;
+        },
+    }
+    
+    Ok(())
+}
+
+fn print_help(all: bool, path: &[String]) -> Result<()> {
+    let mut command = Cli::command();
+    command.build();
+    
+    // 하위 명령 경로를 따라 내려가기
+    let mut target = &command;
+    for name in path {
+        target = target.find_subcommand(name)
+            .ok_or_else(|| anyhow!("알 수 없는 하위 명령: {}", name))?;
+    }
+    
+    if all {
+        print_help_tree(target);
+    } else {
+        println!("{}", target.clone().render_long_help());
+    }
+    
+    Ok(())
+}
+
+fn print_help_tree(command: &clap::Command) {
+    let title = command.get_bin_name().unwrap_or(command.get_name());
+    println!("{}", format!("==== {} ====", title).bold());
+    println!("{}", command.clone().render_long_help());
+    
+    for subcommand in command.get_subcommands() {
+        print_help_tree(subcommand);
+    }
+}
+
+async fn run_sync(args: &SyncArgs, mut resumed: Option<SyncState>) -> Result<()> {
+    let profiles = profile::expand(args)?;
+    if let [profile] = profiles.as_slice() {
+        // retry-failed처럼 이미 프로필을 적용한 설정이면 호출한 쪽에서 출력함
+        if let (Some(_), Some(name)) = (&args.profile, &profile.active_profile) {
+            println!("{} {}", "프로필:".blue(), name);
+        }
+        return sync_profile(profile, resumed).await;
+    }
+    
+    // 한 프로필이 실패해도 다른 미러는 계속 갱신
+    let mut failed = Vec::new();
+    for args in &profiles {
+        let name = args.active_profile.as_deref().unwrap_or_default();
+        println!("{} {}", "프로필:".blue(), name);
+        if let Err(e) = sync_profile(args, resumed.take()).await {
+            println!("{} {}: {:#}", "프로필 동기화 실패".red(), name, e);
+            failed.push(name);
+        }
+    }
+    
+    if !failed.is_empty() {
+        return Err(anyhow!("{}개 프로필 중 {}개의 동기화에 실패했습니다: {}", profiles.len(), failed.len(), failed.join(", ")));
+    }
+    Ok(())
+}
+
+/// 프로필 하나(또는 프로필 없이 명령줄 설정)로 연결을 확인하고 동기화합니다.
+async fn sync_profile(args: &SyncArgs, resumed: Option<SyncState>) -> Result<()> {
+    let args = &SyncArgs {
+        hooks: config::section(&args.config, "hooks")?,
+        resolvers: config::section(&args.config, "resolvers")?,
+        cancel: CancellationToken::new(),
+        ..args.clone()
+    };
+    resolver::validate(&args.resolvers)?;
+    let client = args.http_client()?;
+    let result = match preflight::run(&client, args).await {
+        Ok(args) => sync_extensions(&client, &args, resumed).await,
+        Err(e) => Err(e),
+    };
+    status_file::finish(result.is_ok());
+    
+    if args.notify_desktop {
+        notify::notify_sync_finished(&result);
+    }
+    
+    result
+}
+
+/// 이전 결과에서 다운로드하지 못한 항목만 다시 확인하고 다운로드합니다.
+/// 다운로드 디렉토리와 다른 결과는 그대로 두고, 다시 시도한 항목의 결과만 ID 기준으로 바꿔 넣습니다.
+async fn retry_failed(args: &SyncArgs, mut resumed: Option<SyncState>) -> Result<()> {
+    if args.results_format == ResultsFormat::Csv {
+        return Err(anyhow!("CSV 결과 파일로는 retry-failed를 사용할 수 없습니다"));
+    }
+    
+    for args in profile::expand(args)? {
+        if let Some(name) = &args.active_profile {
+            println!("{} {}", "프로필:".blue(), name);
+        }
+        
+        // --run-dirs면 latest가 가리키는 실행 디렉토리의 결과를 읽고 그 디렉토리에 다시 받음
+        let args = if args.run_dirs {
+            let run_dir = run_dir::latest_run_directory(&args.output_dir)
+                .ok_or_else(|| anyhow!("{}에 다시 시도할 실행 디렉토리(latest)가 없습니다", args.output_dir.display()))?;
+            println!("{} {}", "실행 디렉토리:".blue(), run_dir.display());
+            args.for_run_directory(&run_dir)
+        } else {
+            args
+        };
+        
+        let extensions = failed_extensions(&args)?;
+        if extensions.is_empty() {
+            println!("{}", "다시 시도할 실패 항목이 없습니다.".green());
+            continue;
+        }
+        
+        println!("{}", format!("실패한 {}개 확장 프로그램을 다시 시도합니다...", extensions.len()).yellow());
+        
+        // 명령줄에 더한 ID는 처음 실행에서 처리했으므로 실패 항목만 다시 시도
+        let retry_args = SyncArgs {
+            listed_extensions: Some(extensions),
+            ids: Vec::new(),
+            append_results: true,
+            incremental: true,
+            auto_download: true,
+            run_dirs: false,
+            ..args
+        };
+        
+        run_sync(&retry_args, resumed.take()).await?;
+    }
+    
+    Ok(())
+}
+
+/// 결과 파일에서 다시 시도하면 성공할 수 있는 오류(네트워크, 시간 초과, 요청 한도, 서버 오류, 잘못된 응답)로 실패한 항목.
+/// 정책이나 크기 제한으로 건너뛰었거나 찾을 수 없는 항목은 다시 받지 않습니다.
+fn failed_extensions(args: &SyncArgs) -> Result<Vec<Extension>> {
+    let results_path = args.results_path();
+    let content = fs::read_to_string(&results_path)
+        .with_context(|| format!("Failed to read {}", results_path.display()))?;
+    let results: Results = args.results_format.deserialize(&content)
+        .with_context(|| format!("Failed to parse {}", results_path.display()))?;
+    
+    let downloads = load_downloads(&args.downloads_path(), args.results_format)?;
+    
+    let run_id = results.run.as_ref().map(|run| run.run_id.as_str());
+    let retry: Vec<String> = results.unavailable.iter()
+        .filter(|e| e.failure(&downloads, run_id).is_some_and(|failure| failure.is_retryable()))
+        .map(|e| e.id.clone())
+        .collect();
+    
+    Ok(results.unavailable.into_iter()
+        .filter(|e| retry.contains(&e.id))
+        .map(|e| Extension {
+            id: e.id,
+            uuid: e.uuid,
+            version: e.version,
+            source: e.source,
+            source_file: e.source_file,
+        })
+        .collect())
+}
+
+async fn sync_extensions(client: &HttpClient, args: &SyncArgs, resumed: Option<SyncState>) -> Result<()> {
+    if !args.run_dirs {
+        return sync_into(client, args, resumed).await;
+    }
+    
+    let run_dir = match resumed.as_ref().and_then(|s| s.run_dir.clone()) {
+        Some(run_dir) => run_dir,
+        None => run_dir::create_run_directory(&args.output_dir)?,
+    };
+    println!("{} {}", "실행 디렉토리:".blue(), run_dir.display());
+    
+    sync_into(client, &args.for_run_directory(&run_dir), resumed).await?;
+    
+    // 실패한 실행은 latest로 가리키지 않아 이전 상태로 되돌릴 수 있게 유지
+    run_dir::update_latest_link(&args.output_dir, &run_dir)?;
+    println!("{} {}", "latest 링크를 갱신했습니다:".green(), run_dir.display());
+    
+    Ok(())
+}
+
+async fn sync_into(client: &HttpClient, args: &SyncArgs, resumed: Option<SyncState>) -> Result<()> {
+    let output_path = &args.results_path();
+    let output_dir = args.output_dir.as_path();
+    let results_format = args.results_format;
+    
+    if args.append_results && results_format == ResultsFormat::Csv {
+        return Err(anyhow!("CSV 결과 파일에는 --append-results를 사용할 수 없습니다"));
+    }
+    
+    println!("{}", "확장 프로그램 목록을 확인하는 중...".blue());
+    
+    let resuming = resumed.is_some();
+    
+    // 목록을 읽거나 무엇이든 지우기 전에 경로가 안전하고 쓸 수 있는지 확인
+    paths::validate(args, !resuming && !args.incremental)?;
+    
+    if let Some(ttl) = args.check_ttl {
+        check_cache::enable(ttl)?;
+    }
+    warnings::reset();
+    
+    // 목록 파일 읽기 및 병합 (retry-failed, mirror-publisher는 미리 정한 항목).
+    // 목록에 문제가 있으면 기존 결과와 다운로드를 지우기 전에 중단
+    let mut enabled_extensions = match &args.listed_extensions {
+        Some(extensions) => extensions.clone(),
+        // 명령줄 ID만 지정한 경우
+        None if args.file.is_empty() => Vec::new(),
+        None => load_extension_lists(client, &args.file, args.file_header.as_slice(), ConflictPolicy::Last, args.allow_unknown_fields).await?,
+    };
+    add_extension_ids(&mut enabled_extensions, &args.ids)?;
+    
+    // --exclude-file의 항목은 모든 입력을 합친 뒤에 뺌
+    if !args.exclude_file.is_empty() {
+        let excluded = load_extension_lists(client, &args.exclude_file, args.file_header.as_slice(), ConflictPolicy::Last, args.allow_unknown_fields).await?;
+        let (removed, kept): (Vec<Extension>, Vec<Extension>) = enabled_extensions.into_iter()
+            .partition(|e| excluded.iter().any(|x| x.id.eq_ignore_ascii_case(&e.id)));
+        if !removed.is_empty() {
+            let ids: Vec<&str> = removed.iter().map(|e| e.id.as_str()).collect();
+            println!("{} {}", format!("제외 목록에 있는 {}개를 건너뜁니다:", removed.len()).yellow(), ids.join(", "));
+        }
+        enabled_extensions = kept;
+    }
+    
+    // 설치 목록에서 내보낸 내장 확장 프로그램은 레지스트리에 없으므로 건너뜀
+    let enabled_extensions = if args.include_builtin {
+        enabled_extensions
+    } else {
+        let (builtin, enabled_extensions): (Vec<Extension>, Vec<Extension>) = enabled_extensions.into_iter()
+            .partition(|e| input::is_builtin(&e.id));
+        if !builtin.is_empty() {
+            let ids: Vec<&str> = builtin.iter().map(|e| e.id.as_str()).collect();
+            println!("{} {}", format!("내장 확장 프로그램 {}개를 건너뜁니다:", builtin.len()).yellow(), ids.join(", "));
+        }
+        enabled_extensions
+    };
+    
+    // --category, --keyword 등으로 처리할 확장 프로그램 좁히기
+    let enabled_extensions = filter::apply(client, enabled_extensions, args).await;
+    
+    // --shard면 이 작업이 맡은 확장 프로그램만 처리
+    let enabled_extensions = match args.shard {
+        Some(shard) => shard.select(enabled_extensions),
+        None => enabled_extensions,
+    };
+    
+    // 업스트림 삭제와 게시자 변경 감지에 쓰도록 이전 결과를 지우기 전에 이전 실행의 기록을 한 번 읽어 둠
+    let previous = previous_run(args);
+    let previous_downloads = previous.as_ref()
+        .map(|previous| load_downloads(&previous.downloads_path(), previous.results_format).unwrap_or_default())
+        .unwrap_or_default();
+    let mirrored_before = previously_mirrored(previous.as_ref(), &previous_downloads);
+    publisher::remember_previous(&previous_downloads);
+    
+    // 결과 파일 초기화 (--append-results면 기존 결과에 합침)
+    if output_path.exists() && !args.append_results && !resuming {
+        println!("{}", "기존 결과 파일을 초기화합니다...".yellow());
+        fs::remove_file(output_path)
+            .with_context(|| format!("Failed to remove existing results file: {}", output_path.display()))?;
+    }
+    
+    // 다운로드 디렉토리 초기화 (이어서 실행하거나 증분 동기화할 때는 이미 받은 파일 유지)
+    if output_dir.exists() && !resuming && !args.incremental {
+        println!("{}", "기존 다운로드 디렉토리를 초기화합니다...".yellow());
+        fs::remove_dir_all(output_dir)
+            .with_context(|| format!("Failed to remove existing download directory: {}", output_dir.display()))?;
+    }
+    
+    // 다운로드 디렉토리 생성
+    create_dir_all(filename::long_path(output_dir))
+        .with_context(|| format!("Failed to create download directory: {}", output_dir.display()))?;
+    paths::write_marker(output_dir)?;
+    
+    println!("{} {}", "총".blue(), format!("{} 개의 확장 프로그램을 확인합니다...", enabled_extensions.len()).blue());
+    
+    let run = match &resumed {
+        Some(state) => state.run.clone(),
+        None => RunMetadata::new(Some(&enabled_extensions)),
+    };
+    let run_timestamp = run.timestamp.clone();
+    println!("{} {}", "실행 ID:".blue(), run.run_id);
+    
+    // 중단되더라도 resume으로 이어서 실행할 수 있도록 작업 상태를 기록
+    let argv = match (&resumed, &args.active_profile) {
+        (Some(state), _) => state.argv.clone(),
+        (None, Some(name)) => profile::pin_argv(std::env::args().collect(), name),
+        (None, None) => std::env::args().collect(),
+    };
+    status_file::start(args.status_file.clone(), &run.run_id);
+    // 이전 실행이 중단되며 남긴 저널을 먼저 다운로드 정보 파일에 반영
+    journal::compact(&args.downloads_path(), args.results_format)?;
+    let retry_command = retry_command(&argv, args);
+    if !args.marketplace_only && !args.marketplace_unreachable {
+        check_cache::resolve_latest(client, &enabled_extensions).await;
+    }
+    // --run-dirs면 for_run_directory로 다운로드 디렉토리가 실행 디렉토리로 바뀌어 있음
+    let run_dir = args.run_dirs.then(|| args.output_dir.clone());
+    let tracker = WorkTracker::start(argv, run.clone(), run_dir, args.work_state_path(), &enabled_extensions, resumed)?;
+    
+    let mut results = Results {
+        run: Some(run.clone()),
+        available: Vec::new(),
+        unavailable: Vec::new(),
+        removed_upstream: Vec::new(),
+        download_stats: None,
+        warnings: Vec::new(),
+    };
+    
+    // --pipeline이면 확인과 다운로드를 함께 진행하고, --batch-size면 묶음마다 확인과 다운로드를 마침
+    let limiter = Semaphore::new(args.concurrency.max(1));
+    let pipelined_summary = if args.pipeline && !args.marketplace_unreachable {
+        Some(check_and_download_pipelined(client, &enabled_extensions, args, &run, &mut results, &limiter, &tracker).await?)
+    } else if args.batch_size.is_some() && !args.marketplace_unreachable {
+        Some(check_and_download_batched(client, &enabled_extensions, args, &run, &mut results, &limiter, &tracker).await?)
+    } else {
+        // 동시에 여러 확장 프로그램을 확인하고 끝나는 대로 결과에 반영
+        let to_check: Vec<&Extension> = enabled_extensions.iter().filter(|e| !e.id.is_empty()).collect();
+        let check_bar = check_progress_bar(to_check.len());
+        let mut checks = futures_util::stream::iter(to_check)
+            .map(|extension| with_permit(&limiter, tracked_check(&tracker, client, extension, args)))
+            .buffer_unordered(args.concurrency.max(1));
+        
+        while let Some(outcome) = checks.next().await {
+            let outcome = match outcome {
+                Ok(outcome) => outcome,
+                Err(e) => return Err(cancel_remaining(&mut checks, e, args).await),
+            };
+            check_bar.inc(1);
+            match outcome {
+                CheckOutcome::Available(extension) => results.available.push(extension),
+                CheckOutcome::Unavailable(extension) => results.unavailable.push(extension),
+            }
+            flush_results(&mut results, args, &run_timestamp)?;
+        }
+        check_bar.finish_and_clear();
+        
+        None
+    };
+    
+    println!(
+        "{}", 
+        format!("\n확인 완료:\n- Open VSX에서 사용 가능: {}개\n- VSCode Marketplace에서 다운로드 필요: {}개", 
+            results.available.len(), 
+            results.unavailable.len()
+        ).blue()
+    );
+    
+    // Open VSX에 없는 경우 외의 오류는 확인 실패로 따로 보여줌 (Marketplace에서 다시 시도됨)
+    let check_failures: Vec<&UnavailableExtension> = results.unavailable.iter()
+        .filter(|e| e.error.is_some_and(|code| code != ErrorCode::NotFound))
+        .collect();
+    if !check_failures.is_empty() {
+        println!("{}", format!("- 확인 실패: {}개", check_failures.len()).red());
+        for extension in check_failures {
+            println!("  {} ({})", extension.id, extension.error.unwrap_or(ErrorCode::Other).as_str());
+        }
+    }
+    
+    // 확장 팩과 구성 요소가 함께 있으면 구성 요소는 직접 항목으로 한 번만 처리
+    results.link_packs();
+    let pack_members = results.available.iter().map(|e| (&e.id, &e.included_in))
+        .chain(results.unavailable.iter().map(|e| (&e.id, &e.included_in)))
+        .filter(|(_, packs)| !packs.is_empty())
+        .count();
+    if pack_members > 0 {
+        println!("{}", format!("- 목록에 있는 확장 팩에도 포함된 항목: {}개 (직접 항목으로 한 번만 처리)", pack_members).blue());
+    }
+    
+    // 결과를 파일로 저장 (실행마다 같은 순서가 되도록 정렬)
+    tracker.attach_download_reports(&mut results);
+    results.link_packs();
+    results.sort();
+    results.warnings = warnings::snapshot();
+    write_sync_results(&results, args, &run_timestamp)?;
+    
+    println!("{} {}", "결과가".green(), format!("{} 파일에 저장되었습니다.", output_path.display()).green());
+    
+    // 파이프라인이나 묶음 처리에서는 다운로드가 이미 끝났으므로 요약만 출력
+    if let Some(summary) = pipelined_summary {
+        summary.print();
+        results.download_stats = summary.stats();
+        finish_downloads(&mut results, &tracker, args, &run, &mirrored_before)?;
+        journal::compact(&args.downloads_path(), args.results_format)?;
+        tracker.finish()?;
+        record_history(&results, args, &run)?;
+        warnings::print(&results.warnings);
+        print_failure_recap(&results, args, &run, &retry_command);
+        check_hash_mismatches(args, &run)?;
+        check_removed_upstream(&results, args)?;
+        return run_complete_hook(args, &run).await;
+    }
+    
+    // 다운로드 필요한 확장 프로그램이 있는 경우
+    if args.marketplace_unreachable && !results.unavailable.is_empty() {
+        println!("{}", format!("Marketplace에 연결할 수 없어 {}개 확장 프로그램을 다운로드하지 않았습니다.", results.unavailable.len()).yellow());
+    } else if !results.unavailable.is_empty() {
+        let download_count = results.unavailable.len();
+        
+        // 자동 다운로드 옵션이 있는 경우 바로 다운로드 시작
+        if args.auto_download {
+            println!("{}", format!("VSCode Marketplace에서 {} 개의 확장 프로그램을 다운로드합니다...", download_count).yellow());
+            let summary = download_marketplace_extensions(client, &results.unavailable, args, &run, &limiter, &tracker).await?;
+            results.download_stats = summary.stats();
+            finish_downloads(&mut results, &tracker, args, &run, &mirrored_before)?;
+        } else {
+            // 사용자에게 다운로드 여부 묻기
+            println!(
+                "{}\n{}", 
+                format!("VSCode Marketplace에서 {} 개의 확장 프로그램을 다운로드하시겠습니까? (y/n)", download_count).yellow(),
+                "다운로드할 확장 프로그램: ".yellow()
+            );
+            
+            // 확장 프로그램 ID 출력
+            for (i, extension) in results.unavailable.iter().enumerate() {
+                if i > 0 && i % 5 == 0 {
+                    println!();
+                }
+                print!("{}{}", extension.id.yellow(), if i < results.unavailable.len() - 1 { ", " } else { "" });
+            }
+            println!();
+            
+            // 사용자 입력 받기
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input)?;
+            
+            if input.trim().to_lowercase() == "y" {
+                println!("{}", format!("VSCode Marketplace에서 {} 개의 확장 프로그램을 다운로드합니다...", download_count).green());
+                let summary = download_marketplace_extensions(client, &results.unavailable, args, &run, &limiter, &tracker).await?;
+                results.download_stats = summary.stats();
+                finish_downloads(&mut results, &tracker, args, &run, &mirrored_before)?;
+            } else {
+                println!("{}", "다운로드를 취소했습니다.".red());
+            }
+        }
+    } else {
+        println!("{}", "VSCode Marketplace에서 다운로드할 확장 프로그램이 없습니다.".green());
+    }
+    
+    journal::compact(&args.downloads_path(), args.results_format)?;
+    tracker.finish()?;
+    record_history(&results, args, &run)?;
+    warnings::print(&results.warnings);
+    print_failure_recap(&results, args, &run, &retry_command);
+    check_hash_mismatches(args, &run)?;
+    check_removed_upstream(&results, args)?;
+    run_complete_hook(args, &run).await
+}
+
+/// 동기화가 실패 없이 끝나면 결과 파일 경로 등을 넘겨 `on_complete` 훅을 실행합니다.
+async fn run_complete_hook(args: &SyncArgs, run: &RunMetadata) -> Result<()> {
+    let results = args.results_path().to_string_lossy().to_string();
+    let downloads = args.downloads_path().to_string_lossy().to_string();
+    let output_dir = args.output_dir.to_string_lossy().to_string();
+    
+    hooks::on_complete(&args.hooks, &[
+        ("results", results.as_str()),
+        ("downloads", downloads.as_str()),
+        ("output_dir", output_dir.as_str()),
+        ("run_id", run.run_id.as_str()),
+    ]).await
+}
+
+/// 실행이 끝날 때 이번 실행에서 실패한 확장 프로그램을 이유와 함께 한곳에 모아 출력하고,
+/// 로그를 거슬러 올라가지 않아도 되도록 그대로 붙여 넣어 실행할 수 있는 다시 시도 명령을 알려줍니다.
+fn print_failure_recap(results: &Results, args: &SyncArgs, run: &RunMetadata, retry_command: &str) {
+    let downloads = load_downloads(&args.downloads_path(), args.results_format).unwrap_or_default();
+    
+    // 웹 전용이나 크기 제한, 정책으로 건너뛴 항목은 경고로 따로 보여줌
+    let mut retryable = false;
+    let failures: Vec<(&str, String)> = results.unavailable.iter()
+        .filter(|extension| !results.warnings.iter().any(|w| w.kind.skips_download() && w.id.eq_ignore_ascii_case(&extension.id)))
+        .filter_map(|extension| {
+            let failure = extension.failure(&downloads, Some(&run.run_id))?;
+            if failure.is_skip() {
+                return None;
+            }
+            retryable |= failure.is_retryable();
+            let reason = match (failure.code, failure.attempt.and_then(|attempt| attempt.message.as_deref())) {
+                (Some(code), Some(message)) => format!("{}: {}", code.as_str(), message),
+                (Some(code), None) => code.as_str().to_string(),
+                (None, Some(message)) => message.to_string(),
+                (None, None) => "알 수 없는 오류".to_string(),
+            };
+            Some((extension.id.as_str(), reason))
+        })
+        .collect();
+    
+    if failures.is_empty() {
+        return;
+    }
+    
+    println!("{}", format!("\n실패한 확장 프로그램 {}개:", failures.len()).red().bold());
+    let id_width = failures.iter().map(|(id, _)| id.len()).max().unwrap_or(0);
+    for (id, reason) in &failures {
+        println!("  {:<id_width$}  {}", id.red(), reason, id_width = id_width);
+    }
+    // 찾을 수 없거나 해시가 다른 항목은 다시 시도해도 같으므로 명령을 알려주지 않음
+    if retryable {
+        println!("{}", "실패한 항목만 다시 시도하려면:".yellow());
+        println!("  {}", retry_command);
+    }
+}
+
+/// 이번 실행과 같은 옵션으로 실패한 항목만 다시 시도하는 명령.
+/// `sync`로 실행했으면 하위 명령만 `retry-failed`로 바꾸고, 다른 명령이면 결과 파일과 다운로드 디렉토리를 지정합니다.
+fn retry_command(argv: &[String], args: &SyncArgs) -> String {
+    let program = argv.first().map_or("vsix-downloader", String::as_str);
+    let subcommand = argv.iter().skip(1).position(|arg| !arg.starts_with('-')).map(|index| index + 1);
+    
+    let command: Vec<String> = match subcommand {
+        Some(index) if matches!(argv[index].as_str(), "sync" | "retry-failed") => {
+            let mut command = argv.to_vec();
+            command[index] = "retry-failed".to_string();
+            command
+        },
+        _ => {
+            let mut command = vec![
+                program.to_string(),
+                "retry-failed".to_string(),
+                "-r".to_string(),
+                args.results_path().to_string_lossy().into_owned(),
+                "-o".to_string(),
+                args.output_dir.to_string_lossy().into_owned(),
+            ];
+            if args.results_format != ResultsFormat::Json {
+                command.extend(["--results-format".to_string(), args.results_format.extension().to_string()]);
+            }
+            command
+        },
+    };
+    
+    command.iter().map(|arg| shell_quote(arg)).collect::<Vec<_>>().join(" ")
+}
+
+/// 셸에 그대로 붙여 넣을 수 있도록 공백이나 특수 문자가 있는 인자를 작은따옴표로 감쌉니다.
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty() && arg.chars().all(|c| c.is_alphanumeric() || "-_./:=,@+%".contains(c));
+    if plain {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// 이번 실행에서 확인하거나 받은 확장 프로그램의 버전과 실패 여부를 실행 기록 파일에 덧붙입니다 (`stats`).
+fn record_history(results: &Results, args: &SyncArgs, run: &RunMetadata) -> Result<()> {
+    let downloads = load_downloads(&args.downloads_path(), args.results_format).unwrap_or_default();
+    
+    let mut entries: Vec<history::RunEntry> = results.available.iter()
+        .map(|e| history::RunEntry { id: e.id.clone(), version: e.version.clone(), failed: false })
+        .collect();
+    
+    for extension in &results.unavailable {
+        let download = downloads.iter()
+            .find(|d| d.id.eq_ignore_ascii_case(&extension.id) && d.run.as_ref().is_some_and(|r| r.run_id == run.run_id));
+        
+        // 다운로드 정보가 없으면 조회 단계에서 실패했거나 다운로드하지 않은 항목
+        let entry = match download {
+            Some(download) => history::RunEntry {
+                id: extension.id.clone(),
+                version: download.version.clone(),
+                failed: !download.success,
+            },
+            None if !extension.attempts.is_empty() => history::RunEntry {
+                id: extension.id.clone(),
+                version: None,
+                failed: true,
+            },
+            None => continue,
+        };
+        entries.push(entry);
+    }
+    
+    let bytes = results.download_stats.as_ref().map_or(0, |stats| stats.total_bytes);
+    history::append(&history::RunRecord::finish(run, bytes, entries))
+}
+
+/// 다운로드가 끝난 뒤 실패 기록, 확장 팩 관계, 업스트림 삭제를 결과에 반영해 다시 기록하고 경고를 출력합니다.
+fn finish_downloads(
+    results: &mut Results,
+    tracker: &WorkTracker,
+    args: &SyncArgs,
+    run: &RunMetadata,
+    mirrored_before: &[String]
+) -> Result<()> {
+    // 다운로드 실패 기록과 CSV·누적 결과의 다운로드 결과를 반영해 다시 기록
+    tracker.attach_download_reports(results);
+    results.link_packs();
+    results.removed_upstream = removed_upstream(results, mirrored_before);
+    results.warnings = warnings::snapshot();
+    write_sync_results(results, args, &run.timestamp)?;
+    
+    warn_integrity_findings(args, run)?;
+    
+    if let Some(lockfile) = &args.lockfile {
+        let downloads = load_downloads(&args.downloads_path(), args.results_format)?;
+        let this_run: Vec<&DownloadInfo> = downloads.iter()
+            .filter(|d| d.run.as_ref().is_some_and(|r| r.run_id == run.run_id))
+            .collect();
+        lockfile::update(lockfile, &this_run)?;
+    }
+    
+    if !results.removed_upstream.is_empty() {
+        println!("{}", format!("\n업스트림에서 삭제된 확장 프로그램 {}개:", results.removed_upstream.len()).red().bold());
+        for id in &results.removed_upstream {
+            println!("  {}", id.red());
+        }
+        println!("{}", "이전에 받았지만 이제 Open VSX와 Marketplace 모두에 없습니다. 악성 코드로 삭제되었을 수 있으니 미러에서 제거할지 확인하세요.".red());
+    }
+    
+    Ok(())
+}
+
+/// 이전 실행의 결과 파일과 다운로드 정보 파일을 읽을 설정.
+/// --run-dirs면 이번 실행 디렉토리는 새로 만들어 비어 있으므로 latest가 가리키는 실행 디렉토리를 사용합니다 (없으면 None).
+fn previous_run(args: &SyncArgs) -> Option<SyncArgs> {
+    if !args.run_dirs {
+        return Some(args.clone());
+    }
+    let latest = args.output_dir.parent().and_then(run_dir::latest_run_directory)?;
+    Some(args.for_run_directory(&latest))
+}
+
+/// 이전 실행에서 받은 적이 있는 확장 프로그램 ID (Open VSX에서 확인됐거나 Marketplace에서 다운로드에 성공한 항목)
+fn previously_mirrored(previous: Option<&SyncArgs>, previous_downloads: &[DownloadInfo]) -> Vec<String> {
+    let mut ids: Vec<String> = previous_downloads.iter()
+        .filter(|d| d.success)
+        .map(|d| d.id.clone())
+        .collect();
+    
+    let results = previous.and_then(|previous| {
+        let content = fs::read_to_string(previous.results_path()).ok()?;
+        previous.results_format.deserialize::<Results>(&content).ok()
+    });
+    if let Some(results) = results {
+        ids.extend(results.available.into_iter().map(|e| e.id));
+        ids.extend(results.unavailable.into_iter().filter(|e| e.last_success.is_some()).map(|e| e.id));
+    }
+    
+    ids
+}
+
+/// 이전에 받은 적이 있지만 이번에는 Open VSX 확인과 Marketplace 조회가 모두 404인 확장 프로그램
+fn removed_upstream(results: &Results, mirrored_before: &[String]) -> Vec<String> {
+    results.unavailable.iter()
+        .filter(|e| e.error == Some(ErrorCode::NotFound))
+        .filter(|e| e.attempts.iter().any(|a| matches!(a.stage, AttemptStage::Query) && a.error == Some(ErrorCode::NotFound)))
+        .filter(|e| mirrored_before.iter().any(|id| id.eq_ignore_ascii_case(&e.id)))
+        .map(|e| e.id.clone())
+        .collect()
+}
+
+/// 이번 실행에서 잠금 파일과 해시가 다른 같은 버전을 받았으면 실패로 종료합니다.
+fn check_hash_mismatches(args: &SyncArgs, run: &RunMetadata) -> Result<()> {
+    if args.lockfile.is_none() {
+        return Ok(());
+    }
+    
+    let mismatched: Vec<String> = load_downloads(&args.downloads_path(), args.results_format)?
+        .into_iter()
+        .filter(|d| d.run.as_ref().is_some_and(|r| r.run_id == run.run_id))
+        .filter(|d| d.error == Some(ErrorCode::HashMismatch))
+        .map(|d| format!("{}@{}", d.id, d.version.as_deref().unwrap_or("-")))
+        .collect();
+    
+    if !mismatched.is_empty() {
+        return Err(anyhow!(
+            "같은 버전인데 잠금 파일의 해시와 내용이 다른 확장 프로그램이 {}개 있습니다 (격리됨): {}",
+            mismatched.len(),
+            mismatched.join(", ")
+        ));
+    }
+    
+    Ok(())
+}
+
+/// `--fail-on-removed`면 업스트림에서 삭제된 확장 프로그램이 있을 때 실패로 종료합니다.
+fn check_removed_upstream(results: &Results, args: &SyncArgs) -> Result<()> {
+    if args.fail_on_removed && !results.removed_upstream.is_empty() {
+        return Err(anyhow!(
+            "업스트림에서 삭제된 확장 프로그램이 {}개 있습니다: {}",
+            results.removed_upstream.len(),
+            results.removed_upstream.join(", ")
+        ));
+    }
+    
+    Ok(())
+}
+
+/// Open VSX 확인 결과
+enum CheckOutcome {
+    Available(AvailableExtension),
+    Unavailable(UnavailableExtension),
+}
+
+/// Open VSX 응답의 `bundledExtensions`(확장 팩 구성)를 `publisher.name` ID 목록으로 읽습니다.
+fn bundled_extensions(data: &serde_json::Value) -> Vec<String> {
+    data.get("bundledExtensions")
+        .and_then(|v| v.as_array())
+        .map(|bundled| bundled.iter()
+            .filter_map(|b| Some(format!("{}.{}", b.get("namespace")?.as_str()?, b.get("extension")?.as_str()?)))
+            .collect())
+        .unwrap_or_default()
+}
+
+/// Open VSX 응답에서 사용 중단 표시와 ID 대소문자 차이를 경고로 남깁니다.
+fn record_openvsx_warnings(extension: &Extension, data: &serde_json::Value) {
+    if data.get("deprecated").and_then(|v| v.as_bool()) == Some(true) {
+        let replacement = data.get("replacement")
+            .and_then(|r| r.get("displayName").or_else(|| r.get("url")))
+            .and_then(|v| v.as_str());
+        let message = match replacement {
+            Some(replacement) => format!("Open VSX에서 사용 중단된 확장 프로그램입니다 (대체: {})", replacement),
+            None => "Open VSX에서 사용 중단된 확장 프로그램입니다".to_string(),
+        };
+        warnings::push(&extension.id, WarningKind::Deprecated, message);
+    }
+    
+    let registry_id = data.get("namespace").and_then(|v| v.as_str())
+        .zip(data.get("name").and_then(|v| v.as_str()))
+        .map(|(namespace, name)| format!("{}.{}", namespace, name));
+    if let Some(registry_id) = registry_id {
+        record_id_case(&extension.id, &registry_id);
+    }
+}
+
+/// 목록의 ID가 레지스트리의 ID와 대소문자만 다르면 경고로 남깁니다.
+fn record_id_case(listed_id: &str, registry_id: &str) {
+    if listed_id != registry_id && listed_id.eq_ignore_ascii_case(registry_id) {
+        warnings::push(listed_id, WarningKind::IdCase, format!("레지스트리의 ID는 {}입니다 (대소문자 구분 없이 같은 항목으로 처리)", registry_id));
+    }
+}
+
+/// Open VSX에서 확장 프로그램을 확인해 바로 받을 수 있는지, Marketplace에서 받아야 하는지 판단합니다.
+fn open_vsx_url(extension: &Extension) -> String {
+    match &extension.version {
+        Some(version) => format!("{}/{}/{}", OPEN_VSX_API, extension.id.replace(".", "/"), version),
+        None => format!("{}/{}", OPEN_VSX_API, extension.id.replace(".", "/")),
+    }
+}
+
+/// `verified_only`면 확인된(verified) 네임스페이스가 아닌 Open VSX 확장 프로그램도 Marketplace에서 받도록 판단합니다.
+async fn check_extension(client: &HttpClient, extension: &Extension, verified_only: bool) -> Result<CheckOutcome> {
+    // Open VSX에서 확장 프로그램 확인
+    let url = open_vsx_url(extension);
+    let started = attempt_start();
+    
+    match client.get(&url).await {
+        Ok(response) => {
+            if response.status.is_success() {
+                let data: serde_json::Value = response.json().await
+                    .with_context(|| format!("Failed to parse response for extension: {}", extension.id))?;
+                record_openvsx_warnings(extension, &data);
+                
+                // Open VSX API 구조 확인 - files.download 또는 downloads.universal 필드에서 URL 가져오기
+                let download_url = data.get("files")
+                    .and_then(|files| files.get("download"))
+                    .and_then(|v| v.as_str())
+                    .or_else(|| {
+                        data.get("downloads")
+                            .and_then(|downloads| downloads.get("universal"))
+                            .and_then(|v| v.as_str())
+                    });
+                
+                // 게시자가 네임스페이스 소유자로 확인되었는지 여부
+                let verified = data.get("verified").and_then(|v| v.as_bool());
+                let unverified = verified_only && verified != Some(true);
+                
+                if let Some(url) = download_url.filter(|_| !unverified) {
+                    // Open VSX에서 사용 가능한 확장 프로그램
+                    println!("{} {}: {}", "확인".green(), extension.id, "Open VSX에서 사용 가능".green());
+                    Ok(CheckOutcome::Available(AvailableExtension {
+                        id: extension.id.clone(),
+                        uuid: extension.uuid.clone(),
+                        url: url.to_string(),
+                        version: data.get("version").and_then(|v| v.as_str()).map(|v| v.to_string()),
+                        source_file: extension.source_file.clone(),
+                        verified,
+                        last_success: None,
+                        version_history: Vec::new(),
+                        extension_pack: bundled_extensions(&data),
+                        included_in: Vec::new(),
+                    }))
+                } else {
+                    // Open VSX에 있지만 다운로드 URL이 없거나 확인되지 않은 네임스페이스인 경우 - VSCode Marketplace에서 다운로드 필요
+                    let reason = if unverified && download_url.is_some() {
+                        "확인되지 않은 Open VSX 네임스페이스, VSCode Marketplace에서 다운로드 필요"
+                    } else {
+                        "VSCode Marketplace에서 다운로드 필요"
+                    };
+                    println!("{} {}: {}", "확인".yellow(), extension.id, reason.yellow());
+                    Ok(CheckOutcome::Unavailable(UnavailableExtension {
+                        id: extension.id.clone(),
+                        uuid: extension.uuid.clone(),
+                        version: extension.version.clone(),
+                        source: extension.source,
+                        source_file: extension.source_file.clone(),
+                        error: None,
+                        last_success: None,
+                        version_history: Vec::new(),
+                        attempts: Vec::new(),
+                        suggestions: Vec::new(),
+                        extension_pack: bundled_extensions(&data),
+                        included_in: Vec::new(),
+                    }))
+                }
+            } else {
+                // Open VSX에 없는 확장 프로그램 - VSCode Marketplace에서 다운로드 필요
+                let error = VsixError::from_status(response.status, &url);
+                Ok(CheckOutcome::Unavailable(UnavailableExtension {
+                    id: extension.id.clone(),
+                    uuid: extension.uuid.clone(),
+                    version: extension.version.clone(),
+                    source: extension.source,
+                    source_file: extension.source_file.clone(),
+                    error: Some(error.code()),
+                    last_success: None,
+                    version_history: Vec::new(),
+                    attempts: vec![Attempt::record(AttemptStage::Check, &url, started, Some(&error.into()))],
+                    suggestions: Vec::new(),
+                    extension_pack: Vec::new(),
+                    included_in: Vec::new(),
+                }))
+            }
+        },
+        Err(error) => {
+            // 요청 오류 - VSCode Marketplace에서 다운로드 시도
+            Ok(CheckOutcome::Unavailable(UnavailableExtension {
+                id: extension.id.clone(),
+                uuid: extension.uuid.clone(),
+                version: extension.version.clone(),
+                source: extension.source,
+                source_file: extension.source_file.clone(),
+                error: Some(error.code()),
+                last_success: None,
+                version_history: Vec::new(),
+                attempts: vec![Attempt::record(AttemptStage::Check, &url, started, Some(&error.into()))],
+                suggestions: Vec::new(),
+                extension_pack: Vec::new(),
+                included_in: Vec::new(),
+            }))
+        }
+    }
+}
+
+/// 확인 단계와 다운로드 단계를 채널로 연결해 동시에 진행합니다 (--pipeline).
+/// Marketplace에서 받아야 하는 확장 프로그램은 확인되는 즉시 다운로드를 시작합니다.
+async fn check_and_download_pipelined(
+    client: &HttpClient,
+    extensions: &[Extension],
+    args: &SyncArgs,
+    run: &RunMetadata,
+    results: &mut Results,
+    limiter: &Semaphore,
+    tracker: &WorkTracker
+) -> Result<DownloadSummary> {
+    // 채널 크기를 제한해 다운로드가 밀려도 확인 단계가 앞서 나가며 메모리를 쌓지 않게 함
+    let (sender, receiver) = tokio::sync::mpsc::channel::<UnavailableExtension>(args.concurrency.max(1));
+    
+    println!("{}", "확인이 끝난 확장 프로그램부터 VSCode Marketplace에서 다운로드합니다...".blue());
+    let progress = &plain::Progress::default();
+    
+    let check_stage = async move {
+        let to_check: Vec<&Extension> = extensions.iter().filter(|e| !e.id.is_empty()).collect();
+        let check_bar = check_progress_bar(to_check.len());
+        let mut checks = futures_util::stream::iter(to_check)
+            .map(|extension| with_permit(limiter, tracked_check(tracker, client, extension, args)))
+            .buffer_unordered(args.concurrency.max(1));
+        
+        while let Some(outcome) = checks.next().await {
+            let outcome = match outcome {
+                Ok(outcome) => outcome,
+                Err(e) => return Err(cancel_remaining(&mut checks, e, args).await),
+            };
+            check_bar.inc(1);
+            match outcome {
+                CheckOutcome::Available(extension) => results.available.push(extension),
+                CheckOutcome::Unavailable(extension) => {
+                    // 다운로드 단계가 먼저 끝났으면(중단) 확인만 계속
+                    progress.add_total(1);
+                    let _ = sender.send(extension.clone()).await;
+                    results.unavailable.push(extension);
+                },
+            }
+            if let Err(e) = flush_results(results, args, &run.timestamp) {
+                return Err(cancel_remaining(&mut checks, e, args).await);
+            }
+        }
+        check_bar.finish_and_clear();
+        
+        // 여기서 sender가 해제되어 다운로드 단계가 남은 항목을 처리한 뒤 끝남
+        Ok::<(), anyhow::Error>(())
+    };
+    
+    let download_stage = async {
+        let queue = futures_util::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|extension| (extension, receiver))
+        });
+        let mut downloads = std::pin::pin!(queue
+            .map(|extension| async move {
+                with_permit(limiter, tracked_download(tracker, client, &extension, args, run, progress)).await
+            })
+            .buffer_unordered(args.concurrency.max(1)));
+        
+        let started = Instant::now();
+        let mut summary = DownloadSummary::default();
+        while let Some(outcome) = downloads.next().await {
+            match outcome {
+                Ok(outcome) => summary.record(outcome),
+                Err(e) => return Err(cancel_remaining(&mut downloads, e, args).await),
+            }
+        }
+        summary.wall_time = started.elapsed();
+        Ok::<DownloadSummary, anyhow::Error>(summary)
+    };
+    
+    // 한 단계가 중단되면 취소 신호로 다른 단계도 멈추므로 두 단계가 모두 끝날 때까지 기다림
+    match tokio::join!(check_stage, download_stage) {
+        (Ok(()), Ok(summary)) => Ok(summary),
+        // 취소된 단계가 아니라 먼저 실패한 단계의 오류를 보고
+        (Err(e), Err(other)) if e.is::<Cancelled>() => Err(other),
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    }
+}
+
+/// 목록을 `--batch-size`개씩 나눠 묶음마다 확인과 다운로드를 마치고 결과 파일을 기록합니다.
+/// 중간에 중단돼도 끝난 묶음의 결과와 다운로드 정보가 파일에 남고, 한 번에 쌓아 두는 작업이 묶음 크기로 제한됩니다.
+async fn check_and_download_batched(
+    client: &HttpClient,
+    extensions: &[Extension],
+    args: &SyncArgs,
+    run: &RunMetadata,
+    results: &mut Results,
+    limiter: &Semaphore,
+    tracker: &WorkTracker
+) -> Result<DownloadSummary> {
+    let batch_size = args.batch_size.unwrap_or(1) as usize;
+    let batch_count = extensions.len().div_ceil(batch_size);
+    let progress = plain::Progress::default();
+    let check_bar = check_progress_bar(extensions.iter().filter(|e| !e.id.is_empty()).count());
+    let started = Instant::now();
+    let mut summary = DownloadSummary::default();
+    
+    for (index, batch) in extensions.chunks(batch_size).enumerate() {
+        println!("{}", format!("묶음 {}/{}: 확장 프로그램 {}개를 처리합니다...", index + 1, batch_count, batch.len()).blue());
+        
+        let mut checks = futures_util::stream::iter(batch.iter().filter(|e| !e.id.is_empty()))
+            .map(|extension| with_permit(limiter, tracked_check(tracker, client, extension, args)))
+            .buffer_unordered(args.concurrency.max(1));
+        
+        let mut to_download = Vec::new();
+        while let Some(outcome) = checks.next().await {
+            let outcome = match outcome {
+                Ok(outcome) => outcome,
+                Err(e) => return Err(cancel_remaining(&mut checks, e, args).await),
+            };
+            check_bar.inc(1);
+            match outcome {
+                CheckOutcome::Available(extension) => results.available.push(extension),
+                CheckOutcome::Unavailable(extension) => {
+                    to_download.push(extension.clone());
+                    results.unavailable.push(extension);
+                },
+            }
+        }
+        
+        progress.add_total(to_download.len());
+        let mut downloads = futures_util::stream::iter(&to_download)
+            .map(|extension| with_permit(limiter, tracked_download(tracker, client, extension, args, run, &progress)))
+            .buffer_unordered(args.concurrency.max(1));
+        while let Some(outcome) = downloads.next().await {
+            match outcome {
+                Ok(outcome) => summary.record(outcome),
+                Err(e) => return Err(cancel_remaining(&mut downloads, e, args).await),
+            }
+        }
+        
+        // 묶음의 다운로드 결과까지 반영해 결과 파일 기록
+        tracker.attach_download_reports(results);
+        flush_results(results, args, &run.timestamp)?;
+    }
+    check_bar.finish_and_clear();
+    
+    summary.wall_time = started.elapsed();
+    Ok(summary)
+}
+
+/// 여러 목록 파일을 순서대로 읽어 하나로 합칩니다.
+/// 같은 ID(대소문자 무시)가 여러 번 나오면 `policy`에 따라 어느 항목을 쓸지 정하고,
+/// 목록에서의 위치는 처음 나온 위치를 유지합니다.
+async fn load_extension_lists(
+    client: &HttpClient,
+    files: &[PathBuf],
+    headers: &[(String, String)],
+    policy: ConflictPolicy,
+    allow_unknown_fields: bool
+) -> Result<Vec<Extension>> {
+    let mut merged: Vec<Extension> = Vec::new();
+    
+    for file_path in &input::expand_globs(files)? {
+        // VSCode 원격 서버의 확장 프로그램 디렉토리(또는 그 압축 파일)면 설치된 목록을 사용
+        let extensions = match vscode_server::read_list(file_path)? {
+            Some(extensions) => extensions,
+            None => {
+                // YAML 파일 읽기 (URL이면 원격에서 가져오기)
+                let content = input::read_list_source(client, file_path, headers).await?;
+                input::parse_extension_list(&content, file_path, allow_unknown_fields)?
+            },
+        };
+        
+        for mut extension in extensions.enabled.unwrap_or_default() {
+            // 페이지 URL로 지정된 항목을 ID와 버전으로 정규화
+            let (id, version) = input::parse_extension_reference(&extension.id)?;
+            extension.id = id;
+            if extension.version.is_none() {
+                extension.version = version;
+            }
+            extension.source_file = Some(file_path.display().to_string());
+            
+            match merged.iter_mut().find(|e| e.id.eq_ignore_ascii_case(&extension.id)) {
+                Some(existing) => {
+                    let replace = match policy {
+                        ConflictPolicy::Last => true,
+                        _ if existing.version == extension.version => false,
+                        ConflictPolicy::First => false,
+                        ConflictPolicy::Highest => {
+                            compare_pinned_versions(extension.version.as_deref(), existing.version.as_deref()) == Ordering::Greater
+                        },
+                        ConflictPolicy::Error => {
+                            return Err(anyhow!(
+                                "{}의 고정 버전이 서로 다릅니다: {} ({}) / {} ({})",
+                                extension.id,
+                                existing.version.as_deref().unwrap_or("latest"),
+                                existing.source_file.as_deref().unwrap_or("-"),
+                                extension.version.as_deref().unwrap_or("latest"),
+                                extension.source_file.as_deref().unwrap_or("-")
+                            ));
+                        },
+                    };
+                    
+                    if !replace {
+                        continue;
+                    }
+                    
+                    if existing.source_file != extension.source_file {
+                        println!(
+                            "{} {}: {} -> {}",
+                            "목록 항목 덮어씀".yellow(),
+                            extension.id,
+                            existing.source_file.as_deref().unwrap_or("-"),
+                            extension.source_file.as_deref().unwrap_or("-")
+                        );
+                    }
+                    *existing = extension;
+                },
+                None => merged.push(extension),
+            }
+        }
+    }
+    
+    // 필드 이름 오타 등으로 빈 목록이 되면 아무것도 하지 않고 성공한 것처럼 보이므로 실패로 처리
+    if merged.is_empty() {
+        let sources: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+        return Err(anyhow!("목록 파일에 확장 프로그램이 없습니다: {} (enabled 항목을 확인하세요)", sources.join(", ")));
+    }
+    
+    Ok(merged)
+}
+
+/// 버전 고정이 없는 항목은 최신 버전을 뜻하므로 어떤 고정 버전보다 높게 취급합니다.
+fn compare_pinned_versions(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => version::compare_versions(a, b),
+    }
+}
+
+/// 명령줄에 지정한 ID를 목록에 더합니다. 목록에 같은 ID가 있으면 명령줄 항목으로 바꿉니다.
+fn add_extension_ids(extensions: &mut Vec<Extension>, ids: &[String]) -> Result<()> {
+    for spec in ids {
+        let mut extension = input::extension_from_spec(spec.trim());
+        let (id, version) = input::parse_extension_reference(&extension.id)?;
+        extension.id = id;
+        if extension.version.is_none() {
+            extension.version = version;
+        }
+        extension.source_file = Some("명령줄".to_string());
+        
+        match extensions.iter_mut().find(|e| e.id.eq_ignore_ascii_case(&extension.id)) {
+            Some(existing) => *existing = extension,
+            None => extensions.push(extension),
+        }
+    }
+    Ok(())
+}
+
+/// Git에 커밋해도 변경 내역이 깔끔하도록 출력 목록을 ID(대소문자 무시), 버전 순으로 정렬합니다.
+fn sort_by_id<T>(items: &mut [T], key: impl Fn(&T) -> (&str, Option<&str>)) {
+    items.sort_by(|a, b| {
+        let (a_id, a_version) = key(a);
+        let (b_id, b_version) = key(b);
+        a_id.to_ascii_lowercase().cmp(&b_id.to_ascii_lowercase())
+            .then_with(|| compare_pinned_versions(a_version, b_version))
+    });
+}
+
+async fn merge_extension_lists(client: &HttpClient, args: &MergeArgs) -> Result<()> {
+    let mut merged = load_extension_lists(client, &args.file, args.file_header.as_slice(), args.on_conflict, args.allow_unknown_fields).await?;
+    sort_by_id(&mut merged, |e| (&e.id, e.version.as_deref()));
+    let count = merged.len();
+    
+    let run = RunMetadata::new(Some(&merged));
+    let serialized = serde_yaml::to_string(&Extensions { run: Some(run), enabled: Some(merged) })
+        .with_context(|| "Failed to serialize merged extension list")?;
+    
+    fs::write(&args.output, serialized)
+        .with_context(|| format!("Failed to write merged list to {}", args.output.display()))?;
+    
+    println!(
+        "{} {}",
+        format!("{} 개의 확장 프로그램 목록을 합쳤습니다:", count).green(),
+        args.output.display()
+    );
+    
+    Ok(())
+}
+
+/// 확장 프로그램 하나를 처리할 때마다 지금까지의 결과를 기록해, 중단되더라도 진행 상황이 남고
+/// 다른 도구가 실행 중에도 결과를 읽을 수 있게 합니다.
+fn flush_results(results: &mut Results, args: &SyncArgs, run_timestamp: &str) -> Result<()> {
+    results.link_packs();
+    results.sort();
+    results.warnings = warnings::snapshot();
+    write_sync_results(results, args, run_timestamp)
+}
+
+/// 동기화 결과를 기록합니다. `--append-results`면 기존 결과 파일과 합쳐서 기록합니다.
+fn write_sync_results(results: &Results, args: &SyncArgs, run_timestamp: &str) -> Result<()> {
+    let output_path = args.results_path();
+    
+    if !args.append_results {
+        return write_results(results, &output_path, &args.downloads_path(), args.results_format);
+    }
+    
+    let previous = if output_path.exists() {
+        let content = fs::read_to_string(&output_path)
+            .with_context(|| format!("Failed to read {}", output_path.display()))?;
+        args.results_format.deserialize(&content)
+            .with_context(|| format!("Failed to parse {}", output_path.display()))?
+    } else {
+        Results { run: None, available: Vec::new(), unavailable: Vec::new(), removed_upstream: Vec::new(), download_stats: None, warnings: Vec::new() }
+    };
+    
+    let downloads_path = args.downloads_path();
+    let merged = journal::with_downloads(&downloads_path, args.results_format, |downloads| {
+        Ok(merge_with_previous(previous, results, downloads, run_timestamp))
+    })?;
+    
+    write_results(&merged, &output_path, &downloads_path, args.results_format)
+}
+
+/// 이전 결과에 이번 실행 결과를 ID(대소문자 무시) 기준으로 합칩니다.
+/// 이번 실행에 없는 항목은 그대로 남고, 이번 실행에서 성공한 항목은 마지막 성공 시각을 갱신하며,
+/// 확인된 버전이 바뀌면 버전 기록에 추가합니다.
+fn merge_with_previous(mut previous: Results, current: &Results, downloads: &[DownloadInfo], run_timestamp: &str) -> Results {
+    for extension in &current.available {
+        let (last_success, mut version_history) = take_previous_entry(&mut previous, &extension.id);
+        record_version(&mut version_history, extension.version.as_deref(), run_timestamp);
+        
+        previous.available.push(AvailableExtension {
+            last_success: Some(run_timestamp.to_string()).or(last_success),
+            version_history,
+            ..extension.clone()
+        });
+    }
+    
+    for extension in &current.unavailable {
+        let (last_success, mut version_history) = take_previous_entry(&mut previous, &extension.id);
+        let download = downloads.iter().find(|d| d.success && d.id.eq_ignore_ascii_case(&extension.id));
+        
+        if let Some(download) = download {
+            record_version(&mut version_history, download.version.as_deref(), &download.timestamp);
+        }
+        
+        previous.unavailable.push(UnavailableExtension {
+            last_success: download.map(|d| d.timestamp.clone()).or(last_success),
+            version_history,
+            ..extension.clone()
+        });
+    }
+    
+    previous.run = current.run.clone();
+    previous.removed_upstream = current.removed_upstream.clone();
+    previous.download_stats = current.download_stats.clone();
+    previous.warnings = current.warnings.clone();
+    previous.link_packs();
+    previous.sort();
+    previous
+}
+
+/// 이전 결과에서 해당 ID의 항목을 꺼내 마지막 성공 시각과 버전 기록을 돌려줍니다.
+fn take_previous_entry(previous: &mut Results, id: &str) -> (Option<String>, Vec<VersionChange>) {
+    if let Some(index) = previous.available.iter().position(|e| e.id.eq_ignore_ascii_case(id)) {
+        let entry = previous.available.remove(index);
+        return (entry.last_success, entry.version_history);
+    }
+    
+    if let Some(index) = previous.unavailable.iter().position(|e| e.id.eq_ignore_ascii_case(id)) {
+        let entry = previous.unavailable.remove(index);
+        return (entry.last_success, entry.version_history);
+    }
+    
+    (None, Vec::new())
+}
+
+fn record_version(history: &mut Vec<VersionChange>, version: Option<&str>, timestamp: &str) {
+    let Some(version) = version else {
+        return;
+    };
+    
+    if history.last().is_some_and(|last| last.version == version) {
+        return;
+    }
+    
+    history.push(VersionChange {
+        version: version.to_string(),
+        timestamp: timestamp.to_string(),
+    });
+}
+
+fn write_results(results: &Results, output_path: &Path, downloads_path: &Path, results_format: ResultsFormat) -> Result<()> {
+    if results_format == ResultsFormat::Csv {
+        return journal::with_downloads(downloads_path, results_format, |downloads| {
+            output::write_csv(output_path, results, downloads)
+        });
+    }
+    
+    let serialized = results_format.serialize(results)
+        .with_context(|| "Failed to serialize results")?;
+    
+    output::write_atomically(output_path, serialized.as_bytes())
+        .with_context(|| format!("Failed to write results to {}", output_path.display()))
+}
+
+/// 다운로드 정보 파일에 아직 압축하지 않은 저널의 변경을 적용해 읽습니다.
+fn load_downloads(downloads_path: &Path, results_format: ResultsFormat) -> Result<Vec<DownloadInfo>> {
+    let mut downloads = Vec::new();
+    
+    if downloads_path.exists() {
+        let content = fs::read_to_string(downloads_path)
+            .with_context(|| format!("Failed to read {}", downloads_path.display()))?;
+        
+        downloads = results_format.deserialize(&content)
+            .with_context(|| format!("Failed to parse {}", downloads_path.display()))?;
+    }
+    
+    journal::replay(&mut downloads, downloads_path)?;
+    Ok(downloads)
+}
+
+async fn download_marketplace_extensions(
+    client: &HttpClient,
+    extensions: &[UnavailableExtension],
+    args: &SyncArgs,
+    run: &RunMetadata,
+    limiter: &Semaphore,
+    tracker: &WorkTracker
+) -> Result<DownloadSummary> {
+    let output_dir = args.output_dir.as_path();
+    
+    println!("{}", "VSCode Marketplace에서 확장 프로그램 다운로드 중...".blue());
+    
+    // 다운로드 디렉토리 생성
+    create_dir_all(filename::long_path(output_dir))
+        .with_context(|| format!("Failed to create directory: {}", output_dir.display()))?;
+    
+    let started = Instant::now();
+    let progress = plain::Progress::new(extensions.len());
+    let mut downloads = futures_util::stream::iter(extensions)
+        .map(|extension| with_permit(limiter, tracked_download(tracker, client, extension, args, run, &progress)))
+        .buffer_unordered(args.concurrency.max(1));
+    
+    let mut summary = DownloadSummary::default();
+    while let Some(outcome) = downloads.next().await {
+        match outcome {
+            Ok(outcome) => summary.record(outcome),
+            Err(e) => return Err(cancel_remaining(&mut downloads, e, args).await),
+        }
+    }
+    summary.wall_time = started.elapsed();
+    
+    summary.print();
+    
+    Ok(summary)
+}
+
+/// 이번 실행에서 게시자 소유권 변경이나 Open VSX 해시 불일치가 감지된 확장 프로그램을 다운로드 요약 뒤에 다시 한 번 경고합니다.
+fn warn_integrity_findings(args: &SyncArgs, run: &RunMetadata) -> Result<()> {
+    let downloads = load_downloads(&args.downloads_path(), args.results_format)?;
+    let this_run: Vec<&DownloadInfo> = downloads.iter()
+        .filter(|d| d.run.as_ref().is_some_and(|r| r.run_id == run.run_id))
+        .collect();
+    
+    let changed: Vec<&&DownloadInfo> = this_run.iter()
+        .filter(|d| d.publisher_change.is_some())
+        .collect();
+    if !changed.is_empty() {
+        println!("{}", format!("게시자 소유권이 바뀐 확장 프로그램 {}개:", changed.len()).red().bold());
+        for download in changed {
+            println!("  {}: {}", download.id.red(), download.publisher_change.as_deref().unwrap_or_default());
+        }
+    }
+    
+    let mismatched: Vec<&&DownloadInfo> = this_run.iter()
+        .filter(|d| d.openvsx_sha256.is_some() && d.openvsx_sha256 != d.sha256)
+        .collect();
+    if !mismatched.is_empty() {
+        println!("{}", format!("Open VSX 해시와 다른 확장 프로그램 {}개:", mismatched.len()).red().bold());
+        for download in mismatched {
+            println!("  {} {}", download.id.red(), download.version.as_deref().unwrap_or("-"));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Marketplace에서 받은 파일의 해시를 Open VSX에 게시된 같은 버전의 해시와 비교해 다운로드 정보 파일에 기록합니다.
+/// Open VSX에 같은 버전이 없으면 건너뛰고, 조회에 실패하면 경고만 출력합니다.
+async fn cross_check_openvsx(
+    client: &HttpClient,
+    download_info: &DownloadInfo,
+    downloaded: &DownloadedFile,
+    downloads_path: &Path
+) -> Result<()> {
+    let Some(version) = download_info.version.as_deref() else {
+        return Ok(());
+    };
+    
+    // 플랫폼별 빌드는 같은 대상 플랫폼의 Open VSX 빌드와 비교
+    let target = vsix::read_identity(Path::new(&download_info.download_path)).ok()
+        .and_then(|identity| identity.target_platform);
+    
+    let published = match crosscheck::published_sha256(client, &download_info.id, version, target.as_deref()).await {
+        Ok(Some(published)) => published,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            println!("{} {}: Open VSX 해시를 확인하지 못했습니다 ({:#})", "경고:".yellow(), download_info.id, e);
+            return Ok(());
+        }
+    };
+    
+    if published == downloaded.sha256 {
+        println!("{} {}", "Open VSX 해시와 일치:".green(), download_info.id);
+    } else {
+        println!(
+            "{} {} (Marketplace {}, Open VSX {})",
+            "Open VSX 해시와 다름:".red().bold(), download_info.id, downloaded.sha256, published
+        );
+    }
+    
+    journal::append(downloads_path, &journal::Event::CrossChecked {
+        id: download_info.id.clone(),
+        openvsx_sha256: published,
+    })
+}
+
+/// 정책에 따라 받지 않은 확장 프로그램을 요청 기록과 다운로드 정보 파일에 남깁니다.
+fn skip_download(download_info: &DownloadInfo, reason: VsixError, report: &mut DownloadReport, downloads_path: &Path) -> Result<DownloadOutcome> {
+    let code = reason.code();
+    let err = anyhow::Error::from(reason);
+    println!("{} {}: {}", "다운로드 건너뜀".yellow(), download_info.file_name, err);
+    report.attempts.push(Attempt::record(AttemptStage::Download, &download_info.direct_download_url, attempt_start(), Some(&err)));
+    update_download_status(download_info, Err(DownloadFailure { code, quarantine_path: None }), downloads_path)?;
+    Ok(DownloadOutcome::Skipped)
+}
+
+/// `source: openvsx`로 고정했지만 Open VSX에서 받을 수 없는 항목을 다른 실패와 같이 요청 기록과 다운로드 정보 파일에 남깁니다.
+/// Marketplace는 조회하지 않으므로 버전과 다운로드 URL 대신 Open VSX 주소를 기록합니다.
+fn pinned_source_failure(extension: &UnavailableExtension, args: &SyncArgs, run: &RunMetadata, report: &mut DownloadReport) -> Result<DownloadOutcome> {
+    let downloads_path = args.downloads_path();
+    let url = match &extension.version {
+        Some(version) => format!("{}/{}/{}", OPEN_VSX_API, extension.id.replace('.', "/"), version),
+        None => format!("{}/{}", OPEN_VSX_API, extension.id.replace('.', "/")),
+    };
+    let file_name = filename::vsix_file_name(&extension.id);
+    let download_info = DownloadInfo {
+        id: extension.id.clone(),
+        marketplace_url: url.clone(),
+        direct_download_url: url,
+        fallback_download_url: None,
+        download_path: args.output_dir.join(&file_name).to_string_lossy().to_string(),
+        file_name,
+        version: extension.version.clone(),
+        target_platform: None,
+        timestamp: Utc::now().to_rfc3339(),
+        success: false,
+        size: None,
+        sha256: None,
+        error: None,
+        quarantine_path: None,
+        run: Some(run.clone()),
+        publisher: None,
+        publisher_change: None,
+        openvsx_sha256: None,
+        duration_ms: None,
+        bytes_per_second: None,
+        assets: Vec::new(),
+        scan: None,
+        origin: None,
+        asset_sources: Vec::new(),
+        manifest_urls: Vec::new(),
+    };
+    journal::append(&downloads_path, &journal::Event::Resolved { info: Box::new(download_info.clone()) })?;
+    
+    let reason = VsixError::SourcePinned { id: extension.id.clone() };
+    let code = reason.code();
+    let err = anyhow::Error::from(reason);
+    println!("{} {}: {}", "다운로드 실패".red(), extension.id, err);
+    report.attempts.push(Attempt::record(AttemptStage::Download, &download_info.direct_download_url, attempt_start(), Some(&err)));
+    update_download_status(&download_info, Err(DownloadFailure { code, quarantine_path: None }), &downloads_path)?;
+    Ok(DownloadOutcome::Failed)
+}
+
+/// before_download 훅에 표준 입력으로 보내는 다운로드 정보
+#[derive(Serialize)]
+struct PolicyMetadata<'a> {
+    id: &'a str,
+    version: Option<&'a str>,
+    target_platform: Option<&'a str>,
+    publisher: Option<&'a PublisherInfo>,
+    /// 응답의 Content-Length (모르면 없음)
+    size: Option<u64>,
+    /// package.json의 license (없거나 읽지 못하면 없음)
+    license: Option<String>,
+    url: &'a str,
+}
+
+/// before_download 훅이 있으면 크기와 라이선스를 조회해 훅에 넘기고, 거부했으면 그 이유를 돌려줍니다.
+async fn download_policy(client: &HttpClient, download_info: &DownloadInfo, args: &SyncArgs) -> Result<Option<String>> {
+    if args.hooks.before_download.is_none() {
+        return Ok(None);
+    }
+    
+    // 본문은 받지 않고 HEAD 응답 헤더의 크기만 사용
+    let size = match client.head(&download_info.direct_download_url).await {
+        Ok(response) if response.status.is_success() => response.content_length(),
+        _ => None,
+    };
+    
+    let mut license = None;
+    for url in &download_info.manifest_urls {
+        let Ok(response) = client.get(url).await else {
+            continue;
+        };
+        // 오류 응답 본문을 라이선스로 읽지 않음
+        if !response.status.is_success() {
+            continue;
+        }
+        if let Ok(manifest) = response.json::<serde_json::Value>().await {
+            // 예전 형식의 {"type": "MIT"}도 허용
+            license = manifest["license"].as_str()
+                .or_else(|| manifest["license"]["type"].as_str())
+                .map(str::to_string);
+            break;
+        }
+    }
+    
+    let metadata = PolicyMetadata {
+        id: &download_info.id,
+        version: download_info.version.as_deref(),
+        target_platform: download_info.target_platform.as_deref(),
+        publisher: download_info.publisher.as_ref(),
+        size,
+        license,
+        url: &download_info.direct_download_url,
+    };
+    let size = metadata.size.map(|size| size.to_string()).unwrap_or_default();
+    
+    hooks::before_download(&args.hooks, &[
+        ("id", metadata.id),
+        ("version", metadata.version.unwrap_or_default()),
+        ("publisher", metadata.publisher.map(|p| p.name.as_str()).unwrap_or_default()),
+        ("size", size.as_str()),
+        ("license", metadata.license.as_deref().unwrap_or_default()),
+    ], &metadata).await
+}
+
+/// VSIX와 함께 받은 자산을 다운로드 정보 파일에 기록합니다.
+fn record_assets(extension_id: &str, downloaded: Vec<assets::DownloadedAsset>, downloads_path: &Path) -> Result<()> {
+    journal::append(downloads_path, &journal::Event::Assets {
+        id: extension_id.to_string(),
+        assets: downloaded,
+    })
+}
+
+/// 다른 작업의 중단 오류로 취소된 작업의 오류
+#[derive(Debug, thiserror::Error)]
+#[error("다른 작업이 실패해 취소되었습니다")]
+struct Cancelled;
+
+/// 작업 하나가 중단 오류를 내면 진행 중인 나머지 작업에 취소 신호를 보냅니다.
+/// --fail-fast면 취소된 작업이 작업 상태를 되돌리고 끝날 때까지 기다린 뒤 처음 오류를 돌려줍니다.
+async fn cancel_remaining<S: Stream + Unpin>(tasks: &mut S, error: anyhow::Error, args: &SyncArgs) -> anyhow::Error {
+    args.cancel.cancel();
+    if args.fail_fast {
+        while tasks.next().await.is_some() {}
+    }
+    error
+}
+
+/// 전역 동시 작업 한도(--concurrency) 안에서 작업을 실행합니다.
+/// 세마포어가 요청 순서대로 허가를 내주므로 큰 다운로드가 작업을 독점하지 않습니다.
+async fn with_permit<T>(limiter: &Semaphore, task: impl Future<Output = T>) -> T {
+    let _permit = limiter.acquire().await.expect("limiter is never closed");
+    task.await
+}
+
+/// 이어서 실행할 때 이미 확인한 항목은 저장된 결과를 쓰고, 새로 확인한 결과는 작업 상태에 기록합니다.
+/// `--strict`가 아니면 확인 중 오류가 나도 중단하지 않고, 오류 코드와 함께 Marketplace 다운로드 대상으로 기록합니다.
+async fn tracked_check(tracker: &WorkTracker, client: &HttpClient, extension: &Extension, args: &SyncArgs) -> Result<CheckOutcome> {
+    if let Some(outcome) = tracker.checked(&extension.id) {
+        println!("{} {}: {}", "확인".green(), extension.id, "이전 실행에서 확인됨".green());
+        return Ok(outcome);
+    }
+    if args.cancel.is_cancelled() {
+        return Err(Cancelled.into());
+    }
+    
+    tracker.set_status(&extension.id, WorkStatus::Checking)?;
+    let started = attempt_start();
+    // 목록에서 Marketplace로 고정한 항목은 Open VSX를 확인하지 않음
+    let checked = if args.marketplace_only || extension.source == Some(auth::Registry::Marketplace) {
+        Ok(CheckOutcome::Unavailable(UnavailableExtension {
+            id: extension.id.clone(),
+            uuid: extension.uuid.clone(),
+            version: extension.version.clone(),
+            source: extension.source,
+            source_file: extension.source_file.clone(),
+            error: None,
+            last_success: None,
+            version_history: Vec::new(),
+            attempts: Vec::new(),
+            suggestions: Vec::new(),
+            extension_pack: Vec::new(),
+            included_in: Vec::new(),
+        }))
+    } else if let Some(cached) = check_cache::lookup(extension, args.openvsx_only_verified(&extension.id)) {
+        println!("{} {}: {}", "확인".green(), extension.id, "캐시된 확인 결과 사용 (--check-ttl)".green());
+        Ok(cached)
+    } else {
+        let verified_only = args.openvsx_only_verified(&extension.id);
+        let checked = tokio::select! {
+            biased;
+            _ = args.cancel.cancelled() => {
+                // 다시 실행하면 처음부터 확인하도록 상태를 되돌림
+                tracker.set_status(&extension.id, WorkStatus::Pending)?;
+                return Err(Cancelled.into());
+            },
+            checked = check_extension(client, extension, verified_only) => checked,
+        };
+        // 캐시 파일을 쓰지 못해도 이 확장 프로그램의 확인 결과는 그대로 사용
+        if let Ok(outcome) = &checked {
+            if let Err(e) = check_cache::store(extension, verified_only, outcome) {
+                println!("{} {}: {:#}", "확인 결과를 캐시에 기록하지 못했습니다".yellow(), extension.id, e);
+            }
+        }
+        checked
+    };
+    let outcome = match checked {
+        Ok(outcome) => outcome,
+        Err(e) if !args.stops_on_failure() => {
+            println!("{} {}: {:#}", "확인 실패".red(), extension.id, e);
+            CheckOutcome::Unavailable(UnavailableExtension {
+                id: extension.id.clone(),
+                uuid: extension.uuid.clone(),
+                version: extension.version.clone(),
+                source: extension.source,
+                source_file: extension.source_file.clone(),
+                error: Some(error::error_code(&e)),
+                last_success: None,
+                version_history: Vec::new(),
+                attempts: vec![Attempt::record(AttemptStage::Check, &open_vsx_url(extension), started, Some(&e))],
+                suggestions: Vec::new(),
+                extension_pack: Vec::new(),
+                included_in: Vec::new(),
+            })
+        },
+        Err(e) => return Err(e.context(format!("{} 확인에 실패해 중단합니다 ({})", extension.id, args.stop_flag()))),
+    };
+    tracker.record_check(&extension.id, &outcome)?;
+    
+    Ok(outcome)
+}
+
+/// 이어서 실행할 때 이미 다운로드한 항목은 건너뛰고, 다운로드 진행 상태를 작업 상태에 기록합니다.
+async fn tracked_download(
+    tracker: &WorkTracker,
+    client: &HttpClient,
+    extension: &UnavailableExtension,
+    args: &SyncArgs,
+    run: &RunMetadata,
+    progress: &plain::Progress
+) -> Result<DownloadOutcome> {
+    if tracker.is_downloaded(&extension.id) {
+        println!("{} {}", "이전 실행에서 다운로드됨:".green(), extension.id);
+        progress.report("이전 실행에서 다운로드됨", &extension.id, extension.version.as_deref(), None);
+        return Ok(DownloadOutcome::Downloaded(None));
+    }
+    
+    if args.cancel.is_cancelled() {
+        return Err(Cancelled.into());
+    }
+    
+    tracker.set_status(&extension.id, WorkStatus::Downloading)?;
+    let mut report = DownloadReport::default();
+    let downloaded = tokio::select! {
+        biased;
+        _ = args.cancel.cancelled() => {
+            // 받다 만 파일은 다시 실행할 때 덮어쓰도록 다운로드 전 상태로 되돌림
+            tracker.set_status(&extension.id, WorkStatus::Checked)?;
+            return Err(Cancelled.into());
+        },
+        downloaded = download_extension(client, extension, args, run, &mut report) => downloaded,
+    };
+    let outcome = match downloaded {
+        // --max-size-policy abort는 의도한 중단이므로 그대로 전달
+        Err(e) if !args.stops_on_failure() && error::error_code(&e) != ErrorCode::SizeLimitExceeded => {
+            println!("{} {}: {:#}", "다운로드 실패".red(), extension.id, e);
+            Ok(DownloadOutcome::Failed)
+        },
+        outcome => outcome,
+    };
+    
+    let status = match &outcome {
+        Ok(DownloadOutcome::Downloaded(_) | DownloadOutcome::Unchanged) => WorkStatus::Done,
+        _ => WorkStatus::Failed,
+    };
+    let progress_status = match &outcome {
+        Ok(DownloadOutcome::Downloaded(_)) => "다운로드 완료",
+        Ok(DownloadOutcome::Unchanged) => "이미 최신",
+        Ok(DownloadOutcome::Skipped) => "건너뜀",
+        Ok(DownloadOutcome::Failed) | Err(_) => "실패",
+    };
+    progress.report(progress_status, &extension.id, report.version.as_deref(), report.size);
+    tracker.record_download(&extension.id, report)?;
+    tracker.set_status(&extension.id, status)?;
+    
+    if args.stops_on_failure() && matches!(outcome, Ok(DownloadOutcome::Failed)) {
+        return Err(anyhow!("{} 다운로드에 실패해 중단합니다 ({})", extension.id, args.stop_flag()));
+    }
+    
+    outcome
 }
 
-#[derive(Subcommand)]
-enum Commands {
-    /// YAML 파일에서 확장 프로그램을 확인하고 VSCode Marketplace에서 다운로드합니다
-    Sync {
-        /// 확장 프로그램 목록이 포함된 YAML 파일 경로
-        #[arg(short, long)]
-        file: PathBuf,
-        
-        /// 결과를 저장할 JSON 파일 경로 (기본값: results.json)
-        #[arg(short = 'r', long, default_value = "results.json")]
-        output: PathBuf,
-        
-        /// 다운로드 디렉토리 (기본값: ./downloads)
-        #[arg(short, long, default_value = "downloads")]
-        output_dir: PathBuf,
-        
-        /// 확인 없이 자동으로 다운로드 실행
-        #[arg(short, long, default_value_t = false)]
-        auto_download: bool,
-    },
+/// 다운로드 경로에 이미 있는 VSIX가 받으려는 확장 프로그램, 버전과 같으면 그 파일의 크기와 해시
+fn existing_download(download_info: &DownloadInfo) -> Option<DownloadedFile> {
+    let path = Path::new(&download_info.download_path);
+    let identity = vsix::read_identity(path).ok()?;
+    
+    // --target을 바꾸면 같은 버전이라도 다른 플랫폼의 빌드를 다시 받음
+    if !identity.id().eq_ignore_ascii_case(&download_info.id)
+        || download_info.version.as_deref() != Some(identity.version.as_str())
+        || download_info.target_platform.as_ref().is_some_and(|target| identity.target_platform.as_ref() != Some(target))
+    {
+        return None;
+    }
+    
+    let (size, sha256) = vsix::file_digest(path).ok()?;
+    Some(DownloadedFile { size, sha256, duration_ms: None, origin: None })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Extensions {
-    enabled: Option<Vec<Extension>>,
+/// 받은 VSIX의 매니페스트가 요청한 게시자, 이름, 버전과 같은지 확인합니다.
+/// CDN이 다른 확장 프로그램이나 다른 버전의 파일을 돌려주는 경우를 잡아냅니다.
+fn verify_identity(download_info: &DownloadInfo) -> Result<(), VsixError> {
+    let path = Path::new(&download_info.download_path);
+    
+    // vsixmanifest가 없는 오래된 패키지는 package.json으로 확인
+    let actual = vsix::read_identity(path)
+        .map(|identity| (identity.id(), identity.version))
+        .or_else(|_| vsix::read_manifest(path).map(|package| (package.id, package.version)));
+    
+    let expected_version = download_info.version.as_deref();
+    let expected = format!("{}@{}", download_info.id, expected_version.unwrap_or("latest"));
+    
+    match actual {
+        Ok((id, version)) if id.eq_ignore_ascii_case(&download_info.id)
+            && expected_version.is_none_or(|v| v == version) => Ok(()),
+        Ok((id, version)) => Err(VsixError::IdentityMismatch { expected, actual: format!("{}@{}", id, version) }),
+        Err(_) => Err(VsixError::IdentityMismatch { expected, actual: "매니페스트를 읽을 수 없음".to_string() }),
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Extension {
-    id: String,
-    uuid: Option<String>,
+/// 파일을 받은 뒤 요청한 확장 프로그램이 맞는지, 잠금 파일에 기록된 내용과 같은지 확인하고 `on_download` 훅을 실행합니다.
+async fn download_and_verify(
+    client: &HttpClient,
+    url: &str,
+    download_info: &DownloadInfo,
+    args: &SyncArgs
+) -> Result<DownloadedFile> {
+    let downloaded = match resolver::download(&args.resolvers, url, &download_info.download_path, args.max_size).await {
+        Some(downloaded) => downloaded?,
+        None => download_file(client, url, &download_info.download_path, args.max_size, args.segments).await?,
+    };
+    validation::run(args.verify_jobs, &download_info.id, {
+        let download_info = download_info.clone();
+        move || verify_identity(&download_info)
+    }).await?;
+    if let Some(lockfile) = &args.lockfile {
+        lockfile::verify(lockfile, download_info, &downloaded)?;
+    }
+    if let Some(scanner) = &args.scan {
+        let verdict = scan::scan_file(scanner, &download_info.id, Path::new(&download_info.download_path)).await?;
+        journal::append(&args.downloads_path(), &journal::Event::Scanned {
+            id: download_info.id.clone(),
+            scan: verdict.clone(),
+        })?;
+        scan::ensure_clean(&download_info.id, &verdict)?;
+    }
+    hooks::on_download(&args.hooks, &[
+        ("file", download_info.download_path.as_str()),
+        ("id", download_info.id.as_str()),
+        ("version", download_info.version.as_deref().unwrap_or_default()),
+        ("sha256", downloaded.sha256.as_str()),
+    ]).await?;
+    Ok(downloaded)
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Results {
-    available: Vec<AvailableExtension>,
-    unavailable: Vec<UnavailableExtension>,
+/// 확장 프로그램 하나의 다운로드 결과
+enum DownloadOutcome {
+    /// 이번 실행에서 받았으면 전송량과 소요 시간 (이전 실행에서 받은 항목은 없음)
+    Downloaded(Option<TransferStats>),
+    /// 증분 동기화에서 같은 버전이 이미 있어 받지 않음
+    Unchanged,
+    Failed,
+    Skipped,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct AvailableExtension {
+/// 다운로드 한 건의 전송량과 소요 시간
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TransferStats {
     id: String,
-    uuid: Option<String>,
-    url: String,
+    bytes: u64,
+    duration_ms: u64,
+    bytes_per_second: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct UnavailableExtension {
-    id: String,
-    uuid: Option<String>,
+impl TransferStats {
+    fn new(id: &str, bytes: u64, duration_ms: u64) -> Self {
+        TransferStats {
+            id: id.to_string(),
+            bytes,
+            duration_ms,
+            bytes_per_second: bytes_per_second(bytes, duration_ms),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct DownloadInfo {
-    id: String,
-    marketplace_url: String,
-    direct_download_url: String,
-    download_path: String,
-    file_name: String,
-    version: Option<String>,
-    timestamp: String,
-    success: bool,
+/// 실행 전체의 다운로드 통계 (결과 파일의 `download_stats`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DownloadStats {
+    downloaded: usize,
+    total_bytes: u64,
+    /// 다운로드 단계 전체에 걸린 시간 (동시 다운로드가 겹치므로 각 다운로드 시간의 합보다 짧음)
+    wall_time_ms: u64,
+    /// 전체 전송량을 전체 시간으로 나눈 평균 속도
+    average_bytes_per_second: u64,
+    /// 평균 속도가 가장 느린 다운로드
+    slowest: Option<TransferStats>,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+/// 전송량과 소요 시간으로 계산한 초당 바이트 (0밀리초는 1밀리초로 계산)
+fn bytes_per_second(bytes: u64, duration_ms: u64) -> u64 {
+    (u128::from(bytes) * 1000 / u128::from(duration_ms.max(1))) as u64
+}
 
-    match &cli.command {
-        Commands::Sync { file, output, output_dir, auto_download } => {
-            sync_extensions(file, output, output_dir, *auto_download).await?
-        },
-    }
+fn format_speed(bytes_per_second: u64) -> String {
+    format!("{}/s", size::format_size(bytes_per_second))
+}
 
-    Ok(())
+#[derive(Default)]
+struct DownloadSummary {
+    success: usize,
+    unchanged: usize,
+    failure: usize,
+    skipped: usize,
+    transfers: Vec<TransferStats>,
+    /// 다운로드 단계 전체에 걸린 시간
+    wall_time: Duration,
 }
 
-async fn sync_extensions(file_path: &Path, output_path: &Path, output_dir: &Path, auto_download: bool) -> Result<()> {
-    println!("{}", "확장 프로그램 목록을 확인하는 중...".blue());
-    
-    // 결과 파일 초기화
-    if output_path.exists() {
-        println!("{}", "기존 결과 파일을 초기화합니다...".yellow());
-        fs::remove_file(output_path)
-            .with_context(|| format!("Failed to remove existing results file: {}", output_path.display()))?;
+impl DownloadSummary {
+    fn record(&mut self, outcome: DownloadOutcome) {
+        match outcome {
+            DownloadOutcome::Downloaded(transfer) => {
+                self.success += 1;
+                self.transfers.extend(transfer);
+            },
+            DownloadOutcome::Unchanged => self.unchanged += 1,
+            DownloadOutcome::Failed => self.failure += 1,
+            DownloadOutcome::Skipped => self.skipped += 1,
+        }
     }
     
-    // 다운로드 디렉토리 초기화
-    if output_dir.exists() {
-        println!("{}", "기존 다운로드 디렉토리를 초기화합니다...".yellow());
-        fs::remove_dir_all(output_dir)
-            .with_context(|| format!("Failed to remove existing download directory: {}", output_dir.display()))?;
+    fn print(&self) {
+        let mut message = format!("모든 확장 프로그램 처리 완료: {}개 성공, {}개 실패, {}개 건너뜀", 
+            self.success, 
+            self.failure,
+            self.skipped
+        );
+        if self.unchanged > 0 {
+            message.push_str(&format!(", {}개 이미 최신", self.unchanged));
+        }
+        
+        println!("{}", message.green());
+        
+        if let Some(stats) = self.stats() {
+            println!(
+                "{}",
+                format!("전송: {} ({}개), 소요 시간 {:.1}초, 평균 {}",
+                    size::format_size(stats.total_bytes),
+                    stats.downloaded,
+                    stats.wall_time_ms as f64 / 1000.0,
+                    format_speed(stats.average_bytes_per_second)
+                ).blue()
+            );
+            if let Some(slowest) = &stats.slowest {
+                println!(
+                    "{}",
+                    format!("가장 느린 다운로드: {} ({}, {:.1}초, {})",
+                        slowest.id,
+                        size::format_size(slowest.bytes),
+                        slowest.duration_ms as f64 / 1000.0,
+                        format_speed(slowest.bytes_per_second)
+                    ).blue()
+                );
+            }
+        }
     }
     
-    // 다운로드 디렉토리 생성
-    create_dir_all(output_dir)
-        .with_context(|| format!("Failed to create download directory: {}", output_dir.display()))?;
+    /// 이번 실행에서 실제로 받은 항목이 있으면 전송량과 속도 통계
+    fn stats(&self) -> Option<DownloadStats> {
+        if self.transfers.is_empty() {
+            return None;
+        }
+        
+        let total_bytes = self.transfers.iter().map(|t| t.bytes).sum();
+        let wall_time_ms = self.wall_time.as_millis() as u64;
+        let slowest = self.transfers.iter()
+            .min_by_key(|t| t.bytes_per_second)
+            .cloned();
+        
+        Some(DownloadStats {
+            downloaded: self.transfers.len(),
+            total_bytes,
+            wall_time_ms,
+            average_bytes_per_second: bytes_per_second(total_bytes, wall_time_ms),
+            slowest,
+        })
+    }
+}
+
+/// VSCode Marketplace에서 확장 프로그램 하나를 다운로드하고 다운로드 정보 파일에 상태를 기록합니다.
+/// 동기화 전체를 중단해야 하는 경우(`--max-size-policy abort`)에만 오류를 반환합니다.
+/// 조회와 다운로드 요청마다 결과를 `report`에 기록합니다.
+async fn download_extension(
+    client: &HttpClient,
+    extension: &UnavailableExtension,
+    args: &SyncArgs,
+    run: &RunMetadata,
+    report: &mut DownloadReport
+) -> Result<DownloadOutcome> {
+    let output_dir = args.output_dir.as_path();
+    let downloads_path = args.downloads_path();
     
-    // YAML 파일 읽기
-    let yaml_content = fs::read_to_string(file_path)
-        .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+    // 목록에서 Open VSX로 고정한 항목은 Open VSX에서 받지 못했어도 Marketplace로 넘어가지 않음
+    if extension.source == Some(auth::Registry::OpenVsx) {
+        return pinned_source_failure(extension, args, run, report);
+    }
     
-    let extensions: Extensions = serde_yaml::from_str(&yaml_content)
-        .with_context(|| "Failed to parse YAML file")?;
+    println!("{} {}", extension.id.yellow(), "다운로드 중...".blue());
     
-    let enabled_extensions = extensions.enabled.unwrap_or_default();
+    // 파일 이름 생성 - ID를 우선적으로 사용
+    let file_name = filename::vsix_file_name(&extension.id);
     
-    println!("{} {}", "총".blue(), format!("{} 개의 확장 프로그램을 확인합니다...", enabled_extensions.len()).blue());
+    // 다운로드 정보 생성
+    let started = attempt_start();
+    let download_info = create_download_info(client, &extension.id, extension.version.as_deref(), Some(&file_name), args, run).await;
+    report.attempts.push(Attempt::record(AttemptStage::Query, marketplace::EXTENSION_QUERY_URL, started, download_info.as_ref().err()));
     
-    let mut results = Results {
-        available: Vec::new(),
-        unavailable: Vec::new(),
+    let download_info = match download_info {
+        Ok(download_info) => download_info,
+        Err(err) => {
+            println!("{} {}: {}", extension.id.red(), "다운로드 정보 생성 실패".red(), err);
+            
+            // Open VSX와 Marketplace 모두에 없으면 ID 오타일 가능성이 높음
+            if extension.error == Some(ErrorCode::NotFound) && error::error_code(&err) == ErrorCode::NotFound {
+                report.suggestions = suggest::similar_ids(client, &extension.id).await;
+                if !report.suggestions.is_empty() {
+                    println!("{} {}?", "혹시 이 확장 프로그램인가요:".yellow(), report.suggestions.join(", "));
+                }
+            }
+            return Ok(DownloadOutcome::Failed);
+        }
     };
     
-    let client = Client::new();
+    println!("{} {}", "다운로드 정보가 생성되었습니다:".green(), download_info.direct_download_url);
+    report.version = download_info.version.clone();
     
-    for extension in enabled_extensions {
-        if extension.id.is_empty() {
-            continue;
-        }
-        
-        // Open VSX에서 확장 프로그램 확인
-        let url = format!("{}/{}", OPEN_VSX_API, extension.id.replace(".", "/"));
-        
-        match client.get(&url).send().await {
-            Ok(response) => {
-                if response.status().is_success() {
-                    let data: serde_json::Value = response.json().await
-                        .with_context(|| format!("Failed to parse response for extension: {}", extension.id))?;
-                    
-                    // Open VSX API 구조 확인 - files.download 또는 downloads.universal 필드에서 URL 가져오기
-                    let download_url = data.get("files")
-                        .and_then(|files| files.get("download"))
-                        .and_then(|v| v.as_str())
-                        .or_else(|| {
-                            data.get("downloads")
-                                .and_then(|downloads| downloads.get("universal"))
-                                .and_then(|v| v.as_str())
-                        });
-                    
-                    if let Some(url) = download_url {
-                        // Open VSX에서 사용 가능한 확장 프로그램
-                        println!("{} {}: {}", "확인".green(), extension.id, "Open VSX에서 사용 가능".green());
-                        results.available.push(AvailableExtension {
-                            id: extension.id.clone(),
-                            uuid: extension.uuid.clone(),
-                            url: url.to_string(),
-                        });
-                    } else {
-                        // Open VSX에 있지만 다운로드 URL이 없는 경우 - VSCode Marketplace에서 다운로드 필요
-                        println!("{} {}: {}", "확인".yellow(), extension.id, "VSCode Marketplace에서 다운로드 필요".yellow());
-                        results.unavailable.push(UnavailableExtension {
-                            id: extension.id.clone(),
-                            uuid: extension.uuid.clone(),
-                        });
-                    }
-                } else {
-                    // Open VSX에 없는 확장 프로그램 - VSCode Marketplace에서 다운로드 필요
-                    results.unavailable.push(UnavailableExtension {
-                        id: extension.id.clone(),
-                        uuid: extension.uuid.clone(),
-                    });
-                }
-            },
-            Err(_) => {
-                // 요청 오류 - VSCode Marketplace에서 다운로드 시도
-                results.unavailable.push(UnavailableExtension {
-                    id: extension.id.clone(),
-                    uuid: extension.uuid.clone(),
-                });
-            }
+    // 웹 전용 빌드만 있으면 데스크톱 VS Code에 설치할 수 없으므로 받지 않음
+    let target = platform::resolve_target(args.target.as_deref());
+    if download_info.target_platform.as_deref() == Some("web") && target.as_deref() != Some("web") {
+        let reason = "웹 전용 빌드(web)만 있어 받지 않습니다".to_string();
+        warnings::push(&download_info.id, WarningKind::WebOnly, reason.clone());
+        return skip_download(&download_info, VsixError::PolicySkipped { reason }, report, &downloads_path);
+    }
+    
+    // 증분 동기화: 이미 같은 버전의 파일이 있으면 다시 받지 않음
+    if args.incremental {
+        let existing = validation::run(args.verify_jobs, &download_info.id, {
+            let download_info = download_info.clone();
+            move || existing_download(&download_info)
+        }).await;
+        if let Some(existing) = existing {
+            println!("{} {} ({})", "이미 최신 버전:".green(), download_info.file_name, download_info.version.as_deref().unwrap_or("-"));
+            update_download_status(&download_info, Ok(&existing), &downloads_path)?;
+            report.size = Some(existing.size);
+            report.extension_pack = vsix::read_extension_pack(Path::new(&download_info.download_path)).unwrap_or_default();
+            return Ok(DownloadOutcome::Unchanged);
         }
     }
     
-    println!(
-        "{}", 
-        format!("\n확인 완료:\n- Open VSX에서 사용 가능: {}개\n- VSCode Marketplace에서 다운로드 필요: {}개", 
-            results.available.len(), 
-            results.unavailable.len()
-        ).blue()
-    );
+    // 확인되지 않은 게시자는 받지 않음
+    if args.verified_publishers_only {
+        if let Some(reason) = publisher::policy_violation(&download_info.id, download_info.publisher.as_ref(), &args.trusted_publisher) {
+            return skip_download(&download_info, VsixError::PolicySkipped { reason }, report, &downloads_path);
+        }
+    }
     
-    // 결과를 JSON 파일로 저장
-    let json = serde_json::to_string_pretty(&results)
-        .with_context(|| "Failed to serialize results to JSON")?;
+    // 조직 정책 훅이 거부하면 받지 않음
+    if let Some(reason) = download_policy(client, &download_info, args).await? {
+        return skip_download(&download_info, VsixError::Vetoed { reason }, report, &downloads_path);
+    }
     
-    fs::write(output_path, json)
-        .with_context(|| format!("Failed to write results to {}", output_path.display()))?;
+    // 실제 파일 다운로드 시도. 실패하면 이번 시도가 쓴 파일만 격리하도록 시작 전 상태를 기억
+    let file_before = quarantine::FileState::of(Path::new(&download_info.download_path));
+    journal::append(&downloads_path, &journal::Event::downloading(&download_info.id))?;
+    let started = attempt_start();
+    let mut result = download_and_verify(client, &download_info.direct_download_url, &download_info, args).await;
+    report.attempts.push(Attempt::record(AttemptStage::Download, &download_info.direct_download_url, started, result.as_ref().err()));
     
-    println!("{} {}", "결과가".green(), format!("{} 파일에 저장되었습니다.", output_path.display()).green());
+    // 기본 CDN 주소가 실패하면 fallbackAssetUri로 한 번 더 시도 (해시 불일치나 훅이 거부한 파일은 다른 주소에서 받아 덮지 않음)
+    let should_retry = matches!(
+        &result,
+        Err(e) if !matches!(error::error_code(e), ErrorCode::SizeLimitExceeded | ErrorCode::HashMismatch | ErrorCode::HookFailed | ErrorCode::Infected)
+    );
+    if let (true, Some(fallback_url)) = (should_retry, &download_info.fallback_download_url) {
+        println!("{} {}", "대체 URL로 다시 시도합니다:".yellow(), fallback_url);
+        let started = attempt_start();
+        result = download_and_verify(client, fallback_url, &download_info, args).await;
+        report.attempts.push(Attempt::record(AttemptStage::Download, fallback_url, started, result.as_ref().err()));
+    }
     
-    // 다운로드 필요한 확장 프로그램이 있는 경우
-    if !results.unavailable.is_empty() {
-        let download_count = results.unavailable.len();
-        
-        // 자동 다운로드 옵션이 있는 경우 바로 다운로드 시작
-        if auto_download {
-            println!("{}", format!("VSCode Marketplace에서 {} 개의 확장 프로그램을 다운로드합니다...", download_count).yellow());
-            download_marketplace_extensions(&results.unavailable, output_dir).await?
-        } else {
-            // 사용자에게 다운로드 여부 묻기
-            println!(
-                "{}\n{}", 
-                format!("VSCode Marketplace에서 {} 개의 확장 프로그램을 다운로드하시겠습니까? (y/n)", download_count).yellow(),
-                "다운로드할 확장 프로그램: ".yellow()
-            );
+    match result {
+        Ok(mut downloaded) => {
+            // 성공한 마지막 요청에 걸린 시간
+            downloaded.duration_ms = report.attempts.last().map(|attempt| attempt.duration_ms);
+            println!("{} {}", "다운로드 성공:".green(), download_info.file_name);
+            update_download_status(&download_info, Ok(&downloaded), &downloads_path)?;
+            report.size = Some(downloaded.size);
+            report.extension_pack = vsix::read_extension_pack(Path::new(&download_info.download_path)).unwrap_or_default();
+            if args.cross_check_openvsx {
+                cross_check_openvsx(client, &download_info, &downloaded, &downloads_path).await?;
+            }
+            if !download_info.asset_sources.is_empty() {
+                let downloaded_assets = assets::download_all(client, &download_info.id, &download_info.asset_sources, output_dir).await?;
+                record_assets(&download_info.id, downloaded_assets, &downloads_path)?;
+            }
+            let transfer = downloaded.duration_ms.map(|duration_ms| TransferStats::new(&download_info.id, downloaded.size, duration_ms));
+            Ok(DownloadOutcome::Downloaded(transfer))
+        },
+        Err(e) => {
+            let code = error::error_code(&e);
             
-            // 확장 프로그램 ID 출력
-            for (i, extension) in results.unavailable.iter().enumerate() {
-                if i > 0 && i % 5 == 0 {
-                    println!();
-                }
-                print!("{}{}", extension.id.yellow(), if i < results.unavailable.len() - 1 { ", " } else { "" });
+            // 받다가 실패한 파일은 지우지 않고 원인과 함께 격리
+            let quarantine_path = quarantine::quarantine_file(
+                output_dir,
+                Path::new(&download_info.download_path),
+                &file_before,
+                &download_info.id,
+                &run.run_id,
+                &download_info.direct_download_url,
+                &e
+            )?;
+            if let Some(path) = &quarantine_path {
+                println!("{} {}", "실패한 파일을 격리했습니다:".yellow(), path.display());
             }
-            println!();
             
-            // 사용자 입력 받기
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
+            update_download_status(&download_info, Err(DownloadFailure { code, quarantine_path }), &downloads_path)?;
             
-            if input.trim().to_lowercase() == "y" {
-                println!("{}", format!("VSCode Marketplace에서 {} 개의 확장 프로그램을 다운로드합니다...", download_count).green());
-                download_marketplace_extensions(&results.unavailable, output_dir).await?
-            } else {
-                println!("{}", "다운로드를 취소했습니다.".red());
-            }
-        }
-    } else {
-        println!("{}", "VSCode Marketplace에서 다운로드할 확장 프로그램이 없습니다.".green());
-    }
-    
-    Ok(())
-}
-
-async fn download_marketplace_extensions(extensions: &[UnavailableExtension], output_dir: &Path) -> Result<()> {
-    println!("{}", "VSCode Marketplace에서 확장 프로그램 다운로드 중...".blue());
-    
-    // 다운로드 디렉토리 생성
-    create_dir_all(output_dir)
-        .with_context(|| format!("Failed to create directory: {}", output_dir.display()))?;
-    
-    let mut success_count = 0;
-    let mut failure_count = 0;
-    
-    for extension in extensions {
-        println!("{} {}", extension.id.yellow(), "다운로드 중...".blue());
-        
-        // 파일 이름 생성 - ID를 우선적으로 사용
-        let file_name = format!("{}.vsix", extension.id.replace(".", "-"));
-        
-        // 다운로드 정보 생성
-        match create_download_info(&extension.id, None, Some(&file_name), output_dir).await {
-            Ok(download_info) => {
-                println!("{} {}", "다운로드 정보가 생성되었습니다:".green(), download_info.direct_download_url);
-                
-                // 실제 파일 다운로드 시도
-                match download_file(&download_info.direct_download_url, &download_info.download_path).await {
-                    Ok(_) => {
-                        println!("{} {}", "다운로드 성공:".green(), download_info.file_name);
-                        update_download_status(&download_info.id, true)?;
-                        success_count += 1;
-                    },
-                    Err(e) => {
-                        println!("{} {}: {}", "다운로드 실패".red(), download_info.file_name, e);
-                        update_download_status(&download_info.id, false)?;
-                        failure_count += 1;
-                    }
+            if code == ErrorCode::SizeLimitExceeded {
+                if args.max_size_policy == SizeLimitPolicy::Abort {
+                    return Err(e);
                 }
-            },
-            Err(err) => {
-                println!("{} {}: {}", extension.id.red(), "다운로드 정보 생성 실패".red(), err);
-                failure_count += 1;
+                println!("{} {}: {}", "다운로드 건너뜀".yellow(), download_info.file_name, e);
+                warnings::push(&download_info.id, WarningKind::Oversized, e.to_string());
+                Ok(DownloadOutcome::Skipped)
+            } else {
+                println!("{} {}: {}", "다운로드 실패".red(), download_info.file_name, e);
+                Ok(DownloadOutcome::Failed)
             }
         }
     }
-    
-    println!(
-        "{}", 
-        format!("모든 확장 프로그램 처리 완료: {}개 성공, {}개 실패", 
-            success_count, 
-            failure_count
-        ).green()
-    );
-    
-    Ok(())
 }
 
 async fn create_download_info(
+    client: &HttpClient,
     extension_id: &str, 
     version: Option<&str>, 
     custom_file_name: Option<&str>,
-    output_dir: &Path
+    args: &SyncArgs,
+    run: &RunMetadata
 ) -> Result<DownloadInfo> {
-    println!("{} {}", "VSCode Marketplace에서".blue(), format!("{} 확장 프로그램 다운로드 정보 생성 중...", extension_id).blue());
+    let output_dir = args.output_dir.as_path();
+    let downloads_path = &args.downloads_path();
     
     // 확장 프로그램 ID를 게시자와 이름으로 분리
     let parts: Vec<&str> = extension_id.split('.').collect();
     
     if parts.len() != 2 {
-        return Err(anyhow!("유효하지 않은 확장 프로그램 ID 형식: {}", extension_id));
+        return Err(VsixError::InvalidExtensionId(extension_id.to_string()).into());
     }
     
     let (publisher, name) = (parts[0], parts[1]);
     
     // 파일 이름 생성
     let file_name = match custom_file_name {
-        Some(name) => name.to_string(),
-        None => format!("{}.vsix", extension_id.replace(".", "-")),
+        Some(name) => filename::sanitize_file_name(name),
+        None => filename::vsix_file_name(extension_id),
     };
     
-    // 마켓플레이스 URL 생성
-    let marketplace_url = format!("{}/{}.{}", VSCODE_MARKETPLACE_URL, publisher, name);
-    
-    // 직접 다운로드 URL 생성
-    let version_str = version.unwrap_or("latest");
-    let direct_download_url = format!(
-        "https://{}.gallery.vsassets.io/_apis/public/gallery/publisher/{}/extension/{}/{}/assetbyname/Microsoft.VisualStudio.Services.VSIXPackage",
-        publisher, publisher, name, version_str
-    );
+    // 외부 리졸버가 제공하는 확장 프로그램은 Marketplace 대신 리졸버에서 받음
+    let mut asset_sources = Vec::new();
+    let mut manifest_urls = Vec::new();
+    let (marketplace_url, direct_download_url, fallback_download_url, resolved_version, target_platform, publisher_info) =
+        match resolver::resolve(&args.resolvers, extension_id, version).await {
+            Some(resolved) => (resolved.url.clone(), resolved.url, None, resolved.version, None, None),
+            None => {
+                println!("{} {}", "VSCode Marketplace에서".blue(), format!("{} 확장 프로그램 다운로드 정보 생성 중...", extension_id).blue());
+                
+                // 마켓플레이스 URL 생성
+                let marketplace_url = format!("{}/{}.{}", VSCODE_MARKETPLACE_URL, publisher, name);
+                
+                // extensionquery 응답의 assetUri/fallbackAssetUri로 다운로드 URL 결정
+                let gallery_extension = marketplace::query_extension(client, extension_id, version).await?;
+                record_id_case(extension_id, &gallery_extension.id());
+                let target = platform::resolve_target(args.target.as_deref());
+                let gallery_version = gallery_extension.find_version(version, target.as_deref())
+                    .ok_or_else(|| VsixError::NotFound { url: format!("{}@{}", marketplace_url, version.unwrap_or("latest")) })?;
+                
+                let mut download_urls = gallery_version.vsix_urls().into_iter();
+                let direct_download_url = download_urls.next()
+                    .ok_or_else(|| VsixError::NotFound { url: marketplace_url.clone() })?;
+                let fallback_download_url = download_urls.next();
+                asset_sources = assets::sources(&args.assets, gallery_version);
+                manifest_urls = gallery_version.asset_urls(assets::AssetKind::Manifest.asset_type());
+                
+                (
+                    marketplace_url,
+                    direct_download_url,
+                    fallback_download_url,
+                    gallery_version.version.clone(),
+                    gallery_version.target_platform.clone(),
+                    Some(PublisherInfo::from(&gallery_extension.publisher)),
+                )
+            },
+        };
     
     // 출력 경로 생성
     let output_path = output_dir.join(&file_name);
     
     // 다운로드 정보 생성
-    let download_info = DownloadInfo {
+    let mut download_info = DownloadInfo {
         id: extension_id.to_string(),
         marketplace_url,
         direct_download_url,
+        fallback_download_url,
         download_path: output_path.to_string_lossy().to_string(),
         file_name,
-        version: version.map(|v| v.to_string()),
+        version: Some(resolved_version),
+        target_platform,
         timestamp: Utc::now().to_rfc3339(),
         success: false,
+        size: None,
+        sha256: None,
+        error: None,
+        quarantine_path: None,
+        run: Some(run.clone()),
+        publisher: publisher_info,
+        publisher_change: None,
+        openvsx_sha256: None,
+        duration_ms: None,
+        bytes_per_second: None,
+        assets: Vec::new(),
+        scan: None,
+        origin: None,
+        asset_sources,
+        manifest_urls,
     };
     
-    // 다운로드 정보를 JSON 파일에 저장
-    let downloads_json = PathBuf::from("downloads.json");
-    let mut downloads = Vec::new();
-    
-    if downloads_json.exists() {
-        let content = fs::read_to_string(&downloads_json)
-            .with_context(|| format!("Failed to read {}", downloads_json.display()))?;
-        
-        downloads = serde_json::from_str(&content)
-            .unwrap_or_else(|_| Vec::new());
+    // 이전 실행에 기록된 게시자와 비교해 소유권 변경 감지
+    if let (Some(previous), Some(current)) = (publisher::previous(extension_id), &download_info.publisher) {
+        download_info.publisher_change = publisher::ownership_change(&previous, current);
+    }
+    if let Some(reason) = &download_info.publisher_change {
+        publisher::warn_ownership_change(extension_id, reason);
     }
     
-    // 중복 항목 제거
-    downloads.retain(|d: &DownloadInfo| d.id != extension_id);
-    downloads.push(download_info.clone());
-    
-    let json = serde_json::to_string_pretty(&downloads)
-        .with_context(|| "Failed to serialize downloads to JSON")?;
-    
-    fs::write(&downloads_json, json)
-        .with_context(|| format!("Failed to write downloads to {}", downloads_json.display()))?;
+    // 다운로드 정보를 저널에 기록 (같은 ID의 이전 항목을 바꿈)
+    journal::append(downloads_path, &journal::Event::Resolved { info: Box::new(download_info.clone()) })?;
     
-    println!("{}", "다운로드 정보가 downloads.json 파일에 저장되었습니다.".green());
+    println!("{}", format!("다운로드 정보가 {} 파일에 저장되었습니다.", downloads_path.display()).green());
     
     Ok(download_info)
 }
 
-async fn download_file(url: &str, output_path: &str) -> Result<()> {
-    let client = Client::new();
-    
+fn progress_bars() -> &'static MultiProgress {
+    static PROGRESS_BARS: OnceLock<MultiProgress> = OnceLock::new();
+    PROGRESS_BARS.get_or_init(|| if plain::enabled() {
+        MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+    } else {
+        MultiProgress::new()
+    })
+}
+
+/// 확인 단계 전체의 진행률 표시바. 다운로드 표시바와 같은 모양에 확인한 수와 남은 시간을 보여줍니다.
+fn check_progress_bar(total: usize) -> ProgressBar {
+    let pb = progress_bars().add(ProgressBar::new(total as u64));
+    pb.set_style(plain::progress_style("{spinner:.green} [{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} 확인 중 (남은 시간 {eta})"));
+    pb.enable_steady_tick(Duration::from_millis(120));
+    pb
+}
+
+async fn download_file(
+    client: &HttpClient,
+    url: &str,
+    output_path: &str,
+    max_size: Option<u64>,
+    segments: usize
+) -> Result<DownloadedFile> {
     // 진행률 표시를 위한 설정
-    let progress_style = ProgressStyle::default_bar()
-        .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
-        .unwrap()
-        .progress_chars("##-");
+    let progress_style = plain::progress_style("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}");
     
     println!("{} {}", "다운로드 시작:".blue(), url);
     
     // 요청 보내기
-    let res = client.get(url)
-        .send()
-        .await
-        .with_context(|| format!("Failed to send request to {}", url))?;
+    let res = client.get(url).await?;
     
     // 응답 상태 확인
-    if !res.status().is_success() {
-        return Err(anyhow!("서버 오류: {}", res.status()));
+    if !res.status.is_success() {
+        return Err(VsixError::from_status(res.status, url).into());
     }
     
+    let origin = DownloadOrigin::from_response(&res);
+    
     // 전체 파일 크기 가져오기
     let total_size = res.content_length().unwrap_or(0);
     
-    // 진행률 표시바 생성
-    let pb = ProgressBar::new(total_size);
+    // Content-Length가 최대 크기를 넘으면 받기 전에 중단
+    if let Some(limit) = max_size {
+        if total_size > limit {
+            return Err(VsixError::SizeLimitExceeded { url: url.to_string(), size: total_size, limit }.into());
+        }
+    }
+    
+    // 진행률 표시바 생성 (동시에 받는 파일의 표시바가 겹치지 않도록 한곳에서 관리)
+    let pb = progress_bars().add(ProgressBar::new(total_size));
     pb.set_style(progress_style);
     pb.set_message(format!("Downloading {}", output_path));
+    let file_name = Path::new(output_path).file_name().map_or_else(|| output_path.into(), |name| name.to_string_lossy());
+    let _in_flight = status_file::track_download(&file_name, &pb);
+    
+    let content_type = res.headers.get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    
+    let output_path = filename::long_path(Path::new(output_path));
+    
+    // 큰 파일은 여러 연결로 나눠 받기 (--segments)
+    if segments > 1
+        && total_size >= segmented::MIN_SEGMENTED_SIZE
+        && segmented::accepts_ranges(&res.headers)
+        && !vsix::is_error_content_type(&content_type)
+    {
+        if let Some(downloaded) = segmented::download(client, url, &output_path, total_size, segments, &pb).await? {
+            pb.finish_with_message(format!("다운로드 완료: {}", output_path.display()));
+            return Ok(DownloadedFile { origin: Some(origin), ..downloaded });
+        }
+        println!("{}", "서버가 범위 요청을 지원하지 않아 단일 연결로 받습니다.".yellow());
+    }
     
     // 스트림으로 다운로드
     let mut stream = res.bytes_stream();
-    let output_path = Path::new(output_path);
+    
+    // 갤러리는 일부 실패 상황에서 200과 함께 HTML 오류 페이지를 돌려주므로
+    // 파일을 만들기 전에 Content-Type과 첫 바이트(ZIP 시그니처)를 확인
+    let mut head = Vec::new();
+    while head.len() < vsix::ZIP_MAGIC.len() {
+        match stream.next().await {
+            Some(chunk) => head.extend_from_slice(&chunk.map_err(|e| VsixError::from_request(e, url))?),
+            None => break,
+        }
+    }
+    
+    if vsix::is_error_content_type(&content_type) || !head.starts_with(vsix::ZIP_MAGIC) {
+        // 서버 메시지를 보여줄 수 있도록 본문을 조금 더 읽음
+        if let Some(Ok(chunk)) = stream.next().await {
+            head.extend_from_slice(&chunk);
+        }
+        pb.abandon();
+        return Err(VsixError::InvalidArchive {
+            url: url.to_string(),
+            content_type,
+            message: vsix::server_message_excerpt(&head),
+        }.into());
+    }
+    
+    let mut stream = futures_util::stream::iter([Ok(bytes::Bytes::from(head))])
+        .chain(stream);
     
     // 출력 파일 생성
-    let mut file = File::create(output_path)
-        .with_context(|| format!("Failed to create file: {}", output_path.display()))?;
+    let mut file = File::create(&output_path)
+        .map_err(|source| VsixError::Io { path: output_path.to_path_buf(), source })?;
+    
+    // 스트림에서 데이터 처리 - 기록과 동시에 크기와 해시 계산
+    let mut hasher = Sha256::new();
+    let mut size = 0;
     
-    // 스트림에서 데이터 처리
     while let Some(chunk) = stream.next().await {
-        let chunk = chunk.with_context(|| "Failed to download chunk")?;
+        let chunk = chunk.map_err(|e| VsixError::from_request(e, url))?;
         file.write_all(&chunk)
-            .with_context(|| "Failed to write chunk to file")?;
+            .map_err(|source| VsixError::Io { path: output_path.to_path_buf(), source })?;
+        hasher.update(&chunk);
+        size += chunk.len() as u64;
         pb.inc(chunk.len() as u64);
+        status_file::tick();
+        
+        // Content-Length가 없거나 틀린 경우를 대비해 받은 크기도 확인
+        if let Some(limit) = max_size {
+            if size > limit {
+                pb.abandon();
+                return Err(VsixError::SizeLimitExceeded { url: url.to_string(), size, limit }.into());
+            }
+        }
     }
     
     pb.finish_with_message(format!("다운로드 완료: {}", output_path.display()));
     
-    Ok(())
+    Ok(DownloadedFile {
+        size,
+        sha256: format!("{:x}", hasher.finalize()),
+        duration_ms: None,
+        origin: Some(origin),
+    })
 }
 
-fn update_download_status(extension_id: &str, success: bool) -> Result<()> {
-    let downloads_json = PathBuf::from("downloads.json");
+fn update_download_status(
+    download_info: &DownloadInfo,
+    outcome: std::result::Result<&DownloadedFile, DownloadFailure>,
+    downloads_path: &Path
+) -> Result<()> {
+    let id = download_info.id.clone();
+    let timestamp = Utc::now().to_rfc3339();
+    let event = match outcome {
+        Ok(downloaded) => journal::Event::Done {
+            id,
+            timestamp,
+            size: downloaded.size,
+            sha256: downloaded.sha256.clone(),
+            // 리졸버에서 받은 파일도 어떤 플랫폼 빌드인지 알 수 있도록 매니페스트의 값으로 기록
+            target_platform: vsix::read_identity(Path::new(&download_info.download_path)).ok()
+                .and_then(|identity| identity.target_platform),
+            duration_ms: downloaded.duration_ms,
+            bytes_per_second: downloaded.duration_ms.map(|ms| bytes_per_second(downloaded.size, ms)),
+            origin: downloaded.origin.clone(),
+        },
+        Err(failure) => journal::Event::Failed {
+            id,
+            timestamp,
+            error: failure.code,
+            quarantine_path: failure.quarantine_path.map(|p| p.to_string_lossy().to_string()),
+        },
+    };
     
-    if downloads_json.exists() {
-        let content = fs::read_to_string(&downloads_json)
-            .with_context(|| format!("Failed to read {}", downloads_json.display()))?;
-        
-        let mut downloads: Vec<DownloadInfo> = serde_json::from_str(&content)
-            .with_context(|| "Failed to parse downloads.json")?;
-        
-        // 해당 ID의 확장 프로그램 찾기
-        if let Some(download) = downloads.iter_mut().find(|d| d.id == extension_id) {
-            // 성공 상태 업데이트
-            download.success = success;
-            download.timestamp = Utc::now().to_rfc3339();
-            
-            let json = serde_json::to_string_pretty(&downloads)
-                .with_context(|| "Failed to serialize downloads to JSON")?;
-            
-            fs::write(&downloads_json, json)
-                .with_context(|| format!("Failed to write downloads to {}", downloads_json.display()))?;
-            
-            println!("{}", "다운로드 상태가 업데이트되었습니다.".green());
-        }
-    }
+    journal::append(downloads_path, &event)?;
+    println!("{}", "다운로드 상태가 업데이트되었습니다.".green());
     
     Ok(())
 }
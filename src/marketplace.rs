@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::error::VsixError;
+use crate::http::HttpClient;
+
+pub const EXTENSION_QUERY_URL: &str = "https://marketplace.visualstudio.com/_apis/public/gallery/extensionquery";
+const EXTENSION_QUERY_ACCEPT: &str = "application/json;api-version=3.0-preview.1";
+
+pub const VSIX_ASSET_TYPE: &str = "Microsoft.VisualStudio.Services.VSIXPackage";
+
+/// extensionquery 필터 종류: 게시자.이름 형식의 확장 프로그램 이름
+const FILTER_EXTENSION_NAME: u32 = 7;
+/// extensionquery 필터 종류: 카테고리 (예: Programming Languages)
+const FILTER_CATEGORY: u32 = 5;
+/// extensionquery 필터 종류: 대상 제품 (VSCode 확장 프로그램만 검색)
+const FILTER_TARGET: u32 = 8;
+/// extensionquery 필터 종류: 검색어
+const FILTER_SEARCH_TEXT: u32 = 10;
+/// extensionquery 필터 종류: 게시자 이름
+const FILTER_PUBLISHER_NAME: u32 = 18;
+
+/// extensionquery 정렬 기준: 관련도 (기본값)
+const SORT_BY_RELEVANCE: u32 = 0;
+/// extensionquery 정렬 기준: 마지막 업데이트 시각
+const SORT_BY_LAST_UPDATED: u32 = 1;
+/// extensionquery 정렬 기준: 설치 수
+const SORT_BY_INSTALL_COUNT: u32 = 4;
+/// extensionquery 정렬 기준: 평균 평점
+const SORT_BY_AVERAGE_RATING: u32 = 6;
+
+/// 여러 페이지로 나눠 받을 때 한 페이지의 크기
+const PAGE_SIZE: u32 = 100;
+
+const VSCODE_TARGET: &str = "Microsoft.VisualStudio.Code";
+
+/// extensionquery 플래그
+const FLAG_INCLUDE_VERSIONS: u32 = 0x1;
+const FLAG_INCLUDE_FILES: u32 = 0x2;
+const FLAG_INCLUDE_CATEGORY_AND_TAGS: u32 = 0x4;
+const FLAG_INCLUDE_VERSION_PROPERTIES: u32 = 0x10;
+const FLAG_INCLUDE_ASSET_URI: u32 = 0x80;
+const FLAG_INCLUDE_STATISTICS: u32 = 0x100;
+const FLAG_INCLUDE_LATEST_VERSION_ONLY: u32 = 0x200;
+
+#[derive(Debug, Deserialize)]
+struct QueryResponse {
+    #[serde(default)]
+    results: Vec<QueryResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryResult {
+    #[serde(default)]
+    extensions: Vec<GalleryExtension>,
+    #[serde(default)]
+    result_metadata: Vec<ResultMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResultMetadata {
+    metadata_type: String,
+    #[serde(default)]
+    metadata_items: Vec<MetadataItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataItem {
+    name: String,
+    count: u64,
+}
+
+/// `search --sort` 정렬 기준
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SearchSort {
+    /// 검색어와의 관련도
+    #[default]
+    Relevance,
+    /// 설치 수가 많은 순
+    Installs,
+    /// 평균 평점이 높은 순
+    Rating,
+    /// 최근에 업데이트된 순
+    Updated,
+}
+
+impl SearchSort {
+    fn sort_by(self) -> u32 {
+        match self {
+            SearchSort::Relevance => SORT_BY_RELEVANCE,
+            SearchSort::Installs => SORT_BY_INSTALL_COUNT,
+            SearchSort::Rating => SORT_BY_AVERAGE_RATING,
+            SearchSort::Updated => SORT_BY_LAST_UPDATED,
+        }
+    }
+}
+
+/// 검색 결과 한 페이지와 검색어에 맞는 전체 확장 프로그램 수
+pub struct SearchPage {
+    pub extensions: Vec<GalleryExtension>,
+    pub total: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryExtension {
+    pub extension_name: String,
+    pub publisher: GalleryPublisher,
+    #[serde(default)]
+    pub versions: Vec<GalleryVersion>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub short_description: Option<String>,
+    /// 설치 수, 평점 등 (FLAG_INCLUDE_STATISTICS로 요청한 경우)
+    #[serde(default)]
+    pub statistics: Vec<GalleryStatistic>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryStatistic {
+    pub statistic_name: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryPublisher {
+    pub publisher_name: String,
+    /// 게시자 UUID
+    #[serde(default)]
+    pub publisher_id: Option<String>,
+    #[serde(default)]
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub is_domain_verified: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryVersion {
+    pub version: String,
+    pub asset_uri: Option<String>,
+    pub fallback_asset_uri: Option<String>,
+    /// 플랫폼별 빌드의 대상 플랫폼. 범용 빌드는 없음
+    #[serde(default)]
+    pub target_platform: Option<String>,
+    #[serde(default)]
+    pub files: Vec<GalleryFile>,
+    /// 이 버전을 게시한 시각
+    #[serde(default)]
+    pub last_updated: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryFile {
+    pub asset_type: String,
+    pub source: String,
+}
+
+impl GalleryVersion {
+    /// VSIX 패키지 다운로드 URL 후보 (assetUri 기반, fallbackAssetUri 기반 순)
+    pub fn vsix_urls(&self) -> Vec<String> {
+        self.asset_urls(VSIX_ASSET_TYPE)
+    }
+
+    /// 자산 종류의 다운로드 URL 후보 (`files`에 있는 주소, assetUri 기반, fallbackAssetUri 기반 순).
+    /// VSIX가 아닌 자산은 `files`에 없으면 버전에 없는 것으로 봅니다.
+    pub fn asset_urls(&self, asset_type: &str) -> Vec<String> {
+        let mut urls = Vec::new();
+
+        match self.files.iter().find(|f| f.asset_type == asset_type) {
+            Some(file) => urls.push(file.source.clone()),
+            None if asset_type != VSIX_ASSET_TYPE => return urls,
+            None => {},
+        }
+
+        for base in [&self.asset_uri, &self.fallback_asset_uri].into_iter().flatten() {
+            let url = format!("{}/{}", base.trim_end_matches('/'), asset_type);
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+
+        urls
+    }
+}
+
+/// VSCode Marketplace의 extensionquery API로 확장 프로그램 정보를 조회합니다.
+/// `version`이 없으면 최신 버전만 요청합니다.
+pub async fn query_extension(client: &HttpClient, extension_id: &str, version: Option<&str>) -> Result<GalleryExtension> {
+    let mut flags = FLAG_INCLUDE_VERSIONS
+        | FLAG_INCLUDE_FILES
+        | FLAG_INCLUDE_VERSION_PROPERTIES
+        | FLAG_INCLUDE_ASSET_URI;
+    if version.is_none() {
+        flags |= FLAG_INCLUDE_LATEST_VERSION_ONLY;
+    }
+
+    find_extension(client, extension_id, flags).await
+}
+
+/// 여러 확장 프로그램의 최신 버전을 한 번에 조회합니다 (요청 하나에 최대 100개).
+/// 반환 값의 키는 소문자 ID이며, Marketplace에 없는 확장 프로그램은 빠집니다.
+pub async fn latest_versions(client: &HttpClient, extension_ids: &[&str]) -> Result<HashMap<String, String>> {
+    let mut versions = HashMap::new();
+    for chunk in extension_ids.chunks(PAGE_SIZE as usize) {
+        let criteria: Vec<serde_json::Value> = chunk.iter()
+            .map(|id| json!({ "filterType": FILTER_EXTENSION_NAME, "value": id }))
+            .collect();
+        let extensions = run_query(
+            client,
+            serde_json::Value::Array(criteria),
+            1,
+            PAGE_SIZE,
+            SORT_BY_RELEVANCE,
+            FLAG_INCLUDE_VERSIONS | FLAG_INCLUDE_LATEST_VERSION_ONLY
+        ).await
+            .with_context(|| "Failed to query latest versions")?;
+
+        for extension in extensions {
+            if let Some(latest) = extension.versions.first() {
+                versions.insert(extension.id().to_lowercase(), latest.version.clone());
+            }
+        }
+    }
+
+    Ok(versions)
+}
+
+/// 확장 프로그램의 카테고리와 태그(키워드)를 조회합니다.
+pub async fn query_metadata(client: &HttpClient, extension_id: &str) -> Result<GalleryExtension> {
+    find_extension(client, extension_id, FLAG_INCLUDE_CATEGORY_AND_TAGS | FLAG_INCLUDE_LATEST_VERSION_ONLY).await
+}
+
+async fn find_extension(client: &HttpClient, extension_id: &str, flags: u32) -> Result<GalleryExtension> {
+    let criteria = json!([{ "filterType": FILTER_EXTENSION_NAME, "value": extension_id }]);
+    let extensions = run_query(client, criteria, 1, 1, SORT_BY_RELEVANCE, flags).await
+        .with_context(|| format!("Failed to query extension {}", extension_id))?;
+
+    extensions.into_iter()
+        .find(|e| e.id().eq_ignore_ascii_case(extension_id))
+        .ok_or_else(|| VsixError::NotFound { url: format!("{}#{}", EXTENSION_QUERY_URL, extension_id) }.into())
+}
+
+/// Marketplace 검색어로 VSCode 확장 프로그램을 찾습니다 (관련도 순, 최대 `page_size`개).
+pub async fn search_extensions(client: &HttpClient, text: &str, page_size: u32) -> Result<Vec<GalleryExtension>> {
+    let criteria = json!([
+        { "filterType": FILTER_TARGET, "value": VSCODE_TARGET },
+        { "filterType": FILTER_SEARCH_TEXT, "value": text },
+    ]);
+
+    run_query(client, criteria, 1, page_size, SORT_BY_RELEVANCE, 0).await
+        .with_context(|| format!("Failed to search extensions for {}", text))
+}
+
+/// 검색 결과의 한 페이지를 `sort` 순으로 가져옵니다 (`search` 명령). 설치 수와 평점, 최신 버전을 함께 요청합니다.
+pub async fn search_page(client: &HttpClient, text: &str, sort: SearchSort, page_number: u32, page_size: u32) -> Result<SearchPage> {
+    let criteria = json!([
+        { "filterType": FILTER_TARGET, "value": VSCODE_TARGET },
+        { "filterType": FILTER_SEARCH_TEXT, "value": text },
+    ]);
+    let flags = FLAG_INCLUDE_VERSIONS | FLAG_INCLUDE_STATISTICS | FLAG_INCLUDE_LATEST_VERSION_ONLY;
+
+    let results = query_results(client, criteria, page_number, page_size, sort.sort_by(), flags).await
+        .with_context(|| format!("Failed to search extensions for {}", text))?;
+
+    let total = results.iter()
+        .flat_map(|r| &r.result_metadata)
+        .filter(|m| m.metadata_type == "ResultCount")
+        .flat_map(|m| &m.metadata_items)
+        .find(|item| item.name == "TotalCount")
+        .map(|item| item.count);
+
+    Ok(SearchPage {
+        extensions: results.into_iter().flat_map(|r| r.extensions).collect(),
+        total,
+    })
+}
+
+/// 게시자가 Marketplace에 올린 VSCode 확장 프로그램을 모두 찾습니다 (페이지를 끝까지 넘기며 조회).
+pub async fn publisher_extensions(client: &HttpClient, publisher: &str) -> Result<Vec<GalleryExtension>> {
+    let criteria = json!([
+        { "filterType": FILTER_TARGET, "value": VSCODE_TARGET },
+        { "filterType": FILTER_PUBLISHER_NAME, "value": publisher },
+    ]);
+
+    let mut extensions = Vec::new();
+    for page_number in 1.. {
+        let page = run_query(client, criteria.clone(), page_number, PAGE_SIZE, SORT_BY_RELEVANCE, 0).await
+            .with_context(|| format!("Failed to list extensions of publisher {}", publisher))?;
+        let count = page.len();
+
+        extensions.extend(page.into_iter().filter(|e| e.publisher.publisher_name.eq_ignore_ascii_case(publisher)));
+        if count < PAGE_SIZE as usize {
+            break;
+        }
+    }
+
+    Ok(extensions)
+}
+
+/// 설치 수가 많은 순으로 VSCode 확장 프로그램을 최대 `count`개 찾습니다. `category`를 지정하면 그 카테고리 안에서 찾습니다.
+pub async fn top_extensions(client: &HttpClient, count: usize, category: Option<&str>) -> Result<Vec<GalleryExtension>> {
+    let mut criteria = vec![json!({ "filterType": FILTER_TARGET, "value": VSCODE_TARGET })];
+    if let Some(category) = category {
+        criteria.push(json!({ "filterType": FILTER_CATEGORY, "value": category }));
+    }
+    let criteria = serde_json::Value::Array(criteria);
+
+    let mut extensions = Vec::new();
+    let mut page_number = 1;
+    while extensions.len() < count {
+        let page = run_query(client, criteria.clone(), page_number, PAGE_SIZE, SORT_BY_INSTALL_COUNT, 0).await
+            .with_context(|| "Failed to list popular extensions")?;
+        let page_len = page.len();
+
+        extensions.extend(page);
+        if page_len < PAGE_SIZE as usize {
+            break;
+        }
+        page_number += 1;
+    }
+
+    extensions.truncate(count);
+    Ok(extensions)
+}
+
+async fn run_query(
+    client: &HttpClient,
+    criteria: serde_json::Value,
+    page_number: u32,
+    page_size: u32,
+    sort_by: u32,
+    flags: u32
+) -> Result<Vec<GalleryExtension>> {
+    let results = query_results(client, criteria, page_number, page_size, sort_by, flags).await?;
+    Ok(results.into_iter().flat_map(|r| r.extensions).collect())
+}
+
+async fn query_results(
+    client: &HttpClient,
+    criteria: serde_json::Value,
+    page_number: u32,
+    page_size: u32,
+    sort_by: u32,
+    flags: u32
+) -> Result<Vec<QueryResult>> {
+    let body = json!({
+        "filters": [{
+            "criteria": criteria,
+            "pageNumber": page_number,
+            "pageSize": page_size,
+            "sortBy": sort_by,
+            "sortOrder": 0,
+        }],
+        "assetTypes": [],
+        "flags": flags,
+    });
+
+    let response = client.post_json(EXTENSION_QUERY_URL, EXTENSION_QUERY_ACCEPT, &body).await?;
+    if !response.status.is_success() {
+        return Err(VsixError::from_status(response.status, EXTENSION_QUERY_URL).into());
+    }
+
+    let response: QueryResponse = response.json().await
+        .with_context(|| "Failed to parse extensionquery response")?;
+
+    Ok(response.results)
+}
+
+impl GalleryExtension {
+    /// `publisher.name` 형식의 ID
+    pub fn id(&self) -> String {
+        format!("{}.{}", self.publisher.publisher_name, self.extension_name)
+    }
+
+    /// 통계 값 (예: `install`, `averagerating`). 요청하지 않았거나 없으면 None
+    pub fn statistic(&self, name: &str) -> Option<f64> {
+        self.statistics.iter()
+            .find(|s| s.statistic_name == name)
+            .map(|s| s.value)
+    }
+
+    /// 요청한 버전(없으면 최신 버전)을 찾습니다.
+    /// 같은 버전에 플랫폼별 빌드가 여럿 있으면 `target`과 같은 빌드, 범용 빌드, 첫 번째 빌드 순으로 고릅니다.
+    pub fn find_version(&self, version: Option<&str>, target: Option<&str>) -> Option<&GalleryVersion> {
+        let version = match version {
+            Some(version) => version,
+            None => &self.versions.first()?.version,
+        };
+
+        let candidates: Vec<&GalleryVersion> = self.versions.iter()
+            .filter(|v| v.version == version)
+            .collect();
+
+        candidates.iter()
+            .find(|v| target.is_some() && v.target_platform.as_deref() == target)
+            .or_else(|| candidates.iter().find(|v| v.target_platform.is_none()))
+            .or_else(|| candidates.first())
+            .copied()
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+
+use crate::marketplace::{self, GalleryExtension};
+use crate::resume::SyncState;
+use crate::{run_sync, Extension, MirrorPublisherArgs, MirrorTopArgs, SyncArgs};
+
+/// 게시자의 확장 프로그램 목록을 Marketplace에서 찾아, Open VSX 확인 없이 모두 최신 버전으로 다운로드합니다.
+pub async fn publisher(args: &MirrorPublisherArgs, resumed: Option<SyncState>) -> Result<()> {
+    let client = args.sync.http_client()?;
+
+    println!("{}", format!("게시자 {}의 확장 프로그램을 찾는 중...", args.publisher).blue());
+    let extensions = marketplace::publisher_extensions(&client, &args.publisher).await?;
+
+    if extensions.is_empty() {
+        return Err(anyhow!("게시자 {}의 확장 프로그램을 찾을 수 없습니다", args.publisher));
+    }
+
+    println!("{}", format!("게시자 {}의 확장 프로그램 {}개를 찾았습니다.", args.publisher, extensions.len()).green());
+
+    mirror(&args.sync, &extensions, resumed).await
+}
+
+/// 설치 수가 많은 순으로 확장 프로그램을 찾아 최신 버전으로 다운로드합니다.
+/// `--category`를 지정하면 카테고리마다 `--count`개씩 찾아 합칩니다.
+pub async fn top(args: &MirrorTopArgs, resumed: Option<SyncState>) -> Result<()> {
+    let client = args.sync.http_client()?;
+
+    let categories: Vec<Option<&str>> = match args.sync.category.as_slice() {
+        [] => vec![None],
+        categories => categories.iter().map(|c| Some(c.as_str())).collect(),
+    };
+
+    let mut extensions: Vec<GalleryExtension> = Vec::new();
+    for category in categories {
+        let scope = category.map(|c| format!("{} 카테고리에서 ", c)).unwrap_or_default();
+        println!("{}", format!("{}설치 수가 많은 확장 프로그램 {}개를 찾는 중...", scope, args.count).blue());
+
+        for extension in marketplace::top_extensions(&client, args.count, category).await? {
+            if !extensions.iter().any(|e| e.id().eq_ignore_ascii_case(&extension.id())) {
+                extensions.push(extension);
+            }
+        }
+    }
+
+    if extensions.is_empty() {
+        return Err(anyhow!("설치 수 순으로 찾은 확장 프로그램이 없습니다"));
+    }
+
+    println!("{}", format!("확장 프로그램 {}개를 찾았습니다.", extensions.len()).green());
+
+    // 카테고리 조건은 검색에서 이미 적용했으므로 다시 확인하지 않음
+    let sync = SyncArgs {
+        category: Vec::new(),
+        ..args.sync.clone()
+    };
+
+    mirror(&sync, &extensions, resumed).await
+}
+
+/// 찾은 확장 프로그램을 목록 파일 대신 넘겨 Marketplace에서 바로 다운로드합니다.
+async fn mirror(args: &SyncArgs, extensions: &[GalleryExtension], resumed: Option<SyncState>) -> Result<()> {
+    let extensions: Vec<Extension> = extensions.iter()
+        .map(|e| Extension {
+            id: e.id(),
+            uuid: None,
+            version: None,
+            source: None,
+            source_file: None,
+        })
+        .collect();
+
+    let mirror_args = SyncArgs {
+        listed_extensions: Some(extensions),
+        marketplace_only: true,
+        auto_download: true,
+        ..args.clone()
+    };
+
+    run_sync(&mirror_args, resumed).await
+}
@@ -0,0 +1,22 @@
+use anyhow::Result;
+use colored::Colorize;
+use notify_rust::Notification;
+
+const NOTIFICATION_TITLE: &str = "VSIX Downloader";
+
+/// 동기화 결과를 OS 데스크톱 알림으로 표시합니다.
+/// 알림 표시에 실패해도 동기화 결과에는 영향을 주지 않습니다.
+pub fn notify_sync_finished(result: &Result<()>) {
+    let body = match result {
+        Ok(_) => "확장 프로그램 동기화가 완료되었습니다.".to_string(),
+        Err(e) => format!("확장 프로그램 동기화에 실패했습니다: {}", e),
+    };
+
+    if let Err(e) = Notification::new()
+        .summary(NOTIFICATION_TITLE)
+        .body(&body)
+        .show()
+    {
+        println!("{} {}", "데스크톱 알림 표시 실패:".yellow(), e);
+    }
+}
@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::ErrorCode;
+use crate::filename;
+use crate::{DownloadInfo, Results};
+
+/// 결과 파일과 다운로드 정보 파일의 저장 형식
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ResultsFormat {
+    #[default]
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl ResultsFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ResultsFormat::Json => "json",
+            ResultsFormat::Yaml => "yaml",
+            ResultsFormat::Csv => "csv",
+        }
+    }
+
+    /// 중첩 구조를 저장할 때 사용할 형식.
+    /// CSV는 표 형식이므로 다운로드 정보 같은 상태 파일은 JSON으로 저장합니다.
+    pub fn document_format(self) -> ResultsFormat {
+        match self {
+            ResultsFormat::Csv => ResultsFormat::Json,
+            format => format,
+        }
+    }
+
+    /// 형식에 맞는 다운로드 정보 파일 경로 (downloads.json / downloads.yaml).
+    /// `dir`이 없으면 현재 디렉토리에 둡니다.
+    pub fn downloads_path(self, dir: Option<&Path>) -> PathBuf {
+        let file_name = format!("downloads.{}", self.document_format().extension());
+        match dir {
+            Some(dir) => dir.join(file_name),
+            None => PathBuf::from(file_name),
+        }
+    }
+
+    pub fn serialize<T: Serialize>(self, value: &T) -> Result<String> {
+        match self.document_format() {
+            ResultsFormat::Json => serde_json::to_string_pretty(value)
+                .with_context(|| "Failed to serialize to JSON"),
+            ResultsFormat::Yaml => serde_yaml::to_string(value)
+                .with_context(|| "Failed to serialize to YAML"),
+            ResultsFormat::Csv => unreachable!("CSV is mapped to JSON by document_format"),
+        }
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(self, content: &str) -> Result<T> {
+        match self.document_format() {
+            ResultsFormat::Json => serde_json::from_str(content)
+                .with_context(|| "Failed to parse JSON"),
+            ResultsFormat::Yaml => serde_yaml::from_str(content)
+                .with_context(|| "Failed to parse YAML"),
+            ResultsFormat::Csv => unreachable!("CSV is mapped to JSON by document_format"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    run_id: Option<&'a str>,
+    id: &'a str,
+    status: &'a str,
+    version: Option<&'a str>,
+    source: &'a str,
+    size: Option<u64>,
+    sha256: Option<&'a str>,
+    error: Option<ErrorCode>,
+}
+
+/// 같은 디렉토리의 임시 파일에 쓴 뒤 이름을 바꿔, 읽는 쪽이 절반만 쓰인 파일을 보지 않게 합니다.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+    let staged = staged_path(path);
+    fs::write(filename::long_path(&staged), contents)
+        .with_context(|| format!("Failed to write {}", staged.display()))?;
+    fs::rename(filename::long_path(&staged), filename::long_path(path))
+        .with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+fn staged_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+/// 확장 프로그램마다 한 행씩 실행 ID, 상태, 버전, 출처, 크기, 해시를 CSV로 기록합니다.
+pub fn write_csv(path: &Path, results: &Results, downloads: &[DownloadInfo]) -> Result<()> {
+    let staged = staged_path(path);
+    let mut writer = csv::Writer::from_path(&staged)
+        .with_context(|| format!("Failed to create {}", staged.display()))?;
+
+    let run_id = results.run.as_ref().map(|r| r.run_id.as_str());
+
+    for extension in &results.available {
+        writer.serialize(CsvRow {
+            run_id,
+            id: &extension.id,
+            status: "available",
+            version: extension.version.as_deref(),
+            source: "openvsx",
+            size: None,
+            sha256: None,
+            error: None,
+        })?;
+    }
+
+    for extension in &results.unavailable {
+        // 다운로드 정보 파일에는 이전 실행의 기록도 남아 있으므로 이번 실행의 기록만 사용
+        let download = downloads.iter().find(|d| {
+            d.id.eq_ignore_ascii_case(&extension.id)
+                && run_id.is_none_or(|run_id| d.run.as_ref().is_some_and(|r| r.run_id == run_id))
+        });
+        let status = match download {
+            Some(d) if d.success => "downloaded",
+            Some(_) => "failed",
+            None => "unavailable",
+        };
+
+        writer.serialize(CsvRow {
+            run_id,
+            id: &extension.id,
+            status,
+            version: download.and_then(|d| d.version.as_deref()),
+            source: "marketplace",
+            size: download.and_then(|d| d.size),
+            sha256: download.and_then(|d| d.sha256.as_deref()),
+            error: match download {
+                Some(d) => d.error,
+                None => extension.error,
+            },
+        })?;
+    }
+
+    writer.flush()
+        .with_context(|| format!("Failed to write {}", staged.display()))?;
+    drop(writer);
+
+    fs::rename(&staged, path)
+        .with_context(|| format!("Failed to replace {}", path.display()))
+}
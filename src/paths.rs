@@ -0,0 +1,302 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::check_cache;
+use crate::history;
+use crate::input;
+use crate::SyncArgs;
+
+/// 이 도구가 만든 다운로드 디렉토리 표시. 이 파일이 없는 디렉토리는 `--force` 없이 지우지 않습니다.
+pub const MARKER_FILE: &str = ".vsix-downloader-output";
+
+/// 동기화를 시작하기 전에 다운로드 디렉토리, 결과 파일, 상태 파일, 캐시 위치를 확인합니다.
+/// 시작할 때 다운로드 디렉토리를 통째로 지우므로(`wipes_output`), 현재 디렉토리나 그 상위, 홈 디렉토리를
+/// 지우거나 목록 파일, 설정 파일, 상태 파일을 함께 지우게 되는 경우와 쓸 수 없는 위치를 모두 모아 한 번에 알려줍니다.
+pub fn validate(args: &SyncArgs, wipes_output: bool) -> Result<()> {
+    let cwd = env::current_dir().map_err(|e| anyhow!("현재 디렉토리를 확인할 수 없습니다: {}", e))?;
+    let cwd = cwd.canonicalize().unwrap_or(cwd);
+    let mut problems = problems(args, wipes_output, &cwd, home_dir().as_deref());
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    problems.dedup();
+    Err(anyhow!(
+        "시작 전 경로 확인에 실패해 아무것도 지우지 않고 중단합니다:\n  - {}\n전용 다운로드 디렉토리를 -o/--output-dir로 지정하고, 목록 파일과 상태 파일은 그 밖에 두세요",
+        problems.join("\n  - ")
+    ))
+}
+
+/// `cwd`에서 실행할 때 문제가 되는 경로를 모두 모읍니다.
+fn problems(args: &SyncArgs, wipes_output: bool, cwd: &Path, home: Option<&Path>) -> Vec<String> {
+    let output_dir = absolute(cwd, &args.output_dir);
+    let mut problems = Vec::new();
+
+    if wipes_output && args.output_dir.exists() {
+        if output_dir.parent().is_none() {
+            problems.push(format!("다운로드 디렉토리 {}가 파일 시스템의 루트입니다", args.output_dir.display()));
+        } else if cwd.starts_with(&output_dir) {
+            problems.push(format!(
+                "다운로드 디렉토리 {}가 현재 디렉토리이거나 그 상위 디렉토리입니다. 시작할 때 다운로드 디렉토리를 지우므로 현재 작업 내용까지 지워집니다",
+                args.output_dir.display()
+            ));
+        } else if home.is_some_and(|home| home.starts_with(&output_dir)) {
+            problems.push(format!("다운로드 디렉토리 {}가 홈 디렉토리이거나 그 상위 디렉토리입니다", args.output_dir.display()));
+        }
+
+        // 경로를 잘못 입력해 다른 디렉토리를 지우지 않도록 이전 실행이 남긴 표시가 있어야 지움
+        if !args.force && !is_managed(&args.output_dir) {
+            problems.push(format!(
+                "다운로드 디렉토리 {}는 이 도구가 만든 디렉토리가 아닙니다 ({} 없음). 경로를 잘못 지정한 것이 아니라면 --force로 지우고 다시 받으세요",
+                args.output_dir.display(),
+                MARKER_FILE
+            ));
+        }
+
+        for (label, path) in kept_files(args) {
+            if path.exists() && absolute(cwd, &path).starts_with(&output_dir) {
+                problems.push(format!(
+                    "{} {}이(가) 다운로드 디렉토리 안에 있어 시작할 때 지워집니다",
+                    label,
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    let state_dir = args.state_dir.clone().unwrap_or_else(|| PathBuf::from("."));
+    let results_dir = args.results_path().parent().map(Path::to_path_buf).unwrap_or_default();
+    let mut locations = vec![
+        ("다운로드 디렉토리", args.output_dir.clone()),
+        ("상태 디렉토리", state_dir),
+        ("결과 파일 위치", results_dir),
+    ];
+    if args.check_ttl.is_some() {
+        locations.push(("확인 캐시 위치", PathBuf::from(".")));
+    }
+    for (label, dir) in locations {
+        if let Err(reason) = check_writable(&dir) {
+            problems.push(format!("{} {}에 쓸 수 없습니다: {}", label, display_dir(&dir), reason));
+        }
+    }
+
+    problems
+}
+
+/// 다운로드 디렉토리에 이 도구가 관리한다는 표시를 남깁니다.
+pub fn write_marker(dir: &Path) -> Result<()> {
+    let path = dir.join(MARKER_FILE);
+    if path.exists() {
+        return Ok(());
+    }
+    fs::write(&path, "vsix-downloader가 관리하는 다운로드 디렉토리입니다. 동기화를 시작할 때 이 디렉토리를 지우고 새로 받습니다.\n")
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// 표시 파일이 있거나 비어 있어 지워도 잃을 것이 없는 디렉토리인지
+fn is_managed(dir: &Path) -> bool {
+    dir.join(MARKER_FILE).is_file()
+        || fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none())
+}
+
+/// 다운로드 디렉토리를 지워도 남아 있어야 하는 파일
+fn kept_files(args: &SyncArgs) -> Vec<(&'static str, PathBuf)> {
+    let mut files: Vec<(&'static str, PathBuf)> = args.file.iter()
+        .chain(&args.exclude_file)
+        .filter(|file| !input::is_remote(&file.to_string_lossy()))
+        .map(|file| ("목록 파일", file.clone()))
+        .collect();
+    files.push(("설정 파일", args.config.clone()));
+    if let Some(lockfile) = &args.lockfile {
+        files.push(("잠금 파일", lockfile.clone()));
+    }
+    files.push(("작업 상태 파일", args.work_state_path()));
+    files.push(("실행 기록 파일", PathBuf::from(history::HISTORY_FILE)));
+    files.push(("확인 캐시 파일", PathBuf::from(check_cache::CACHE_FILE)));
+    // --run-dirs는 상태 디렉토리가 다운로드 디렉토리 자체이지만 새로 만드는 디렉토리라 지워지지 않음
+    if let Some(state_dir) = args.state_dir.as_ref().filter(|dir| *dir != &args.output_dir) {
+        files.push(("상태 디렉토리", state_dir.clone()));
+    }
+    files
+}
+
+/// 디렉토리(아직 없으면 가장 가까운 상위 디렉토리)에 임시 파일을 만들어 쓸 수 있는지 확인합니다.
+fn check_writable(dir: &Path) -> std::result::Result<(), String> {
+    let existing = dir.ancestors()
+        .map(|ancestor| if ancestor.as_os_str().is_empty() { Path::new(".") } else { ancestor })
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(Path::new("."));
+    if !existing.is_dir() {
+        return Err(format!("{}이(가) 디렉토리가 아닙니다", existing.display()));
+    }
+
+    let probe = existing.join(format!(".vsix-downloader-write-test-{}", std::process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            Ok(())
+        },
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// 아직 없는 경로도 비교할 수 있도록 가장 가까운 기존 상위 디렉토리까지만 실제 경로로 바꿉니다.
+pub fn absolute(cwd: &Path, path: &Path) -> PathBuf {
+    let path = cwd.join(path);
+    let mut missing = Vec::new();
+    let mut existing = path.as_path();
+    loop {
+        if let Ok(resolved) = existing.canonicalize() {
+            return missing.iter().rev().fold(resolved, |resolved, part| resolved.join(part));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            },
+            _ => return path,
+        }
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .and_then(|home| home.canonicalize().ok())
+}
+
+fn display_dir(dir: &Path) -> String {
+    if dir.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        dir.display().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::{Cli, Commands};
+
+    /// 테스트마다 다른 임시 디렉토리 (실제 경로)
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("vsix-downloader-paths-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    /// `output_dir`을 지우는 동기화 설정. 상태 파일과 결과 파일은 `state_dir`에 둠
+    fn sync_args(output_dir: &Path, state_dir: &Path, extra: &[&str]) -> SyncArgs {
+        let mut argv = vec!["vsix-downloader".to_string(), "sync".to_string(), "-o".to_string(), output_dir.display().to_string()];
+        argv.extend(["--config".to_string(), state_dir.join("vsix-downloader.yml").display().to_string()]);
+        argv.extend(["-r".to_string(), state_dir.join("results.json").display().to_string()]);
+        argv.extend(["-f".to_string(), state_dir.join("extensions.yml").display().to_string()]);
+        argv.extend(extra.iter().map(|arg| arg.to_string()));
+        let Commands::Sync(mut args) = Cli::try_parse_from(argv).unwrap().command else {
+            unreachable!();
+        };
+        args.state_dir = Some(state_dir.to_path_buf());
+        args
+    }
+
+    fn has(problems: &[String], text: &str) -> bool {
+        problems.iter().any(|problem| problem.contains(text))
+    }
+
+    #[test]
+    fn rejects_cwd_or_its_ancestor() {
+        let dir = temp_dir("cwd");
+        let state = temp_dir("cwd-state");
+        let cwd = dir.join("work");
+        fs::create_dir_all(&cwd).unwrap();
+        fs::write(dir.join(MARKER_FILE), "").unwrap();
+
+        let args = sync_args(&dir, &state, &[]);
+        assert!(has(&problems(&args, true, &cwd, None), "현재 디렉토리이거나 그 상위"));
+        assert!(has(&problems(&args, true, &dir, None), "현재 디렉토리이거나 그 상위"));
+        // 지우지 않는 실행에서는 확인하지 않음
+        assert!(problems(&args, false, &cwd, None).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&state).unwrap();
+    }
+
+    #[test]
+    fn rejects_home_or_its_ancestor() {
+        let dir = temp_dir("home");
+        let state = temp_dir("home-state");
+        let home = dir.join("home").join("user");
+        fs::create_dir_all(&home).unwrap();
+        fs::write(dir.join(MARKER_FILE), "").unwrap();
+
+        let args = sync_args(&dir, &state, &[]);
+        assert!(has(&problems(&args, true, &state, Some(&home)), "홈 디렉토리"));
+        assert!(!has(&problems(&args, true, &state, Some(&state)), "홈 디렉토리"));
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&state).unwrap();
+    }
+
+    #[test]
+    fn rejects_filesystem_root() {
+        let state = temp_dir("root-state");
+        let root = state.ancestors().last().unwrap();
+
+        let args = sync_args(root, &state, &["--force"]);
+        assert!(has(&problems(&args, true, &state, None), "파일 시스템의 루트"));
+        fs::remove_dir_all(&state).unwrap();
+    }
+
+    #[test]
+    fn requires_marker_unless_forced() {
+        let dir = temp_dir("marker");
+        let state = temp_dir("marker-state");
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let args = sync_args(&dir, &state, &[]);
+        assert!(has(&problems(&args, true, &state, None), MARKER_FILE));
+        let forced = sync_args(&dir, &state, &["--force"]);
+        assert!(problems(&forced, true, &state, None).is_empty());
+
+        write_marker(&dir).unwrap();
+        assert!(is_managed(&dir));
+        assert!(problems(&args, true, &state, None).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&state).unwrap();
+    }
+
+    #[test]
+    fn empty_directory_is_managed() {
+        let dir = temp_dir("empty");
+        assert!(is_managed(&dir));
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        assert!(!is_managed(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_kept_files_inside_output_dir() {
+        let dir = temp_dir("kept");
+        let state = temp_dir("kept-state");
+        fs::write(dir.join(MARKER_FILE), "").unwrap();
+        for name in ["extensions.yml", "vsix-downloader.yml", "vsix.lock"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let mut args = sync_args(&dir, &state, &[]);
+        args.file = vec![dir.join("extensions.yml")];
+        args.config = dir.join("vsix-downloader.yml");
+        args.lockfile = Some(dir.join("vsix.lock"));
+        let problems = problems(&args, true, &state, None);
+        for label in ["목록 파일", "설정 파일", "잠금 파일"] {
+            assert!(has(&problems, label), "{}: {:?}", label, problems);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&state).unwrap();
+    }
+}
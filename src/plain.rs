@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use indicatif::ProgressStyle;
+
+use crate::size;
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// 진행률 표시바와 색을 끄고 한 줄씩 상태를 출력하는 모드를 켭니다 (`--plain`).
+/// 화면 낭독기나 단순 터미널에서 표시바를 다시 그리는 제어 문자가 읽히지 않게 합니다.
+pub fn enable() {
+    PLAIN.store(true, Ordering::Relaxed);
+    colored::control::set_override(false);
+}
+
+pub fn enabled() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// 진행률 표시바와 스피너에 ASCII 문자만 쓰는 모드를 켭니다 (`--ascii`).
+/// 일부 Windows 터미널과 시리얼 콘솔은 유니코드 스피너 문자를 깨진 글자로 표시합니다.
+pub fn enable_ascii() {
+    ASCII.store(true, Ordering::Relaxed);
+}
+
+/// 모든 진행률 표시바가 같은 문자를 쓰도록 표시바 모양을 한곳에서 정합니다.
+/// `--ascii`면 스피너를 `|/-\`로 바꾸고 나머지 배치는 그대로 둡니다.
+pub fn progress_style(template: &str) -> ProgressStyle {
+    let style = ProgressStyle::default_bar()
+        .template(template)
+        .expect("progress bar template is valid")
+        .progress_chars("##-");
+    if ASCII.load(Ordering::Relaxed) {
+        style.tick_chars("|/-\\ ")
+    } else {
+        style
+    }
+}
+
+/// 다운로드 한 건이 끝날 때마다 `3/42 다운로드 완료 rust-lang.rust-analyzer 0.4.1 (12.0 MiB)`처럼 출력할 번호
+#[derive(Default)]
+pub struct Progress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+}
+
+impl Progress {
+    pub fn new(total: usize) -> Self {
+        Progress { done: AtomicUsize::new(0), total: AtomicUsize::new(total) }
+    }
+
+    /// 확인과 다운로드를 동시에 진행하면 확인이 끝날 때마다 다운로드할 항목 수가 늘어납니다.
+    pub fn add_total(&self, count: usize) {
+        self.total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// `--plain`이면 다운로드 한 건의 결과를 한 줄로 출력합니다.
+    pub fn report(&self, status: &str, id: &str, version: Option<&str>, size: Option<u64>) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if !enabled() {
+            return;
+        }
+
+        let mut line = format!("{}/{} {} {}", done, self.total.load(Ordering::Relaxed), status, id);
+        if let Some(version) = version {
+            line.push_str(&format!(" {}", version));
+        }
+        if let Some(size) = size {
+            line.push_str(&format!(" ({})", size::format_size(size)));
+        }
+        println!("{}", line);
+    }
+}
@@ -0,0 +1,31 @@
+use std::env;
+
+/// `--target host` 또는 생략 시 현재 실행 중인 OS/아키텍처를 뜻하는 값
+pub const HOST: &str = "host";
+
+/// 현재 실행 중인 OS/아키텍처에 해당하는 Marketplace 대상 플랫폼 이름 (예: `darwin-arm64`).
+/// Marketplace가 플랫폼별 빌드를 제공하지 않는 조합이면 None을 반환합니다.
+pub fn host_platform() -> Option<&'static str> {
+    let musl = cfg!(target_env = "musl");
+
+    match (env::consts::OS, env::consts::ARCH) {
+        ("windows", "x86_64") => Some("win32-x64"),
+        ("windows", "aarch64") => Some("win32-arm64"),
+        ("linux", "x86_64") if musl => Some("alpine-x64"),
+        ("linux", "aarch64") if musl => Some("alpine-arm64"),
+        ("linux", "x86_64") => Some("linux-x64"),
+        ("linux", "aarch64") => Some("linux-arm64"),
+        ("linux", "arm") => Some("linux-armhf"),
+        ("macos", "x86_64") => Some("darwin-x64"),
+        ("macos", "aarch64") => Some("darwin-arm64"),
+        _ => None,
+    }
+}
+
+/// `--target` 값을 실제 대상 플랫폼으로 바꿉니다. 생략하거나 `host`면 현재 플랫폼을 사용합니다.
+pub fn resolve_target(target: Option<&str>) -> Option<String> {
+    match target {
+        None | Some(HOST) => host_platform().map(str::to_string),
+        Some(target) => Some(target.to_string()),
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use colored::Colorize;
+
+use crate::http::HttpClient;
+use crate::marketplace;
+use crate::{SyncArgs, OPEN_VSX_API};
+
+/// 동기화를 시작할 때 레지스트리에 연결할 수 없으면 어떻게 할지
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PreflightPolicy {
+    /// 바로 중단
+    Fail,
+    /// 연결되는 레지스트리만 사용 (Open VSX가 안 되면 Marketplace에서만 받고, Marketplace가 안 되면 Open VSX 확인만 함)
+    Fallback,
+    /// 미리 확인하지 않음
+    Off,
+}
+
+/// 두 레지스트리에 한 번씩 요청을 보내 연결할 수 있는지 확인하고, 정책에 따라 중단하거나 동기화 설정을 바꿉니다.
+/// 제한된 네트워크에서 수백 개 확장 프로그램이 모두 "사용 불가"로 분류된 뒤에야 문제를 알게 되는 일을 막습니다.
+pub async fn run(client: &HttpClient, args: &SyncArgs) -> Result<SyncArgs> {
+    // 재생 모드에는 확인 요청의 기록이 없으므로 건너뜀
+    if args.preflight == PreflightPolicy::Off || args.replay.is_some() {
+        return Ok(args.clone());
+    }
+
+    println!("{}", "레지스트리 연결을 확인하는 중...".blue());
+
+    let open_vsx = if args.marketplace_only {
+        None
+    } else {
+        probe(client, &format!("{}/-/search?size=1", OPEN_VSX_API)).await
+    };
+    let marketplace = probe(client, marketplace::EXTENSION_QUERY_URL).await;
+
+    match (open_vsx, marketplace) {
+        (None, None) => Ok(args.clone()),
+        (Some(open_vsx), Some(marketplace)) => Err(anyhow!(
+            "Open VSX와 Marketplace 모두에 연결할 수 없습니다.\n  Open VSX: {}\n  Marketplace: {}\n`vsix-downloader doctor`로 프록시와 인증서 설정을 확인하세요",
+            open_vsx, marketplace
+        )),
+        (Some(reason), None) => {
+            if args.preflight == PreflightPolicy::Fail {
+                return Err(anyhow!(
+                    "Open VSX에 연결할 수 없습니다: {}\n모든 확장 프로그램이 사용 불가로 분류되지 않도록 중단합니다. Marketplace에서만 받으려면 --preflight fallback을 지정하세요",
+                    reason
+                ));
+            }
+            println!("{} {}", "Open VSX에 연결할 수 없어 모든 확장 프로그램을 Marketplace에서 받습니다:".yellow(), reason);
+            Ok(SyncArgs {
+                marketplace_only: true,
+                ..args.clone()
+            })
+        },
+        (None, Some(reason)) => {
+            // 미러링은 Marketplace에서만 받으므로 대신할 방법이 없음
+            if args.preflight == PreflightPolicy::Fail || args.marketplace_only {
+                return Err(anyhow!(
+                    "Marketplace에 연결할 수 없습니다: {}\n다운로드 단계에서 모두 실패하지 않도록 중단합니다. Open VSX 확인만 하려면 --preflight fallback을 지정하세요",
+                    reason
+                ));
+            }
+            println!("{} {}", "Marketplace에 연결할 수 없어 Open VSX 확인만 하고 다운로드는 건너뜁니다:".yellow(), reason);
+            Ok(SyncArgs {
+                marketplace_unreachable: true,
+                ..args.clone()
+            })
+        },
+    }
+}
+
+/// 연결할 수 없으면 그 이유. HTTP 응답이 오면 상태 코드와 관계없이 연결된 것으로 봅니다.
+pub async fn probe(client: &HttpClient, url: &str) -> Option<String> {
+    match client.get(url).await {
+        Ok(_) => None,
+        Err(e) => {
+            let e = anyhow::Error::from(e);
+            Some(format!("{} ({})", e, e.root_cause()))
+        },
+    }
+}
@@ -0,0 +1,187 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use crate::encoding;
+use crate::filename;
+use crate::SyncArgs;
+
+/// 기본 설정 파일 경로
+pub const CONFIG_FILE: &str = "vsix-downloader.yml";
+
+/// 프로필마다 결과 파일과 다운로드 정보 파일을 둘 디렉토리의 상위 디렉토리
+const PROFILE_STATE_DIR: &str = "profiles";
+
+/// 설정 파일에 정의된 동기화 프로필 (팀이나 장비별 미러)
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Profile {
+    /// 목록 파일 경로 또는 http(s) URL
+    #[serde(default)]
+    file: Vec<PathBuf>,
+    /// 다운로드 디렉토리 (기본값: `<--output-dir>/<프로필 이름>`)
+    output_dir: Option<PathBuf>,
+    /// 결과 파일 경로 (기본값: `profiles/<프로필 이름>/results.<형식 확장자>`)
+    results: Option<PathBuf>,
+    /// 확장 프로그램을 어디서 확인할지
+    source: Option<ProfileSource>,
+    /// 받을 대상 플랫폼
+    target: Option<String>,
+    #[serde(default)]
+    category: Vec<String>,
+    #[serde(default)]
+    exclude_category: Vec<String>,
+    #[serde(default)]
+    keyword: Vec<String>,
+    #[serde(default)]
+    exclude_keyword: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ProfileSource {
+    /// Open VSX에서 확인하고 Marketplace에서 받음 (기본 동작)
+    Auto,
+    /// Open VSX 확인 없이 Marketplace에서만 받음
+    Marketplace,
+}
+
+/// `--profile`이나 `--all-profiles`가 지정되면 설정 파일의 프로필마다 동기화 설정을 만듭니다.
+/// 프로필을 지정하지 않았거나 이미 프로필을 적용한 설정이면 그대로 반환합니다.
+pub fn expand(args: &SyncArgs) -> Result<Vec<SyncArgs>> {
+    if args.profile.is_none() && !args.all_profiles {
+        return Ok(vec![args.clone()]);
+    }
+
+    let profiles = load(&args.config)?;
+    let selected: Vec<(String, Profile)> = match &args.profile {
+        Some(name) => {
+            let names: Vec<String> = profiles.iter().map(|(name, _)| name.clone()).collect();
+            let profile = profiles.into_iter().find(|(existing, _)| existing == name);
+            match profile {
+                Some(profile) => vec![profile],
+                None => return Err(anyhow!(
+                    "{}에 {} 프로필이 없습니다 (정의된 프로필: {})",
+                    args.config.display(),
+                    name,
+                    if names.is_empty() { "없음".to_string() } else { names.join(", ") }
+                )),
+            }
+        },
+        None => profiles,
+    };
+
+    if selected.is_empty() {
+        return Err(anyhow!("{}에 정의된 프로필이 없습니다", args.config.display()));
+    }
+
+    let base = args.config.parent().unwrap_or(Path::new(""));
+    selected.into_iter()
+        .map(|(name, profile)| apply(args, &name, profile, base))
+        .collect()
+}
+
+/// 설정 파일의 `profiles` 아래 프로필을 정의된 순서대로 읽습니다.
+fn load(path: &Path) -> Result<Vec<(String, Profile)>> {
+    let content = encoding::read_file(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let config: serde_yaml::Value = serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+
+    let Some(profiles) = config.get("profiles") else {
+        return Ok(Vec::new());
+    };
+    let profiles = profiles.as_mapping()
+        .ok_or_else(|| anyhow!("{}의 profiles는 프로필 이름을 키로 하는 맵이어야 합니다", path.display()))?;
+
+    profiles.iter()
+        .map(|(name, profile)| {
+            let name = name.as_str()
+                .ok_or_else(|| anyhow!("{}의 프로필 이름은 문자열이어야 합니다", path.display()))?
+                .to_string();
+            let profile: Profile = match profile {
+                serde_yaml::Value::Null => Profile::default(),
+                value => serde_yaml::from_value(value.clone())
+                    .with_context(|| format!("Failed to parse profile {} in {}", name, path.display()))?,
+            };
+            Ok((name, profile))
+        })
+        .collect()
+}
+
+/// 프로필의 결과 파일, 다운로드 정보 파일, 작업 상태 파일을 두는 디렉토리 (`profiles/<이름>`)
+pub fn state_dir(name: &str) -> PathBuf {
+    Path::new(PROFILE_STATE_DIR).join(name)
+}
+
+/// 프로필 값을 명령줄 설정 위에 적용합니다. 필터 조건은 명령줄 조건에 더합니다.
+/// 설정 파일의 상대 경로는 설정 파일이 있는 디렉토리 기준입니다.
+fn apply(args: &SyncArgs, name: &str, profile: Profile, base: &Path) -> Result<SyncArgs> {
+    let state_dir = state_dir(name);
+    fs::create_dir_all(filename::long_path(&state_dir))
+        .with_context(|| format!("Failed to create directory: {}", state_dir.display()))?;
+
+    let file = if profile.file.is_empty() {
+        args.file.clone()
+    } else {
+        profile.file.iter().map(|f| resolve(base, f)).collect()
+    };
+    if file.is_empty() && args.ids.is_empty() && args.listed_extensions.is_none() {
+        return Err(anyhow!("{} 프로필에 목록 파일(file)이 없습니다", name));
+    }
+
+    let output = profile.results.map(|r| resolve(base, &r))
+        .or_else(|| args.output.clone())
+        .unwrap_or_else(|| state_dir.join(format!("results.{}", args.results_format.extension())));
+
+    let concat = |cli: &[String], profile: Vec<String>| cli.iter().cloned().chain(profile).collect::<Vec<String>>();
+
+    Ok(SyncArgs {
+        file,
+        output: Some(output),
+        output_dir: profile.output_dir.map(|d| resolve(base, &d)).unwrap_or_else(|| args.output_dir.join(name)),
+        state_dir: Some(state_dir),
+        marketplace_only: args.marketplace_only || matches!(profile.source, Some(ProfileSource::Marketplace)),
+        target: profile.target.or_else(|| args.target.clone()),
+        category: concat(&args.category, profile.category),
+        exclude_category: concat(&args.exclude_category, profile.exclude_category),
+        keyword: concat(&args.keyword, profile.keyword),
+        exclude_keyword: concat(&args.exclude_keyword, profile.exclude_keyword),
+        profile: None,
+        all_profiles: false,
+        active_profile: Some(name.to_string()),
+        ..args.clone()
+    })
+}
+
+fn resolve(base: &Path, path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if path.is_absolute() || text.starts_with("http://") || text.starts_with("https://") {
+        path.to_path_buf()
+    } else {
+        base.join(path)
+    }
+}
+
+/// `resume`이 중단된 프로필 하나만 다시 실행하도록 명령줄에서 프로필 선택을 이 프로필로 바꿉니다.
+pub fn pin_argv(argv: Vec<String>, name: &str) -> Vec<String> {
+    let mut pinned = Vec::with_capacity(argv.len() + 2);
+    let mut args = argv.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--all-profiles" || arg.starts_with("--profile=") {
+            continue;
+        }
+        if arg == "--profile" {
+            args.next();
+            continue;
+        }
+        pinned.push(arg);
+    }
+
+    pinned.push("--profile".to_string());
+    pinned.push(name.to_string());
+    pinned
+}
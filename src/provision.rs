@@ -0,0 +1,193 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use clap::ValueEnum;
+use colored::Colorize;
+use serde_json::{json, Value};
+
+use crate::installed;
+use crate::version;
+use crate::vsix::{self, VsixIdentity};
+use crate::CodeServerArgs;
+
+/// code-server 확장 프로그램 디렉토리의 설치 목록 파일
+const EXTENSIONS_MANIFEST: &str = "extensions.json";
+
+/// code-server에 확장 프로그램을 설치하는 방법
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProvisionMethod {
+    /// VSIX를 직접 풀고 extensions.json에 기록 (code-server 없이 이미지 빌드 중에도 사용 가능)
+    Extract,
+    /// `code-server --install-extension`으로 설치
+    Cli,
+}
+
+/// 다운로드 디렉토리에서 설치할 VSIX (ID마다 가장 높은 버전)
+struct Package {
+    path: PathBuf,
+    identity: VsixIdentity,
+}
+
+impl Package {
+    /// code-server가 쓰는 설치 디렉토리 이름 (`publisher.name-버전[-대상 플랫폼]`, 소문자 ID)
+    fn directory_name(&self) -> String {
+        match &self.identity.target_platform {
+            Some(target) => format!("{}-{}-{}", self.identity.id().to_lowercase(), self.identity.version, target),
+            None => format!("{}-{}", self.identity.id().to_lowercase(), self.identity.version),
+        }
+    }
+}
+
+/// 다운로드 디렉토리의 VSIX를 code-server 확장 프로그램 디렉토리에 설치합니다.
+/// 네트워크가 없는 원격 개발 호스트를 만들 때 씁니다.
+pub async fn code_server(args: &CodeServerArgs) -> Result<()> {
+    let packages = latest_packages(&args.output_dir)?;
+    if packages.is_empty() {
+        return Err(anyhow!("{}에 설치할 VSIX가 없습니다", args.output_dir.display()));
+    }
+
+    fs::create_dir_all(&args.extensions_dir)
+        .with_context(|| format!("Failed to create directory: {}", args.extensions_dir.display()))?;
+
+    println!(
+        "{}",
+        format!("확장 프로그램 {}개를 {}에 설치합니다...", packages.len(), args.extensions_dir.display()).blue()
+    );
+
+    match args.method {
+        ProvisionMethod::Cli => {
+            for package in &packages {
+                println!("{} {}@{}", "설치".blue(), package.identity.id(), package.identity.version);
+                if !args.dry_run {
+                    installed::install_vsix_into(&args.code_server_bin, &package.path, &args.extensions_dir).await?;
+                }
+            }
+        },
+        ProvisionMethod::Extract => extract_all(&packages, &args.extensions_dir, args.dry_run)?,
+    }
+
+    println!("{}", format!("code-server 확장 프로그램 {}개 설치 완료", packages.len()).green());
+    Ok(())
+}
+
+fn latest_packages(dir: &Path) -> Result<Vec<Package>> {
+    let mut packages: Vec<Package> = Vec::new();
+
+    for local in vsix::scan_directory(dir)? {
+        let identity = match vsix::read_identity(&local.path) {
+            Ok(identity) => identity,
+            Err(e) => {
+                println!("{} {}: {:#}", "건너뜀".yellow(), local.path.display(), e);
+                continue;
+            },
+        };
+
+        match packages.iter_mut().find(|p| p.identity.id().eq_ignore_ascii_case(&identity.id())) {
+            Some(existing) if version::compare_versions(&identity.version, &existing.identity.version) == Ordering::Greater => {
+                *existing = Package { path: local.path, identity };
+            },
+            Some(_) => {},
+            None => packages.push(Package { path: local.path, identity }),
+        }
+    }
+
+    packages.sort_by_key(|p| p.identity.id().to_lowercase());
+    Ok(packages)
+}
+
+/// VSIX의 `extension/` 내용을 설치 디렉토리로 풀고 extensions.json을 갱신합니다.
+/// 같은 확장 프로그램의 다른 버전이 있으면 그 디렉토리를 지우고 항목을 교체합니다.
+fn extract_all(packages: &[Package], extensions_dir: &Path, dry_run: bool) -> Result<()> {
+    let manifest_path = extensions_dir.join(EXTENSIONS_MANIFEST);
+    let mut entries: Vec<Value> = if manifest_path.exists() {
+        let content = fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", manifest_path.display()))?
+    } else {
+        Vec::new()
+    };
+
+    for package in packages {
+        let id = package.identity.id();
+        let directory_name = package.directory_name();
+        let target = extensions_dir.join(&directory_name);
+
+        let previous = entries.iter()
+            .position(|entry| entry_id(entry).is_some_and(|existing| existing.eq_ignore_ascii_case(&id)));
+        let previous_directory = previous
+            .and_then(|index| entries[index].get("relativeLocation").and_then(Value::as_str))
+            .map(str::to_string);
+
+        if previous_directory.as_deref() == Some(directory_name.as_str()) && target.exists() {
+            println!("{} {}@{}", "이미 설치됨".green(), id, package.identity.version);
+            continue;
+        }
+
+        println!("{} {}@{}", "설치".blue(), id, package.identity.version);
+        if dry_run {
+            continue;
+        }
+
+        if let Some(old) = previous_directory.filter(|old| old != &directory_name) {
+            let old_path = extensions_dir.join(&old);
+            if old_path.exists() {
+                fs::remove_dir_all(&old_path)
+                    .with_context(|| format!("Failed to remove {}", old_path.display()))?;
+            }
+        }
+        if target.exists() {
+            fs::remove_dir_all(&target)
+                .with_context(|| format!("Failed to remove {}", target.display()))?;
+        }
+
+        let (_, skipped) = vsix::extract_extension(&package.path, &target)?;
+        for name in &skipped {
+            println!("{} {}", "안전하지 않은 경로라 건너뜀:".yellow(), name);
+        }
+
+        let entry = manifest_entry(package, &target, &directory_name);
+        match previous {
+            Some(index) => entries[index] = entry,
+            None => entries.push(entry),
+        }
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    let serialized = serde_json::to_string(&entries)
+        .with_context(|| "Failed to serialize extensions.json")?;
+    fs::write(&manifest_path, serialized)
+        .with_context(|| format!("Failed to write {}", manifest_path.display()))
+}
+
+fn entry_id(entry: &Value) -> Option<&str> {
+    entry.get("identifier")?.get("id")?.as_str()
+}
+
+/// code-server(VSCode)가 extensions.json에 기록하는 설치 항목
+fn manifest_entry(package: &Package, location: &Path, directory_name: &str) -> Value {
+    let absolute = fs::canonicalize(location).unwrap_or_else(|_| location.to_path_buf());
+    let path = absolute.to_string_lossy().replace('\\', "/");
+
+    json!({
+        "identifier": { "id": package.identity.id().to_lowercase() },
+        "version": package.identity.version,
+        "location": { "$mid": 1, "path": path, "scheme": "file" },
+        "relativeLocation": directory_name,
+        "metadata": {
+            "installedTimestamp": Utc::now().timestamp_millis(),
+            "source": "vsix",
+            "targetPlatform": package.identity.target_platform.as_deref().unwrap_or("undefined"),
+            "isApplicationScoped": false,
+            "isMachineScoped": false,
+            "isBuiltin": false,
+            "pinned": true,
+        },
+    })
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::marketplace::GalleryPublisher;
+use crate::DownloadInfo;
+
+/// 이번 동기화를 시작할 때 읽은 이전 실행의 게시자 (소문자 ID 기준)
+static PREVIOUS: Mutex<Option<HashMap<String, PublisherInfo>>> = Mutex::new(None);
+
+/// 다운로드 정보 파일에 기록하는 Marketplace 게시자 정보
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublisherInfo {
+    /// 게시자 UUID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    /// 게시자가 등록한 도메인
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    /// Marketplace가 도메인 소유를 확인했는지 여부
+    #[serde(default)]
+    pub domain_verified: bool,
+}
+
+impl From<&GalleryPublisher> for PublisherInfo {
+    fn from(publisher: &GalleryPublisher) -> Self {
+        PublisherInfo {
+            id: publisher.publisher_id.clone(),
+            name: publisher.publisher_name.clone(),
+            domain: publisher.domain.clone(),
+            domain_verified: publisher.is_domain_verified,
+        }
+    }
+}
+
+/// 동기화를 시작할 때 이전 실행의 다운로드 정보에 기록된 게시자를 기억합니다.
+/// 확장 프로그램마다 다운로드 정보 파일을 다시 읽지 않도록 한 번만 읽습니다.
+pub fn remember_previous(downloads: &[DownloadInfo]) {
+    let publishers = downloads.iter()
+        .filter_map(|d| Some((d.id.to_ascii_lowercase(), d.publisher.clone()?)))
+        .collect();
+    *PREVIOUS.lock().expect("publisher lock poisoned") = Some(publishers);
+}
+
+/// 이전 실행에 기록된 확장 프로그램의 게시자 (대소문자 무시)
+pub fn previous(extension_id: &str) -> Option<PublisherInfo> {
+    PREVIOUS.lock().expect("publisher lock poisoned").as_ref()?
+        .get(&extension_id.to_ascii_lowercase())
+        .cloned()
+}
+
+/// 이전 실행에 기록된 게시자와 비교해 소유권이 바뀐 것으로 보이면 그 이유를 반환합니다.
+/// 게시자 UUID가 바뀌었거나, 확인된 도메인이 사라지거나 다른 도메인으로 바뀐 경우입니다.
+pub fn ownership_change(previous: &PublisherInfo, current: &PublisherInfo) -> Option<String> {
+    if let (Some(before), Some(after)) = (&previous.id, &current.id) {
+        if !before.eq_ignore_ascii_case(after) {
+            return Some(format!("게시자 UUID가 바뀌었습니다: {} -> {}", before, after));
+        }
+    }
+
+    if previous.domain_verified {
+        let before = previous.domain.as_deref().unwrap_or("-");
+        if !current.domain_verified {
+            return Some(format!("확인된 도메인 {}이(가) 더 이상 확인되지 않습니다", before));
+        }
+        if previous.domain != current.domain {
+            return Some(format!("확인된 도메인이 바뀌었습니다: {} -> {}", before, current.domain.as_deref().unwrap_or("-")));
+        }
+    }
+
+    None
+}
+
+/// `--trusted-publisher`에 지정한 게시자인지 (ID의 게시자 부분, 대소문자 무시)
+pub fn is_trusted(extension_id: &str, trusted: &[String]) -> bool {
+    let publisher = extension_id.split('.').next().unwrap_or(extension_id);
+    trusted.iter().any(|name| name.eq_ignore_ascii_case(publisher))
+}
+
+/// `--verified-publishers-only`에서 Marketplace 게시자를 받을 수 없는 이유.
+/// 도메인이 확인된 게시자나 신뢰 목록의 게시자면 None입니다.
+pub fn policy_violation(extension_id: &str, publisher: Option<&PublisherInfo>, trusted: &[String]) -> Option<String> {
+    if is_trusted(extension_id, trusted) {
+        return None;
+    }
+
+    match publisher {
+        Some(publisher) if publisher.domain_verified => None,
+        Some(publisher) => Some(format!("게시자 {}의 도메인이 확인되지 않았습니다", publisher.name)),
+        // 리졸버에서 받는 확장 프로그램은 게시자 정보가 없음
+        None => Some("게시자의 도메인 확인 여부를 알 수 없습니다".to_string()),
+    }
+}
+
+/// 게시자 변경을 눈에 띄게 경고합니다. 미러링하는 갤러리에서는 확장 프로그램 탈취의 전형적인 신호입니다.
+pub fn warn_ownership_change(extension_id: &str, reason: &str) {
+    let banner = "!".repeat(60);
+    println!("{}", banner.red().bold());
+    println!("{} {}", "경고: 게시자 소유권이 바뀌었습니다:".red().bold(), extension_id.red().bold());
+    println!("  {}", reason.red());
+    println!("  {}", "미러링하기 전에 게시자와 확장 프로그램이 신뢰할 수 있는지 확인하세요.".red());
+    println!("{}", banner.red().bold());
+}
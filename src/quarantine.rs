@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::error::{self, ErrorCode};
+use crate::filename;
+
+/// 다운로드 디렉토리 안의 격리 디렉토리 이름
+pub const QUARANTINE_DIR: &str = "quarantine";
+
+/// 격리된 파일 옆에 `<파일 이름>.json`으로 저장되는 실패 정보
+#[derive(Debug, Serialize)]
+struct QuarantineRecord<'a> {
+    id: &'a str,
+    run_id: &'a str,
+    url: &'a str,
+    error: ErrorCode,
+    message: String,
+    timestamp: String,
+}
+
+/// 다운로드를 시작할 때 다운로드 경로에 있던 파일의 크기와 수정 시각.
+/// 실패한 뒤 같은 상태면 이번 시도가 쓰지 않은 파일(증분 동기화에서 남겨 둔 이전 버전)이므로 격리하지 않습니다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileState(Option<(u64, SystemTime)>);
+
+impl FileState {
+    pub fn of(path: &Path) -> Self {
+        FileState(fs::metadata(filename::long_path(path)).ok()
+            .and_then(|metadata| Some((metadata.len(), metadata.modified().ok()?))))
+    }
+}
+
+/// 이번 시도가 쓴 실패하거나 손상된 다운로드 파일을 격리 디렉토리로 옮기고 원인을 기록합니다.
+/// 옮길 파일이 없거나 `before` 이후 바뀌지 않았으면 None을 반환합니다.
+pub fn quarantine_file(
+    output_dir: &Path,
+    file_path: &Path,
+    before: &FileState,
+    extension_id: &str,
+    run_id: &str,
+    url: &str,
+    err: &anyhow::Error
+) -> Result<Option<PathBuf>> {
+    let after = FileState::of(file_path);
+    if after.0.is_none() || after == *before {
+        return Ok(None);
+    }
+
+    let quarantine_dir = output_dir.join(QUARANTINE_DIR);
+    fs::create_dir_all(&quarantine_dir)
+        .with_context(|| format!("Failed to create quarantine directory: {}", quarantine_dir.display()))?;
+
+    let target = quarantine_target(&quarantine_dir, file_path, run_id)
+        .with_context(|| format!("Invalid download path: {}", file_path.display()))?;
+
+    fs::rename(filename::long_path(file_path), filename::long_path(&target))
+        .with_context(|| format!("Failed to move {} to quarantine", file_path.display()))?;
+
+    let record = QuarantineRecord {
+        id: extension_id,
+        run_id,
+        url,
+        error: error::error_code(err),
+        message: format!("{:#}", err),
+        timestamp: Utc::now().to_rfc3339(),
+    };
+
+    let mut sidecar = target.clone().into_os_string();
+    sidecar.push(".json");
+
+    let json = serde_json::to_string_pretty(&record)
+        .with_context(|| "Failed to serialize quarantine record")?;
+    fs::write(&sidecar, json)
+        .with_context(|| format!("Failed to write {}", PathBuf::from(&sidecar).display()))?;
+
+    Ok(Some(target))
+}
+
+/// 격리 위치 `<이름>.<실행 ID>.<확장자>`. 같은 파일이 여러 번 실패해도 이전에 격리한 파일을 덮지 않도록
+/// 실행 ID를 넣고, 한 실행 안에서 겹치면 번호를 붙입니다.
+fn quarantine_target(quarantine_dir: &Path, file_path: &Path, run_id: &str) -> Option<PathBuf> {
+    let stem = file_path.file_stem()?.to_string_lossy();
+    let extension = file_path.extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|n| match n {
+            1 => format!("{}.{}{}", stem, run_id, extension),
+            n => format!("{}.{}-{}{}", stem, run_id, n, extension),
+        })
+        .map(|name| quarantine_dir.join(name))
+        .find(|target| !filename::long_path(target).exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_earlier_quarantined_files() {
+        let dir = std::env::temp_dir().join(format!("vsix-downloader-quarantine-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file = Path::new("downloads/golang-go.vsix");
+
+        let first = quarantine_target(&dir, file, "20240601T000000Z-abc").unwrap();
+        assert_eq!(first, dir.join("golang-go.20240601T000000Z-abc.vsix"));
+        fs::write(&first, b"").unwrap();
+
+        assert_eq!(
+            quarantine_target(&dir, file, "20240601T000000Z-abc").unwrap(),
+            dir.join("golang-go.20240601T000000Z-abc-2.vsix")
+        );
+        assert_eq!(
+            quarantine_target(&dir, file, "20240602T000000Z-def").unwrap(),
+            dir.join("golang-go.20240602T000000Z-def.vsix")
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,353 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use futures_util::{stream, StreamExt};
+
+use crate::error::VsixError;
+use crate::http::{HttpClient, HttpMode};
+use crate::lockfile;
+use crate::marketplace;
+use crate::size;
+use crate::version;
+use crate::vsix;
+use crate::{load_extension_lists, ConflictPolicy, EngineReportArgs, FreshnessReportArgs, SizeReportArgs, OPEN_VSX_API};
+
+/// 목록의 확장 프로그램마다 다운로드 디렉토리의 VSIX가 요구하는 최소 VSCode 버전을 출력합니다.
+/// `--oldest-vscode`를 지정하면 그 버전에서 동작하지 않는 확장 프로그램을 강조하고 실패로 종료합니다.
+pub async fn engines(args: &EngineReportArgs) -> Result<()> {
+    let client = HttpClient::new(HttpMode::Live)?;
+    let desired = load_extension_lists(&client, &args.file, args.file_header.as_slice(), ConflictPolicy::Last, args.allow_unknown_fields).await?;
+    let packages = vsix::scan_directory(&args.output_dir)?;
+
+    let id_width = desired.iter().map(|e| e.id.len()).max().unwrap_or(0);
+
+    // 한글 제목은 글자당 두 칸을 차지하므로 그만큼 적게 채움
+    println!(
+        "{:<id_width$}  {:<14}  {:<12}  최소 VSCode",
+        "ID", "VSIX 버전", "엔진 범위",
+        id_width = id_width
+    );
+
+    let mut highest: Option<(String, String)> = None;
+    let mut breaking = Vec::new();
+
+    for extension in &desired {
+        let package = packages.iter()
+            .filter(|p| p.id.eq_ignore_ascii_case(&extension.id))
+            .filter(|p| extension.version.as_ref().is_none_or(|v| &p.version == v))
+            .max_by(|a, b| version::compare_versions(&a.version, &b.version));
+
+        let Some(package) = package else {
+            println!(
+                "{:<id_width$}  {:<16}  {:<16}  {}",
+                extension.id, "-", "-", "VSIX 없음".yellow(),
+                id_width = id_width
+            );
+            continue;
+        };
+
+        let range = vsix::read_engine(&package.path).ok().flatten();
+        let minimum = range.as_deref().and_then(version::minimum_engine_version);
+
+        let breaks = match (&args.oldest_vscode, &minimum) {
+            (Some(oldest), Some(minimum)) => version::compare_versions(minimum, oldest) == Ordering::Greater,
+            _ => false,
+        };
+
+        let minimum_column = match &minimum {
+            Some(minimum) if breaks => minimum.red(),
+            Some(minimum) => minimum.green(),
+            None => "-".normal(),
+        };
+
+        println!(
+            "{:<id_width$}  {:<16}  {:<16}  {}",
+            extension.id, package.version, range.as_deref().unwrap_or("-"), minimum_column,
+            id_width = id_width
+        );
+
+        if let Some(minimum) = minimum {
+            let is_higher = highest.as_ref()
+                .is_none_or(|(current, _)| version::compare_versions(&minimum, current) == Ordering::Greater);
+            if is_higher {
+                highest = Some((minimum.clone(), extension.id.clone()));
+            }
+            if breaks {
+                breaking.push((extension.id.clone(), minimum));
+            }
+        }
+    }
+
+    if let Some((minimum, id)) = &highest {
+        println!("\n{} {} ({})", "가장 높은 최소 요구 버전:".blue(), minimum, id);
+    }
+
+    let Some(oldest) = &args.oldest_vscode else {
+        return Ok(());
+    };
+
+    if breaking.is_empty() {
+        println!("{}", format!("모든 확장 프로그램이 VSCode {}에서 동작합니다.", oldest).green());
+        return Ok(());
+    }
+
+    println!("{}", format!("VSCode {}에서 동작하지 않는 확장 프로그램:", oldest).red());
+    for (id, minimum) in &breaking {
+        println!("  {} (최소 {})", id, minimum);
+    }
+
+    Err(anyhow!("VSCode {}에서 동작하지 않는 확장 프로그램이 {}개 있습니다", oldest, breaking.len()))
+}
+
+/// 다운로드 디렉토리의 VSIX를 크기가 큰 순으로 정렬해 파일별 크기, 누적 합계, 전체 대비 비율을 출력합니다.
+/// `--budget`을 지정하면 누적 합계가 예산을 넘는 항목부터 강조하고, 전체가 예산을 넘으면 실패로 종료합니다.
+pub fn sizes(args: &SizeReportArgs) -> Result<()> {
+    let mut packages: Vec<(vsix::LocalVsix, u64)> = vsix::scan_directory(&args.output_dir)?
+        .into_iter()
+        .map(|p| {
+            let size = fs::metadata(&p.path).map(|m| m.len()).unwrap_or(0);
+            (p, size)
+        })
+        .collect();
+
+    if packages.is_empty() {
+        println!("{}", format!("{} 디렉토리에 VSIX 파일이 없습니다.", args.output_dir.display()).yellow());
+        return Ok(());
+    }
+
+    packages.sort_by(|(a, a_size), (b, b_size)| b_size.cmp(a_size).then_with(|| a.id.cmp(&b.id)));
+
+    let total: u64 = packages.iter().map(|(_, size)| size).sum();
+    let id_width = packages.iter().map(|(p, _)| p.id.len()).max().unwrap_or(0);
+
+    // 한글 제목은 글자당 두 칸을 차지하므로 그만큼 적게 채움
+    println!(
+        "{:<id_width$}  {:<14}  {:<11}  {:>8}  {:>8}  비율",
+        "ID", "버전", "플랫폼", "크기", "누적",
+        id_width = id_width
+    );
+
+    let mut cumulative = 0;
+    for (package, size) in &packages {
+        cumulative += size;
+        let platform = vsix::read_identity(&package.path).ok()
+            .and_then(|i| i.target_platform)
+            .unwrap_or_else(|| "범용".to_string());
+
+        // "범용"처럼 한글이 들어간 값도 같은 칸에 맞춤
+        let platform_width = 14 - platform.chars().filter(|c| !c.is_ascii()).count();
+
+        let line = format!(
+            "{:<id_width$}  {:<16}  {:<platform_width$}  {:>10}  {:>10}  {:>5.1}%",
+            package.id, package.version, platform, size::format_size(*size), size::format_size(cumulative),
+            *size as f64 / total as f64 * 100.0,
+            id_width = id_width,
+            platform_width = platform_width
+        );
+
+        if args.budget.is_some_and(|budget| cumulative > budget) {
+            println!("{}", line.red());
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    println!("\n{} {} ({}개)", "전체 크기:".blue(), size::format_size(total), packages.len());
+
+    let Some(budget) = args.budget else {
+        return Ok(());
+    };
+
+    if total <= budget {
+        println!("{}", format!("예산 {} 안에 들어옵니다.", size::format_size(budget)).green());
+        return Ok(());
+    }
+
+    Err(anyhow!("전체 크기 {}가 예산 {}를 {} 넘습니다", size::format_size(total), size::format_size(budget), size::format_size(total - budget)))
+}
+
+/// 미러한 확장 프로그램 버전 하나와 미러한 시각
+struct Mirrored {
+    id: String,
+    version: String,
+    mirrored_at: Option<DateTime<Utc>>,
+}
+
+/// 레지스트리의 최신 버전과 미러한 버전이 뒤처졌는지
+struct Upstream {
+    latest: String,
+    outdated: bool,
+    /// 미러한 버전보다 새 버전이 처음 공개된 시각 (레지스트리가 알려주지 않으면 없음)
+    outdated_since: Option<DateTime<Utc>>,
+}
+
+/// 미러한 확장 프로그램마다 레지스트리의 최신 버전과 비교해 며칠째 뒤처져 있는지 출력합니다.
+/// 잠금 파일을 지정하면 기록된 버전과 기록 시각을, 아니면 다운로드 디렉토리의 VSIX와 파일 수정 시각을 사용합니다.
+/// `--max-behind`를 지정하면 그보다 오래 뒤처진 항목을 강조하고 실패로 종료합니다.
+pub async fn freshness(args: &FreshnessReportArgs) -> Result<()> {
+    let mirrored = match &args.lockfile {
+        Some(path) => lockfile::locked_versions(path)?
+            .into_iter()
+            .map(|(id, version, recorded)| Mirrored { id, version, mirrored_at: parse_time(&recorded) })
+            .collect(),
+        None => newest_packages(&args.output_dir)?,
+    };
+
+    if mirrored.is_empty() {
+        println!("{}", "미러한 확장 프로그램이 없습니다.".yellow());
+        return Ok(());
+    }
+
+    let client = HttpClient::new(HttpMode::Live)?;
+    let now = Utc::now();
+    let mut rows: Vec<(Mirrored, Result<Upstream>)> = stream::iter(mirrored)
+        .map(|m| {
+            let client = &client;
+            async move {
+                let upstream = upstream(client, &m.id, &m.version).await;
+                (m, upstream)
+            }
+        })
+        .buffered(8)
+        .collect()
+        .await;
+
+    let days_behind = |upstream: &Result<Upstream>| upstream.as_ref().ok()
+        .and_then(|upstream| upstream.outdated_since)
+        .map(|since| (now - since).num_days().max(0));
+    // 오래 뒤처진 순, 최신인 항목과 조회하지 못한 항목은 뒤로
+    rows.sort_by(|(a, a_upstream), (b, b_upstream)| {
+        days_behind(b_upstream).cmp(&days_behind(a_upstream)).then_with(|| a.id.cmp(&b.id))
+    });
+
+    let id_width = rows.iter().map(|(m, _)| m.id.len()).max().unwrap_or(0);
+
+    // 한글 제목은 글자당 두 칸을 차지하므로 그만큼 적게 채움
+    println!(
+        "{:<id_width$}  {:<13}  {:<13}  {:>6}  미러한 지",
+        "ID", "미러 버전", "최신 버전", "뒤처짐",
+        id_width = id_width
+    );
+
+    let mut outdated = 0;
+    let mut too_old = Vec::new();
+    for (mirrored, upstream) in &rows {
+        let mirrored_age = mirrored.mirrored_at
+            .map_or_else(|| "-".to_string(), |at| format!("{}일", (now - at).num_days().max(0)));
+
+        let latest = match upstream {
+            Ok(upstream) => upstream,
+            Err(e) => {
+                println!(
+                    "{:<id_width$}  {:<16}  {}",
+                    mirrored.id, mirrored.version, format!("조회 실패: {}", e).yellow(),
+                    id_width = id_width
+                );
+                continue;
+            },
+        };
+
+        let behind = days_behind(upstream);
+        let line = format!(
+            "{:<id_width$}  {:<16}  {:<16}  {:>8}  {}",
+            mirrored.id,
+            mirrored.version,
+            latest.latest,
+            match (latest.outdated, behind) {
+                (false, _) => "-".to_string(),
+                (true, Some(days)) => format!("{}일", days),
+                (true, None) => "?".to_string(),
+            },
+            mirrored_age,
+            id_width = id_width
+        );
+
+        match behind {
+            _ if !latest.outdated => println!("{}", line.green()),
+            Some(days) if args.max_behind.is_some_and(|max| days > max) => {
+                println!("{}", line.red());
+                too_old.push((mirrored.id.clone(), days));
+            },
+            _ => println!("{}", line.yellow()),
+        }
+        if latest.outdated {
+            outdated += 1;
+        }
+    }
+
+    println!(
+        "\n{} {}개 중 {}개가 최신 버전보다 뒤처져 있습니다.",
+        "신선도:".blue(), rows.len(), outdated
+    );
+
+    let Some(max_behind) = args.max_behind else {
+        return Ok(());
+    };
+    if too_old.is_empty() {
+        println!("{}", format!("{}일보다 오래 뒤처진 확장 프로그램이 없습니다.", max_behind).green());
+        return Ok(());
+    }
+
+    Err(anyhow!("{}일보다 오래 뒤처진 확장 프로그램이 {}개 있습니다", max_behind, too_old.len()))
+}
+
+/// 다운로드 디렉토리에서 확장 프로그램마다 가장 높은 버전의 VSIX
+fn newest_packages(output_dir: &Path) -> Result<Vec<Mirrored>> {
+    let mut newest: Vec<vsix::LocalVsix> = Vec::new();
+    for package in vsix::scan_directory(output_dir)? {
+        match newest.iter_mut().find(|p| p.id.eq_ignore_ascii_case(&package.id)) {
+            Some(existing) if version::compare_versions(&package.version, &existing.version) == Ordering::Greater => *existing = package,
+            Some(_) => {},
+            None => newest.push(package),
+        }
+    }
+
+    Ok(newest.into_iter()
+        .map(|package| Mirrored {
+            mirrored_at: fs::metadata(&package.path).and_then(|m| m.modified()).ok().map(DateTime::<Utc>::from),
+            id: package.id,
+            version: package.version,
+        })
+        .collect())
+}
+
+/// Marketplace의 버전 목록(최신 순)에서 미러한 버전보다 앞선 버전 중 가장 먼저 게시된 시각을 찾습니다.
+/// Marketplace에 없으면 Open VSX의 최신 버전 게시 시각을 사용합니다.
+async fn upstream(client: &HttpClient, id: &str, mirrored: &str) -> Result<Upstream> {
+    if let Ok(extension) = marketplace::query_extension(client, id, Some(mirrored)).await {
+        let latest = extension.versions.first()
+            .ok_or_else(|| anyhow!("Marketplace에 버전이 없습니다"))?
+            .version.clone();
+        let newer: Vec<_> = extension.versions.iter()
+            .take_while(|v| version::compare_versions(&v.version, mirrored) == Ordering::Greater)
+            .collect();
+        let outdated_since = newer.iter()
+            .filter_map(|v| v.last_updated.as_deref().and_then(parse_time))
+            .min();
+
+        return Ok(Upstream { latest, outdated: !newer.is_empty(), outdated_since });
+    }
+
+    let url = format!("{}/{}", OPEN_VSX_API, id.replacen('.', "/", 1));
+    let response = client.get(&url).await?;
+    if !response.status.is_success() {
+        return Err(VsixError::from_status(response.status, &url).into());
+    }
+    let data: serde_json::Value = response.json().await?;
+    let latest = data["version"].as_str()
+        .ok_or_else(|| anyhow!("Open VSX 응답에 버전이 없습니다"))?
+        .to_string();
+    let outdated = version::compare_versions(&latest, mirrored) == Ordering::Greater;
+    // Open VSX는 최신 버전의 게시 시각만 알려주므로 그 시각부터 셈
+    let outdated_since = data["timestamp"].as_str().and_then(parse_time).filter(|_| outdated);
+
+    Ok(Upstream { latest, outdated, outdated_since })
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
@@ -0,0 +1,182 @@
+use std::path::Path;
+use std::process::Stdio;
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::error::VsixError;
+use crate::hooks;
+use crate::vsix;
+use crate::{DownloadOrigin, DownloadedFile};
+
+/// 리졸버가 제공하는 다운로드를 가리키는 URL 접두사 (`resolver:<이름>/<ID>/<버전>`)
+const URL_PREFIX: &str = "resolver:";
+
+/// 설정 파일의 `resolvers`에 등록한 외부 레지스트리 실행 파일
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResolverPlugin {
+    /// 출력과 다운로드 정보에 표시할 이름
+    pub name: String,
+    /// 실행할 명령 (`resolve`나 `download` 인자가 덧붙음)
+    pub command: String,
+}
+
+#[derive(Serialize)]
+struct ResolveRequest<'a> {
+    id: &'a str,
+    version: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct DownloadRequest<'a> {
+    id: &'a str,
+    version: &'a str,
+    path: &'a str,
+}
+
+/// 리졸버의 응답. `resolve`는 제공할 수 있으면 `version`을 채우고, 실패하면 `error`를 채웁니다.
+#[derive(Debug, Default, Deserialize)]
+struct Response {
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// 리졸버에서 찾은 확장 프로그램
+pub struct Resolved {
+    pub version: String,
+    /// 다운로드 정보에 기록하고 다운로드할 때 리졸버를 다시 찾는 데 쓰는 URL
+    pub url: String,
+}
+
+/// 등록된 순서대로 리졸버에 확장 프로그램을 제공할 수 있는지 묻습니다.
+/// 리졸버가 실패하면 경고만 출력하고 다음 리졸버(마지막에는 Marketplace)로 넘어갑니다.
+pub async fn resolve(plugins: &[ResolverPlugin], id: &str, version: Option<&str>) -> Option<Resolved> {
+    for plugin in plugins {
+        let response = call(plugin, "resolve", &ResolveRequest { id, version }).await;
+        match response {
+            Ok(Response { version: Some(resolved), .. }) => {
+                println!("{} {}@{}", format!("{} 리졸버에서 제공:", plugin.name).green(), id, resolved);
+                return Some(Resolved {
+                    url: format!("{}{}/{}/{}", URL_PREFIX, plugin.name, id, resolved),
+                    version: resolved,
+                });
+            },
+            Ok(_) => {},
+            Err(e) => println!("{} {} 리졸버에서 {}을(를) 확인하지 못했습니다: {:#}", "경고:".yellow(), plugin.name, id, e),
+        }
+    }
+
+    None
+}
+
+/// 리졸버 URL이면 리졸버에게 VSIX를 `output_path`에 쓰게 하고 크기와 해시를 계산합니다. 리졸버 URL이 아니면 None입니다.
+pub async fn download(
+    plugins: &[ResolverPlugin],
+    url: &str,
+    output_path: &str,
+    max_size: Option<u64>
+) -> Option<Result<DownloadedFile>> {
+    let (name, id, version) = parse_url(url)?;
+    Some(download_from(plugins, url, name, id, version, output_path, max_size).await)
+}
+
+async fn download_from(
+    plugins: &[ResolverPlugin],
+    url: &str,
+    name: &str,
+    id: &str,
+    version: &str,
+    output_path: &str,
+    max_size: Option<u64>
+) -> Result<DownloadedFile> {
+    let plugin = plugins.iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| anyhow!("설정 파일에 {} 리졸버가 없습니다", name))?;
+
+    println!("{} {}", "다운로드 시작:".blue(), url);
+    call(plugin, "download", &DownloadRequest { id, version, path: output_path }).await?;
+
+    let path = Path::new(output_path);
+    if !path.exists() {
+        return Err(anyhow!("{} 리졸버가 {}에 파일을 쓰지 않았습니다", name, output_path));
+    }
+
+    let (size, sha256) = vsix::file_digest(path)?;
+    if let Some(limit) = max_size.filter(|limit| size > *limit) {
+        return Err(VsixError::SizeLimitExceeded { url: url.to_string(), size, limit }.into());
+    }
+
+    let origin = DownloadOrigin { resolver: Some(name.to_string()), ..DownloadOrigin::default() };
+    Ok(DownloadedFile { size, sha256, duration_ms: None, origin: Some(origin) })
+}
+
+/// `resolver:<이름>/<ID>/<버전>`을 나눕니다.
+fn parse_url(url: &str) -> Option<(&str, &str, &str)> {
+    let mut parts = url.strip_prefix(URL_PREFIX)?.splitn(3, '/');
+    Some((parts.next()?, parts.next()?, parts.next()?))
+}
+
+/// 리졸버 명령에 동작 이름을 인자로 붙여 실행하고, 요청 JSON을 표준 입력으로 보내 표준 출력의 응답 JSON을 읽습니다.
+/// 리졸버의 표준 오류는 그대로 출력됩니다.
+async fn call<T: Serialize>(plugin: &ResolverPlugin, action: &str, request: &T) -> Result<Response> {
+    let argv = hooks::split_command(&plugin.command)?;
+    let Some((program, args)) = argv.split_first() else {
+        return Err(anyhow!("{} 리졸버의 명령이 비어 있습니다", plugin.name));
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .arg(action)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run resolver {}: {}", plugin.name, program))?;
+
+    let body = serde_json::to_vec(request)
+        .with_context(|| "Failed to serialize resolver request")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(&body).await
+            .with_context(|| format!("Failed to write request to resolver {}", plugin.name))?;
+    }
+
+    let output = child.wait_with_output().await
+        .with_context(|| format!("Failed to run resolver {}", plugin.name))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let response: Response = if stdout.trim().is_empty() {
+        Response::default()
+    } else {
+        serde_json::from_str(stdout.trim())
+            .with_context(|| format!("Failed to parse response from resolver {}", plugin.name))?
+    };
+
+    if let Some(error) = response.error {
+        return Err(anyhow!("{} 리졸버 오류: {}", plugin.name, error));
+    }
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{} 리졸버가 {} 요청에 실패했습니다 ({})",
+            plugin.name,
+            action,
+            output.status.code().map_or_else(|| "시그널로 종료".to_string(), |code| format!("종료 코드 {}", code))
+        ));
+    }
+
+    Ok(response)
+}
+
+/// 리졸버 이름은 URL에 들어가므로 비어 있거나 `/`를 포함할 수 없습니다.
+pub fn validate(plugins: &[ResolverPlugin]) -> Result<()> {
+    for plugin in plugins {
+        if plugin.name.is_empty() || plugin.name.contains('/') {
+            return Err(anyhow!("리졸버 이름이 올바르지 않습니다 (비어 있거나 /를 포함): {:?}", plugin.name));
+        }
+    }
+    Ok(())
+}
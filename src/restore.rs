@@ -0,0 +1,87 @@
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+
+use crate::http::{HttpClient, HttpMode};
+use crate::installed;
+use crate::version;
+use crate::vsix;
+use crate::{load_extension_lists, ConflictPolicy, RestoreArgs};
+
+/// 스냅샷 목록과 정확히 같은 확장 프로그램 구성이 되도록
+/// 다운로드 디렉토리의 VSIX로 고정 버전을 설치하고 목록에 없는 확장 프로그램을 제거합니다.
+pub async fn run(args: &RestoreArgs) -> Result<()> {
+    let client = HttpClient::new(HttpMode::Live)?;
+    let desired = load_extension_lists(&client, std::slice::from_ref(&args.file), &[], ConflictPolicy::Last, false).await?;
+    let installed = installed::list_installed(&args.code_bin).await?;
+    let packages = vsix::scan_directory(&args.output_dir)?;
+
+    let mut to_install = Vec::new();
+    let mut missing = Vec::new();
+
+    for extension in &desired {
+        let current = installed.iter().find(|i| i.id.eq_ignore_ascii_case(&extension.id));
+        let up_to_date = match (current, &extension.version) {
+            (Some(current), Some(version)) => &current.version == version,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+
+        if up_to_date {
+            println!("{} {}", "이미 설치됨".green(), extension.id);
+            continue;
+        }
+
+        // 고정 버전이 없으면 디렉토리에 있는 가장 높은 버전을 설치
+        let package = packages.iter()
+            .filter(|p| p.id.eq_ignore_ascii_case(&extension.id))
+            .filter(|p| extension.version.as_ref().is_none_or(|v| &p.version == v))
+            .max_by(|a, b| version::compare_versions(&a.version, &b.version));
+
+        match package {
+            Some(package) => to_install.push(package),
+            None => {
+                println!(
+                    "{} {}@{}",
+                    "VSIX 없음".red(),
+                    extension.id,
+                    extension.version.as_deref().unwrap_or("latest")
+                );
+                missing.push(extension.id.clone());
+            },
+        }
+    }
+
+    // 일부만 복원된 상태로 남지 않도록 VSIX가 모두 있을 때만 변경
+    if !missing.is_empty() {
+        return Err(anyhow!("다운로드 디렉토리에 VSIX가 없는 확장 프로그램: {}", missing.join(", ")));
+    }
+
+    let extras: Vec<_> = if args.keep_extras {
+        Vec::new()
+    } else {
+        installed.iter()
+            .filter(|i| !desired.iter().any(|d| d.id.eq_ignore_ascii_case(&i.id)))
+            .collect()
+    };
+
+    for package in &to_install {
+        println!("{} {}@{}", "설치".blue(), package.id, package.version);
+        if !args.dry_run {
+            installed::install_vsix(&args.code_bin, &package.path).await?;
+        }
+    }
+
+    for extra in &extras {
+        println!("{} {}@{}", "제거".yellow(), extra.id, extra.version);
+        if !args.dry_run {
+            installed::uninstall(&args.code_bin, &extra.id).await?;
+        }
+    }
+
+    println!(
+        "{}",
+        format!("복원 완료: {}개 설치, {}개 제거", to_install.len(), extras.len()).green()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,280 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::profile;
+use crate::run_meta::RunMetadata;
+use crate::status_file;
+use crate::{Attempt, AttemptStage, AvailableExtension, CheckOutcome, DownloadReport, Extension, Results, UnavailableExtension};
+
+/// 진행 중인 동기화의 작업 상태 파일 이름
+pub const STATE_FILE: &str = "sync-state.json";
+
+/// 작업 상태 파일 경로. 프로필은 서로의 상태를 덮지 않도록 `profiles/<이름>/`에, 아니면 현재 디렉토리에 둡니다.
+pub fn state_path(profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(name) => profile::state_dir(name).join(STATE_FILE),
+        None => PathBuf::from(STATE_FILE),
+    }
+}
+
+/// 중단된 동기화를 이어서 실행하기 위한 작업 대기열 상태
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncState {
+    /// 동기화를 시작한 명령줄 (resume에서 같은 옵션으로 다시 해석)
+    pub argv: Vec<String>,
+    pub run: RunMetadata,
+    /// --run-dirs로 만든 실행 디렉토리
+    #[serde(default)]
+    pub run_dir: Option<PathBuf>,
+    pub items: Vec<WorkItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkItem {
+    pub id: String,
+    pub status: WorkStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available: Option<AvailableExtension>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unavailable: Option<UnavailableExtension>,
+    /// 마지막 다운로드 단계의 요청 기록
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<Attempt>,
+    /// 마지막 다운로드 단계에서 찾은 비슷한 ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+    /// 받은 VSIX의 확장 팩 구성
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extension_pack: Vec<String>,
+}
+
+/// 확장 프로그램 하나의 진행 상태.
+/// 확인 중이거나 다운로드 중에 중단된 항목은 이어서 실행할 때 해당 단계부터 다시 진행합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkStatus {
+    Pending,
+    Checking,
+    Checked,
+    Downloading,
+    Failed,
+    Done,
+}
+
+/// 작업 상태가 바뀔 때마다 기록합니다. 시작할 때 전체 상태를 상태 파일에 쓰고,
+/// 이후의 변경은 바뀐 항목 하나를 저널(sync-state.json.journal)에 한 줄씩 덧붙여 항목 수에 비례하는 쓰기를 반복하지 않습니다.
+pub struct WorkTracker {
+    state: Mutex<SyncState>,
+    path: PathBuf,
+}
+
+/// 상태 파일 옆의 변경 저널
+fn journal_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".journal");
+    PathBuf::from(name)
+}
+
+/// 저장된 작업 상태를 읽고 저널의 변경을 차례로 적용합니다. 기록 도중 중단되어 마지막 줄이 끊겼으면 그 줄은 무시합니다.
+pub fn load_state(path: &Path) -> Result<SyncState> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("이어서 실행할 동기화가 없습니다 ({}를 읽을 수 없음)", path.display()))?;
+
+    let mut state: SyncState = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let journal = journal_path(path);
+    if !journal.exists() {
+        return Ok(state);
+    }
+
+    let content = fs::read_to_string(&journal)
+        .with_context(|| format!("Failed to read {}", journal.display()))?;
+    let complete = content.rfind('\n').map_or("", |end| &content[..end]);
+
+    for (number, line) in complete.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let item: WorkItem = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse {} line {}", journal.display(), number + 1))?;
+        match state.items.iter_mut().find(|i| i.id.eq_ignore_ascii_case(&item.id)) {
+            Some(existing) => *existing = item,
+            None => state.items.push(item),
+        }
+    }
+
+    Ok(state)
+}
+
+impl WorkTracker {
+    /// 새 실행이면 모든 항목을 대기 상태로 만들고, 이어서 실행하면 저장된 상태에 새 목록 항목을 더합니다.
+    pub fn start(
+        argv: Vec<String>,
+        run: RunMetadata,
+        run_dir: Option<PathBuf>,
+        path: PathBuf,
+        extensions: &[Extension],
+        resumed: Option<SyncState>
+    ) -> Result<Self> {
+        let mut items = resumed.map(|s| s.items).unwrap_or_default();
+
+        for extension in extensions {
+            if !items.iter().any(|i| i.id.eq_ignore_ascii_case(&extension.id)) {
+                items.push(WorkItem {
+                    id: extension.id.clone(),
+                    status: WorkStatus::Pending,
+                    available: None,
+                    unavailable: None,
+                    attempts: Vec::new(),
+                    suggestions: Vec::new(),
+                    extension_pack: Vec::new(),
+                });
+            }
+        }
+
+        let tracker = WorkTracker {
+            state: Mutex::new(SyncState { argv, run, run_dir, items }),
+            path,
+        };
+        tracker.save()?;
+
+        Ok(tracker)
+    }
+
+    /// 이미 확인이 끝난 항목의 결과
+    pub fn checked(&self, id: &str) -> Option<CheckOutcome> {
+        let state = self.state.lock().expect("work state lock poisoned");
+        let item = state.items.iter().find(|i| i.id.eq_ignore_ascii_case(id))?;
+
+        match item.status {
+            WorkStatus::Pending | WorkStatus::Checking => None,
+            _ => item.available.clone().map(CheckOutcome::Available)
+                .or_else(|| item.unavailable.clone().map(CheckOutcome::Unavailable)),
+        }
+    }
+
+    pub fn is_downloaded(&self, id: &str) -> bool {
+        let state = self.state.lock().expect("work state lock poisoned");
+        state.items.iter().any(|i| i.id.eq_ignore_ascii_case(id) && i.status == WorkStatus::Done)
+    }
+
+    pub fn set_status(&self, id: &str, status: WorkStatus) -> Result<()> {
+        self.update(id, |item| item.status = status)
+    }
+
+    pub fn record_check(&self, id: &str, outcome: &CheckOutcome) -> Result<()> {
+        self.update(id, |item| {
+            item.status = match outcome {
+                // Open VSX에서 받을 수 있는 항목은 다운로드 단계가 없음
+                CheckOutcome::Available(_) => WorkStatus::Done,
+                CheckOutcome::Unavailable(_) => WorkStatus::Checked,
+            };
+            match outcome {
+                CheckOutcome::Available(e) => item.available = Some(e.clone()),
+                CheckOutcome::Unavailable(e) => item.unavailable = Some(e.clone()),
+            }
+        })
+    }
+
+    pub fn record_download(&self, id: &str, report: DownloadReport) -> Result<()> {
+        self.update(id, |item| {
+            item.attempts = report.attempts;
+            item.suggestions = report.suggestions;
+            item.extension_pack = report.extension_pack;
+        })
+    }
+
+    /// 다운로드에 실패한 항목의 요청 기록과 비슷한 ID 제안을 결과에 덧붙입니다.
+    /// 다운로드에 성공한 항목은 확인 단계의 실패 기록도 지웁니다.
+    pub fn attach_download_reports(&self, results: &mut Results) {
+        let state = self.state.lock().expect("work state lock poisoned");
+
+        for extension in &mut results.unavailable {
+            let Some(item) = state.items.iter().find(|i| i.id.eq_ignore_ascii_case(&extension.id)) else {
+                continue;
+            };
+
+            match item.status {
+                WorkStatus::Done => {
+                    extension.attempts.clear();
+                    if !item.extension_pack.is_empty() {
+                        extension.extension_pack = item.extension_pack.clone();
+                    }
+                },
+                WorkStatus::Failed => {
+                    // 여러 번 호출해도 같은 결과가 되도록 다운로드 단계 기록은 바꿔 넣음
+                    extension.attempts.retain(|a| matches!(a.stage, AttemptStage::Check));
+                    extension.attempts.extend(item.attempts.iter().cloned());
+                    extension.suggestions = item.suggestions.clone();
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// 동기화가 끝나면 상태 파일과 저널을 지웁니다.
+    pub fn finish(self) -> Result<()> {
+        for path in [journal_path(&self.path), self.path.clone()] {
+            if path.exists() {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 항목 하나를 바꾸고 바뀐 항목을 저널에 한 줄로 덧붙입니다.
+    /// 상태 잠금을 쥔 채 기록하므로 저널의 줄 순서는 변경 순서와 같습니다.
+    fn update(&self, id: &str, change: impl FnOnce(&mut WorkItem)) -> Result<()> {
+        let mut state = self.state.lock().expect("work state lock poisoned");
+        let Some(item) = state.items.iter_mut().find(|i| i.id.eq_ignore_ascii_case(id)) else {
+            return Ok(());
+        };
+        change(item);
+
+        let mut line = serde_json::to_string(item)
+            .with_context(|| "Failed to serialize work item")?;
+        line.push('\n');
+        status_file::record_items(&state.items);
+
+        let path = journal_path(&self.path);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        file.write_all(line.as_bytes())
+            .with_context(|| format!("Failed to append to {}", path.display()))
+    }
+
+    /// 전체 상태를 임시 파일에 쓴 뒤 이름을 바꿔 중간에 끊겨도 상태 파일이 깨지지 않게 하고, 상태 파일에 반영된 저널을 지웁니다.
+    /// 이름을 바꾼 뒤 저널을 지우기 전에 중단되어도 같은 변경을 다시 적용할 뿐이므로 결과는 같습니다.
+    fn save(&self) -> Result<()> {
+        let state = self.state.lock().expect("work state lock poisoned");
+        status_file::record_items(&state.items);
+        let json = serde_json::to_string_pretty(&*state)
+            .with_context(|| "Failed to serialize work state")?;
+
+        let mut staged = self.path.as_os_str().to_os_string();
+        staged.push(".tmp");
+        let staged = PathBuf::from(staged);
+        fs::write(&staged, json)
+            .with_context(|| format!("Failed to write {}", staged.display()))?;
+        fs::rename(&staged, &self.path)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+
+        let journal = journal_path(&self.path);
+        if journal.exists() {
+            fs::remove_file(&journal)
+                .with_context(|| format!("Failed to remove {}", journal.display()))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+/// 마지막으로 성공한 실행 디렉토리를 가리키는 링크 이름
+pub const LATEST_LINK: &str = "latest";
+
+/// `<base>/<시각>` 형식의 새 실행 디렉토리를 만듭니다 (예: downloads/2024-06-01T03-00).
+/// 같은 분에 여러 번 실행하면 `-2`, `-3` 같은 접미사를 붙입니다.
+pub fn create_run_directory(base: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(base)
+        .with_context(|| format!("Failed to create directory: {}", base.display()))?;
+
+    let name = Utc::now().format("%Y-%m-%dT%H-%M").to_string();
+    let mut run_dir = base.join(&name);
+    let mut attempt = 1;
+
+    while run_dir.exists() {
+        attempt += 1;
+        run_dir = base.join(format!("{}-{}", name, attempt));
+    }
+
+    fs::create_dir(&run_dir)
+        .with_context(|| format!("Failed to create run directory: {}", run_dir.display()))?;
+
+    Ok(run_dir)
+}
+
+/// `<base>/latest`가 가리키는 실행 디렉토리 (아직 성공한 실행이 없으면 None)
+pub fn latest_run_directory(base: &Path) -> Option<PathBuf> {
+    let target = fs::read_link(base.join(LATEST_LINK)).ok()?;
+    let run_dir = base.join(target);
+    run_dir.is_dir().then_some(run_dir)
+}
+
+/// `<base>/latest`가 실행 디렉토리를 가리키도록 상대 경로 심볼릭 링크를 교체합니다.
+pub fn update_latest_link(base: &Path, run_dir: &Path) -> Result<()> {
+    let link = base.join(LATEST_LINK);
+    let target = run_dir.file_name()
+        .map(PathBuf::from)
+        .with_context(|| format!("Invalid run directory: {}", run_dir.display()))?;
+
+    // 새 링크를 만든 뒤 이름을 바꿔 latest가 잠시라도 사라지지 않게 함
+    let staged = base.join(format!(".{}.tmp", LATEST_LINK));
+    let _ = remove_link(&staged);
+
+    symlink_dir(&target, &staged)
+        .with_context(|| format!("Failed to create link: {}", staged.display()))?;
+
+    if cfg!(windows) {
+        let _ = remove_link(&link);
+    }
+
+    fs::rename(&staged, &link)
+        .with_context(|| format!("Failed to update link: {}", link.display()))?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+fn remove_link(link: &Path) -> io::Result<()> {
+    // Windows의 디렉토리 링크는 remove_dir로 지워야 함
+    fs::remove_file(link).or_else(|_| fs::remove_dir(link))
+}
@@ -0,0 +1,46 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::Extension;
+
+/// 출력 파일을 만든 실행을 추적하기 위한 정보
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub run_id: String,
+    pub tool_version: String,
+    pub timestamp: String,
+    /// 합쳐진 입력 목록(ID와 고정 버전)의 SHA-256 해시
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_hash: Option<String>,
+}
+
+impl RunMetadata {
+    pub fn new(input: Option<&[Extension]>) -> Self {
+        let now = Utc::now();
+
+        // 같은 초에 여러 번 실행해도 겹치지 않도록 임의 값을 붙임
+        let random = RandomState::new().build_hasher().finish() as u32;
+
+        RunMetadata {
+            run_id: format!("{}-{:08x}", now.format("%Y%m%dT%H%M%SZ"), random),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: now.to_rfc3339(),
+            input_hash: input.map(input_hash),
+        }
+    }
+}
+
+fn input_hash(extensions: &[Extension]) -> String {
+    let mut hasher = Sha256::new();
+    for extension in extensions {
+        hasher.update(extension.id.as_bytes());
+        hasher.update(b"@");
+        hasher.update(extension.version.as_deref().unwrap_or("latest").as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
@@ -0,0 +1,328 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use crate::error::VsixError;
+
+/// 검사 서버에 보내는 조각 크기
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// 파일 하나를 검사하는 데 기다리는 최대 시간
+const SCAN_TIMEOUT: Duration = Duration::from_secs(300);
+
+const CLAMD_PORT: u16 = 3310;
+const ICAP_PORT: u16 = 1344;
+
+/// 받은 VSIX를 보낼 악성 코드 검사 서버 (`--scan`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scanner {
+    /// clamd TCP 소켓 (`clamd://host:3310`)
+    ClamdTcp { addr: String },
+    /// clamd 유닉스 소켓 (`clamd:///run/clamav/clamd.ctl`)
+    ClamdUnix { path: String },
+    /// ICAP 서버의 RESPMOD 서비스 (`icap://host:1344/avscan`)
+    Icap { addr: String, host: String, service: String },
+}
+
+/// 검사 결과 (다운로드 정보 파일의 `scan`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanVerdict {
+    /// 검사한 서버 (`--scan` 값)
+    pub scanner: String,
+    pub infected: bool,
+    /// 검사 서버가 알려준 악성 코드 이름
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    pub timestamp: String,
+}
+
+impl FromStr for Scanner {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (scheme, rest) = value.split_once("://")
+            .ok_or_else(|| format!("검사 서버는 clamd:// 또는 icap:// 주소여야 합니다: {}", value))?;
+
+        match scheme {
+            "clamd" if rest.starts_with('/') => Ok(Scanner::ClamdUnix { path: rest.to_string() }),
+            "clamd" if !rest.is_empty() => Ok(Scanner::ClamdTcp { addr: with_port(rest.trim_end_matches('/'), CLAMD_PORT) }),
+            "icap" => {
+                let (authority, service) = rest.split_once('/').unwrap_or((rest, ""));
+                if authority.is_empty() || service.is_empty() {
+                    return Err(format!("ICAP 주소에는 서버와 서비스 이름이 필요합니다 (예: icap://host:1344/avscan): {}", value));
+                }
+                Ok(Scanner::Icap {
+                    addr: with_port(authority, ICAP_PORT),
+                    host: authority.to_string(),
+                    service: service.to_string(),
+                })
+            },
+            _ => Err(format!("지원하지 않는 검사 서버 주소입니다 (clamd:// 또는 icap://): {}", value)),
+        }
+    }
+}
+
+impl fmt::Display for Scanner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scanner::ClamdTcp { addr } => write!(f, "clamd://{}", addr),
+            Scanner::ClamdUnix { path } => write!(f, "clamd://{}", path),
+            Scanner::Icap { host, service, .. } => write!(f, "icap://{}/{}", host, service),
+        }
+    }
+}
+
+fn with_port(authority: &str, default_port: u16) -> String {
+    // IPv6 주소([::1])의 콜론은 포트로 보지 않음
+    let has_port = authority.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok() && !authority.ends_with(']'));
+    if has_port {
+        authority.to_string()
+    } else {
+        format!("{}:{}", authority, default_port)
+    }
+}
+
+/// 파일을 검사 서버로 흘려보내 판정을 받습니다. 검사 서버에 연결하지 못하거나 응답을 해석하지 못하면
+/// 검사되지 않은 파일이 통과하지 않도록 오류를 돌려줍니다.
+pub async fn scan_file(scanner: &Scanner, id: &str, path: &Path) -> Result<ScanVerdict> {
+    let signature = tokio::time::timeout(SCAN_TIMEOUT, scan(scanner, path)).await
+        .map_err(|_| anyhow!("악성 코드 검사 시간이 초과되었습니다 ({})", scanner))?
+        .with_context(|| format!("Failed to scan {} with {}", path.display(), scanner))?;
+
+    let verdict = ScanVerdict {
+        scanner: scanner.to_string(),
+        infected: signature.is_some(),
+        signature,
+        timestamp: Utc::now().to_rfc3339(),
+    };
+
+    match &verdict.signature {
+        None => println!("{} {}", "악성 코드 검사 통과:".green(), id),
+        Some(signature) => println!("{} {} ({})", "악성 코드 발견:".red().bold(), id, signature),
+    }
+    Ok(verdict)
+}
+
+/// 발견된 악성 코드 이름. 깨끗하면 None
+async fn scan(scanner: &Scanner, path: &Path) -> Result<Option<String>> {
+    match scanner {
+        Scanner::ClamdTcp { addr } => {
+            let stream = TcpStream::connect(addr).await
+                .with_context(|| format!("Failed to connect to clamd at {}", addr))?;
+            clamd_instream(stream, path).await
+        },
+        #[cfg(unix)]
+        Scanner::ClamdUnix { path: socket } => {
+            let stream = tokio::net::UnixStream::connect(socket).await
+                .with_context(|| format!("Failed to connect to clamd at {}", socket))?;
+            clamd_instream(stream, path).await
+        },
+        #[cfg(not(unix))]
+        Scanner::ClamdUnix { .. } => Err(anyhow!("이 운영체제에서는 clamd 유닉스 소켓을 사용할 수 없습니다. clamd://host:port를 사용하세요")),
+        Scanner::Icap { addr, host, service } => {
+            let stream = TcpStream::connect(addr).await
+                .with_context(|| format!("Failed to connect to ICAP server at {}", addr))?;
+            icap_respmod(stream, host, service, path).await
+        },
+    }
+}
+
+/// clamd INSTREAM: 길이(4바이트 빅 엔디언)를 앞에 붙인 조각들을 보내고 길이 0으로 끝냅니다.
+/// 응답은 `stream: OK` 또는 `stream: <이름> FOUND`입니다.
+async fn clamd_instream<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, path: &Path) -> Result<Option<String>> {
+    stream.write_all(b"zINSTREAM\0").await?;
+
+    let mut file = File::open(path).await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        stream.write_all(&(read as u32).to_be_bytes()).await?;
+        if read == 0 {
+            break;
+        }
+        stream.write_all(&buffer[..read]).await?;
+    }
+    stream.flush().await?;
+
+    let mut reply = Vec::new();
+    stream.read_to_end(&mut reply).await?;
+    clamd_verdict(&reply)
+}
+
+/// clamd 응답에서 발견된 악성 코드 이름을 꺼냅니다 (깨끗하면 None).
+fn clamd_verdict(reply: &[u8]) -> Result<Option<String>> {
+    let reply = String::from_utf8_lossy(reply);
+    let reply = reply.trim_end_matches(['\0', '\n']).trim();
+    let verdict = reply.strip_prefix("stream:").unwrap_or(reply).trim();
+
+    if verdict == "OK" {
+        Ok(None)
+    } else if let Some(signature) = verdict.strip_suffix("FOUND") {
+        Ok(Some(signature.trim().to_string()))
+    } else {
+        Err(anyhow!("clamd 응답을 해석할 수 없습니다: {}", reply))
+    }
+}
+
+/// ICAP RESPMOD (RFC 3507): 파일을 HTTP 응답 본문으로 감싸 청크 단위로 보냅니다.
+/// 204면 깨끗하고, 200이면 악성 코드 헤더나 서버가 바꾼 응답 상태로 판정합니다.
+async fn icap_respmod(stream: TcpStream, host: &str, service: &str, path: &Path) -> Result<Option<String>> {
+    let file_name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let size = tokio::fs::metadata(path).await
+        .with_context(|| format!("Failed to read {}", path.display()))?
+        .len();
+
+    let request_header = format!("GET /{} HTTP/1.1\r\nHost: vsix-downloader\r\n\r\n", file_name);
+    let response_header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/vsix\r\nContent-Length: {}\r\n\r\n",
+        size
+    );
+    let icap_header = format!(
+        "RESPMOD icap://{}/{} ICAP/1.0\r\nHost: {}\r\nAllow: 204\r\nEncapsulated: req-hdr=0, res-hdr={}, res-body={}\r\n\r\n",
+        host, service, host, request_header.len(), request_header.len() + response_header.len()
+    );
+
+    let mut stream = BufReader::new(stream);
+    stream.write_all(icap_header.as_bytes()).await?;
+    stream.write_all(request_header.as_bytes()).await?;
+    stream.write_all(response_header.as_bytes()).await?;
+
+    let mut file = File::open(path).await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        stream.write_all(format!("{:x}\r\n", read).as_bytes()).await?;
+        stream.write_all(&buffer[..read]).await?;
+        stream.write_all(b"\r\n").await?;
+    }
+    stream.write_all(b"0\r\n\r\n").await?;
+    stream.flush().await?;
+
+    let mut status_line = String::new();
+    stream.read_line(&mut status_line).await?;
+    let status: u16 = status_line.split_whitespace().nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| anyhow!("ICAP 응답을 해석할 수 없습니다: {}", status_line.trim()))?;
+
+    let mut threat = None;
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim().to_ascii_lowercase();
+            if matches!(name.as_str(), "x-infection-found" | "x-virus-id" | "x-violations-found") {
+                threat = Some(threat_name(value.trim()));
+            }
+        }
+    }
+
+    match status {
+        204 => Ok(None),
+        200 if threat.is_some() => Ok(threat),
+        200 => {
+            // 악성 코드 헤더 없이 차단 페이지로 바꾼 응답을 돌려주는 서버도 있음
+            let mut http_status = String::new();
+            stream.read_line(&mut http_status).await?;
+            match http_status.split_whitespace().nth(1) {
+                Some("200") | None => Ok(None),
+                Some(code) => Ok(Some(format!("ICAP 서버가 차단함 (HTTP {})", code))),
+            }
+        },
+        _ => Err(anyhow!("ICAP 서버가 검사하지 못했습니다: {}", status_line.trim())),
+    }
+}
+
+/// `Type=0; Resolution=2; Threat=EICAR;` 형식이면 Threat 값만, 아니면 헤더 값 전체
+fn threat_name(value: &str) -> String {
+    value.split(';')
+        .find_map(|part| part.trim().strip_prefix("Threat="))
+        .unwrap_or(value)
+        .trim()
+        .to_string()
+}
+
+/// 악성 코드가 발견된 판정을 다운로드 실패 오류로 바꿉니다.
+pub fn ensure_clean(id: &str, verdict: &ScanVerdict) -> Result<()> {
+    match &verdict.signature {
+        Some(signature) if verdict.infected => Err(VsixError::Infected {
+            id: id.to_string(),
+            scanner: verdict.scanner.clone(),
+            signature: signature.clone(),
+        }.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_clamd_addresses() {
+        assert_eq!("clamd://scanner".parse(), Ok(Scanner::ClamdTcp { addr: "scanner:3310".to_string() }));
+        assert_eq!("clamd://scanner:3311/".parse(), Ok(Scanner::ClamdTcp { addr: "scanner:3311".to_string() }));
+        assert_eq!(
+            "clamd:///run/clamav/clamd.ctl".parse(),
+            Ok(Scanner::ClamdUnix { path: "/run/clamav/clamd.ctl".to_string() })
+        );
+        assert!("clamd://".parse::<Scanner>().is_err());
+    }
+
+    #[test]
+    fn parses_icap_addresses() {
+        assert_eq!(
+            "icap://scanner/avscan".parse(),
+            Ok(Scanner::Icap { addr: "scanner:1344".to_string(), host: "scanner".to_string(), service: "avscan".to_string() })
+        );
+        assert!("icap://scanner".parse::<Scanner>().is_err());
+        assert!("icap:///avscan".parse::<Scanner>().is_err());
+        assert!("http://scanner".parse::<Scanner>().is_err());
+        assert!("scanner:3310".parse::<Scanner>().is_err());
+    }
+
+    #[test]
+    fn keeps_bracketed_ipv6_colons() {
+        assert_eq!(with_port("[::1]", CLAMD_PORT), "[::1]:3310");
+        assert_eq!(with_port("[fe80::2]:3311", CLAMD_PORT), "[fe80::2]:3311");
+        assert_eq!(with_port("scanner", ICAP_PORT), "scanner:1344");
+        assert_eq!("clamd://[::1]".parse(), Ok(Scanner::ClamdTcp { addr: "[::1]:3310".to_string() }));
+    }
+
+    #[test]
+    fn displays_scanner_addresses() {
+        let icap: Scanner = "icap://scanner:1345/avscan".parse().unwrap();
+        assert_eq!(icap.to_string(), "icap://scanner:1345/avscan");
+        let unix: Scanner = "clamd:///run/clamav/clamd.ctl".parse().unwrap();
+        assert_eq!(unix.to_string(), "clamd:///run/clamav/clamd.ctl");
+    }
+
+    #[test]
+    fn reads_clamd_replies() {
+        assert_eq!(clamd_verdict(b"stream: OK\0").unwrap(), None);
+        assert_eq!(clamd_verdict(b"stream: Eicar-Signature FOUND\0").unwrap().as_deref(), Some("Eicar-Signature"));
+        assert_eq!(clamd_verdict(b"stream: Eicar FOUND\n").unwrap().as_deref(), Some("Eicar"));
+        assert!(clamd_verdict(b"INSTREAM size limit exceeded. ERROR\0").is_err());
+    }
+
+    #[test]
+    fn extracts_threat_from_icap_headers() {
+        assert_eq!(threat_name("Type=0; Resolution=2; Threat=EICAR;"), "EICAR");
+        assert_eq!(threat_name("Eicar-Test-Signature"), "Eicar-Test-Signature");
+    }
+}
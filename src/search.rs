@@ -0,0 +1,106 @@
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::http::{HttpClient, HttpMode};
+use crate::marketplace;
+use crate::SearchArgs;
+
+/// `search --json` 출력 내용
+#[derive(Debug, Serialize)]
+struct SearchOutput<'a> {
+    query: &'a str,
+    page: u32,
+    limit: u32,
+    /// 검색어에 맞는 전체 확장 프로그램 수 (Marketplace가 알려주지 않으면 없음)
+    total: Option<u64>,
+    extensions: Vec<SearchResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchResult {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    version: Option<String>,
+    installs: Option<u64>,
+    rating: Option<f64>,
+    updated: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+/// Marketplace에서 검색어로 확장 프로그램을 찾아 `--sort` 순으로 `--page`번째 페이지를 출력합니다.
+pub async fn run(args: &SearchArgs) -> Result<()> {
+    let client = HttpClient::new(HttpMode::Live)?;
+    let page = marketplace::search_page(&client, &args.query, args.sort, args.page, args.limit).await?;
+
+    let extensions: Vec<SearchResult> = page.extensions.iter()
+        .map(|extension| {
+            let latest = extension.versions.first();
+            SearchResult {
+                id: extension.id(),
+                display_name: extension.display_name.clone(),
+                version: latest.map(|v| v.version.clone()),
+                installs: extension.statistic("install").map(|count| count as u64),
+                rating: extension.statistic("averagerating"),
+                updated: latest.and_then(|v| v.last_updated.clone()),
+                description: extension.short_description.clone(),
+            }
+        })
+        .collect();
+
+    if args.json {
+        let output = SearchOutput {
+            query: &args.query,
+            page: args.page,
+            limit: args.limit,
+            total: page.total,
+            extensions,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if extensions.is_empty() {
+        println!("{}", format!("'{}'에 맞는 확장 프로그램이 없습니다 ({}페이지).", args.query, args.page).yellow());
+        return Ok(());
+    }
+
+    let id_width = extensions.iter().map(|e| e.id.len()).max().unwrap_or(0);
+    // 한글 제목은 글자당 두 칸을 차지하므로 그만큼 적게 채움
+    println!("{:<id_width$}  {:<10}  {:>6}  {:>4}  {:<8}  이름", "ID", "버전", "설치 수", "평점", "업데이트", id_width = id_width);
+    for extension in &extensions {
+        println!(
+            "{:<id_width$}  {:<12}  {:>9}  {:>6}  {:<12}  {}",
+            extension.id.green(),
+            extension.version.as_deref().unwrap_or("-"),
+            extension.installs.map_or_else(|| "-".to_string(), format_count),
+            extension.rating.map_or_else(|| "-".to_string(), |rating| format!("{:.1}", rating)),
+            extension.updated.as_deref().map_or("-", |updated| updated.get(..10).unwrap_or(updated)),
+            extension.display_name.as_deref().unwrap_or_default(),
+            id_width = id_width
+        );
+    }
+
+    let first = (args.page - 1) as u64 * args.limit as u64 + 1;
+    let last = first + extensions.len() as u64 - 1;
+    match page.total {
+        Some(total) => println!("\n{}", format!("전체 {}개 중 {}-{}번째", total, first, last).blue()),
+        None => println!("\n{}", format!("{}-{}번째", first, last).blue()),
+    }
+    let has_next = page.total.map_or(extensions.len() as u32 == args.limit, |total| last < total);
+    if has_next {
+        println!("{}", format!("다음 페이지: --page {}", args.page + 1).blue());
+    }
+    Ok(())
+}
+
+/// 설치 수를 `1.2M`, `34.5K`처럼 줄여 씁니다.
+fn format_count(count: u64) -> String {
+    match count {
+        1_000_000.. => format!("{:.1}M", count as f64 / 1_000_000.0),
+        1_000.. => format!("{:.1}K", count as f64 / 1_000.0),
+        _ => count.to_string(),
+    }
+}
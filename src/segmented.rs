@@ -0,0 +1,130 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use anyhow::Result;
+use futures_util::StreamExt;
+use indicatif::ProgressBar;
+use reqwest::header::{HeaderMap, ACCEPT_RANGES};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+
+use crate::error::VsixError;
+use crate::http::HttpClient;
+use crate::status_file;
+use crate::vsix;
+use crate::DownloadedFile;
+
+/// 이 크기보다 작은 파일은 나눠 받지 않습니다.
+pub const MIN_SEGMENTED_SIZE: u64 = 8 * 1024 * 1024;
+
+/// 서버가 바이트 범위 요청을 지원한다고 알리는지 확인합니다.
+pub fn accepts_ranges(headers: &HeaderMap) -> bool {
+    headers.get(ACCEPT_RANGES)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("bytes"))
+}
+
+/// 파일을 `segments`개의 바이트 범위로 나눠 동시에 받아 한 파일로 합칩니다.
+/// 서버가 범위 요청에 206으로 답하지 않으면 None을 반환해 단일 연결 다운로드로 돌아가게 합니다.
+pub async fn download(
+    client: &HttpClient,
+    url: &str,
+    output_path: &Path,
+    total_size: u64,
+    segments: usize,
+    pb: &ProgressBar
+) -> Result<Option<DownloadedFile>> {
+    let io_error = |source| VsixError::Io { path: output_path.to_path_buf(), source };
+
+    // 각 구간이 자기 위치에 바로 쓸 수 있도록 전체 크기로 미리 만들어 둠
+    File::create(output_path)
+        .and_then(|file| file.set_len(total_size))
+        .map_err(io_error)?;
+
+    let fetches = split_ranges(total_size, segments)
+        .into_iter()
+        .map(|(start, end)| fetch_segment(client, url, output_path, start, end, pb));
+    let completed = futures_util::future::try_join_all(fetches).await?;
+
+    if !completed.into_iter().all(|ok| ok) {
+        pb.set_position(0);
+        return Ok(None);
+    }
+
+    // 합친 파일을 다시 읽어 시그니처를 확인하고 해시를 계산
+    let mut file = File::open(output_path).map_err(io_error)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    let mut head = Vec::new();
+
+    loop {
+        let read = file.read(&mut buffer).map_err(io_error)?;
+        if read == 0 {
+            break;
+        }
+        if head.len() < vsix::ZIP_MAGIC.len() {
+            head.extend_from_slice(&buffer[..read]);
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    if !head.starts_with(vsix::ZIP_MAGIC) {
+        return Err(VsixError::InvalidArchive {
+            url: url.to_string(),
+            content_type: String::new(),
+            message: vsix::server_message_excerpt(&head),
+        }.into());
+    }
+
+    Ok(Some(DownloadedFile {
+        size: total_size,
+        sha256: format!("{:x}", hasher.finalize()),
+        duration_ms: None,
+        origin: None,
+    }))
+}
+
+/// `[start, end]` 구간(양 끝 포함) 목록. 마지막 구간이 나머지를 가져갑니다.
+fn split_ranges(total_size: u64, segments: usize) -> Vec<(u64, u64)> {
+    let segments = (segments as u64).clamp(1, total_size.max(1));
+    let segment_size = total_size / segments;
+
+    (0..segments)
+        .map(|i| {
+            let start = i * segment_size;
+            let end = if i == segments - 1 { total_size - 1 } else { start + segment_size - 1 };
+            (start, end)
+        })
+        .collect()
+}
+
+/// 구간 하나를 받아 파일의 해당 위치에 씁니다. 서버가 범위를 지키지 않으면 false를 반환합니다.
+async fn fetch_segment(
+    client: &HttpClient,
+    url: &str,
+    output_path: &Path,
+    start: u64,
+    end: u64,
+    pb: &ProgressBar
+) -> Result<bool> {
+    let io_error = |source| VsixError::Io { path: output_path.to_path_buf(), source };
+
+    let response = client.get_range(url, start, end).await?;
+    if response.status != StatusCode::PARTIAL_CONTENT || response.content_length() != Some(end - start + 1) {
+        return Ok(false);
+    }
+
+    let mut file = OpenOptions::new().write(true).open(output_path).map_err(io_error)?;
+    file.seek(SeekFrom::Start(start)).map_err(io_error)?;
+
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| VsixError::from_request(e, url))?;
+        file.write_all(&chunk).map_err(io_error)?;
+        pb.inc(chunk.len() as u64);
+        status_file::tick();
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,239 @@
+use std::cmp::Ordering;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::http;
+use crate::version;
+
+const GITHUB_RELEASES_API: &str =
+    "https://api.github.com/repos/jukqaz/vsix-downloader-for-sync-setting/releases/latest";
+const CHECKSUMS_ASSET_NAMES: [&str; 2] = ["SHA256SUMS", "checksums.txt"];
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// GitHub 릴리스에서 최신 버전을 확인하고 현재 실행 파일을 교체합니다.
+pub async fn self_update(check_only: bool) -> Result<()> {
+    let client = http::configure(Client::builder().user_agent(concat!("vsix-downloader/", env!("CARGO_PKG_VERSION"))))
+        .build()
+        .with_context(|| "Failed to build HTTP client")?;
+
+    println!("{}", "최신 릴리스를 확인하는 중...".blue());
+
+    let release: Release = client.get(GITHUB_RELEASES_API)
+        .send()
+        .await
+        .with_context(|| format!("Failed to send request to {}", GITHUB_RELEASES_API))?
+        .error_for_status()
+        .with_context(|| "Failed to fetch the latest release")?
+        .json()
+        .await
+        .with_context(|| "Failed to parse release information")?;
+
+    let current_version = env!("CARGO_PKG_VERSION");
+    let latest_version = release.tag_name.trim_start_matches('v');
+
+    if version::compare_versions(latest_version, current_version) != Ordering::Greater {
+        println!("{}", format!("이미 최신 버전입니다: {}", current_version).green());
+        return Ok(());
+    }
+
+    println!("{}", format!("새 버전이 있습니다: {} -> {}", current_version, latest_version).yellow());
+
+    if check_only {
+        return Ok(());
+    }
+
+    let asset_name = platform_asset_name();
+    let asset = release.assets.iter()
+        .find(|a| a.name == asset_name)
+        .ok_or_else(|| anyhow!("현재 플랫폼용 릴리스 파일을 찾을 수 없습니다: {}", asset_name))?;
+
+    let expected_checksum = find_expected_checksum(&client, &release, &asset_name).await?;
+
+    println!("{} {}", "다운로드 시작:".blue(), asset.browser_download_url);
+
+    let binary = client.get(&asset.browser_download_url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to send request to {}", asset.browser_download_url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to download {}", asset.name))?
+        .bytes()
+        .await
+        .with_context(|| format!("Failed to read {}", asset.name))?;
+
+    let actual_checksum = format!("{:x}", Sha256::digest(&binary));
+    if !actual_checksum.eq_ignore_ascii_case(&expected_checksum) {
+        return Err(anyhow!(
+            "체크섬이 일치하지 않습니다: 예상 {}, 실제 {}",
+            expected_checksum, actual_checksum
+        ));
+    }
+
+    println!("{}", "체크섬 확인 완료".green());
+
+    let current_exe = env::current_exe()
+        .with_context(|| "Failed to locate the current executable")?;
+    replace_executable(&current_exe, &binary)?;
+
+    println!("{}", format!("{} 버전으로 업데이트되었습니다.", latest_version).green());
+
+    Ok(())
+}
+
+/// 릴리스 파일 이름 규칙: vsix-downloader-<os>-<arch>[.exe]
+fn platform_asset_name() -> String {
+    format!(
+        "vsix-downloader-{}-{}{}",
+        env::consts::OS,
+        env::consts::ARCH,
+        env::consts::EXE_SUFFIX
+    )
+}
+
+/// `<asset>.sha256` 파일 또는 SHA256SUMS 형식의 체크섬 목록에서 예상 해시를 찾습니다.
+async fn find_expected_checksum(client: &Client, release: &Release, asset_name: &str) -> Result<String> {
+    let sidecar_name = format!("{}.sha256", asset_name);
+
+    let checksum_asset = release.assets.iter()
+        .find(|a| a.name == sidecar_name)
+        .or_else(|| {
+            release.assets.iter()
+                .find(|a| CHECKSUMS_ASSET_NAMES.contains(&a.name.as_str()))
+        })
+        .ok_or_else(|| anyhow!("릴리스에 체크섬 파일이 없어 업데이트를 진행할 수 없습니다"))?;
+
+    let content = client.get(&checksum_asset.browser_download_url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to send request to {}", checksum_asset.browser_download_url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to download {}", checksum_asset.name))?
+        .text()
+        .await
+        .with_context(|| format!("Failed to read {}", checksum_asset.name))?;
+
+    parse_checksum(&content, asset_name, checksum_asset.name == sidecar_name)
+        .ok_or_else(|| anyhow!("{} 파일에서 {}의 체크섬을 찾을 수 없습니다", checksum_asset.name, asset_name))
+}
+
+/// 체크섬 파일 내용에서 `asset_name`의 해시를 찾습니다.
+/// 해시만 있는 줄은 그 자산 전용 사이드카 파일(`sidecar`)일 때만 인정합니다.
+fn parse_checksum(content: &str, asset_name: &str, sidecar: bool) -> Option<String> {
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(hash) = fields.next() else {
+            continue;
+        };
+
+        // 사이드카 파일은 해시만 있거나 "해시  파일이름" 형식, 공용 목록은 항상 "해시  파일이름" 형식
+        match fields.next() {
+            None if sidecar => return Some(hash.to_string()),
+            None => continue,
+            Some(name) if name.trim_start_matches('*') == asset_name => return Some(hash.to_string()),
+            Some(_) => continue,
+        }
+    }
+
+    None
+}
+
+/// 새 바이너리를 실행 파일 옆에 기록한 뒤 원자적으로 교체합니다.
+fn replace_executable(current_exe: &Path, binary: &[u8]) -> Result<()> {
+    let staged_path = with_suffix(current_exe, ".new");
+
+    fs::write(&staged_path, binary)
+        .with_context(|| format!("Failed to write {}", staged_path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged_path, fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to set permissions on {}", staged_path.display()))?;
+    }
+
+    // Windows는 실행 중인 파일을 덮어쓸 수 없으므로 먼저 이름을 바꿔 둠
+    let backup_path = cfg!(windows).then(|| with_suffix(current_exe, ".old"));
+    if let Some(backup_path) = &backup_path {
+        let _ = fs::remove_file(backup_path);
+        fs::rename(current_exe, backup_path)
+            .with_context(|| format!("Failed to move {} aside", current_exe.display()))?;
+    }
+
+    if let Err(e) = fs::rename(&staged_path, current_exe) {
+        // 교체에 실패하면 옮겨 둔 기존 실행 파일을 되돌려 실행 파일이 사라지지 않게 함
+        if let Some(backup_path) = &backup_path {
+            if let Err(restore) = fs::rename(backup_path, current_exe) {
+                eprintln!(
+                    "{} {}: {}",
+                    "기존 실행 파일을 되돌리지 못했습니다:".red(),
+                    backup_path.display(),
+                    restore
+                );
+            }
+        }
+        let _ = fs::remove_file(&staged_path);
+        return Err(e).with_context(|| format!("Failed to replace {}", current_exe.display()));
+    }
+
+    Ok(())
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+    const ASSET: &str = "vsix-downloader-linux-x86_64";
+
+    #[test]
+    fn sidecar_accepts_hash_only_line() {
+        assert_eq!(parse_checksum(&format!("{}\n", HASH), ASSET, true).as_deref(), Some(HASH));
+    }
+
+    #[test]
+    fn shared_list_rejects_hash_only_line() {
+        assert_eq!(parse_checksum(&format!("{}\n", HASH), ASSET, false), None);
+    }
+
+    #[test]
+    fn shared_list_matches_asset_name() {
+        let content = format!(
+            "{}  vsix-downloader-darwin-aarch64\n\n{} *{}\n",
+            "0".repeat(64),
+            HASH,
+            ASSET
+        );
+        assert_eq!(parse_checksum(&content, ASSET, false).as_deref(), Some(HASH));
+        assert_eq!(parse_checksum(&content, "vsix-downloader-windows-x86_64.exe", false), None);
+    }
+
+    #[test]
+    fn sidecar_with_other_name_does_not_match() {
+        let content = format!("{}  vsix-downloader-darwin-aarch64\n", HASH);
+        assert_eq!(parse_checksum(&content, ASSET, true), None);
+    }
+}
@@ -0,0 +1,178 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use tokio::process::Command;
+
+use crate::InstallServiceArgs;
+
+/// 지금 실행한 `daemon ... install-service` 명령의 daemon 옵션으로 서비스를 등록합니다.
+/// 상대 경로로 지정한 목록과 설정 파일이 그대로 읽히도록 현재 디렉토리에서 실행되게 합니다.
+pub async fn install(args: &InstallServiceArgs) -> Result<()> {
+    let exe = std::env::current_exe()
+        .with_context(|| "Failed to locate the current executable")?;
+    let working_dir = std::env::current_dir()
+        .with_context(|| "Failed to read the current directory")?;
+    let daemon_args = daemon_args(std::env::args_os().skip(1))?;
+
+    // 서비스 로그에는 진행률 표시바와 색 제어 문자가 필요 없음
+    let mut command = vec![exe.to_string_lossy().into_owned(), "--plain".to_string(), "daemon".to_string()];
+    command.extend(daemon_args);
+
+    if cfg!(windows) {
+        install_windows_task(args, &working_dir, &command).await
+    } else {
+        install_systemd_unit(args, &working_dir, &command).await
+    }
+}
+
+/// 명령줄에서 `daemon`과 `install-service` 사이의 인자 (`--plain`은 항상 붙이므로 제외)
+fn daemon_args(argv: impl Iterator<Item = OsString>) -> Result<Vec<String>> {
+    let argv: Vec<String> = argv
+        .map(|arg| arg.into_string().map_err(|arg| anyhow!("UTF-8이 아닌 인자는 서비스 명령에 넣을 수 없습니다: {:?}", arg)))
+        .collect::<Result<_>>()?;
+    let start = argv.iter().position(|arg| arg == "daemon")
+        .ok_or_else(|| anyhow!("명령줄에서 daemon 명령을 찾을 수 없습니다"))?;
+    let end = argv.iter().rposition(|arg| arg == "install-service")
+        .ok_or_else(|| anyhow!("명령줄에서 install-service 명령을 찾을 수 없습니다"))?;
+
+    Ok(argv[start + 1..end].iter()
+        .filter(|arg| *arg != "--plain")
+        .cloned()
+        .collect())
+}
+
+async fn install_systemd_unit(args: &InstallServiceArgs, working_dir: &Path, command: &[String]) -> Result<()> {
+    let unit = systemd_unit(args, working_dir, command);
+    if args.print {
+        print!("{}", unit);
+        return Ok(());
+    }
+
+    let unit_dir = if args.user {
+        let home = std::env::var_os("HOME")
+            .ok_or_else(|| anyhow!("HOME 환경 변수가 없어 사용자 유닛 디렉토리를 정할 수 없습니다"))?;
+        PathBuf::from(home).join(".config/systemd/user")
+    } else {
+        PathBuf::from("/etc/systemd/system")
+    };
+    std::fs::create_dir_all(&unit_dir)
+        .with_context(|| format!("Failed to create directory: {}", unit_dir.display()))?;
+    let unit_path = unit_dir.join(format!("{}.service", args.name));
+    std::fs::write(&unit_path, unit)
+        .with_context(|| format!("Failed to write systemd unit to {}", unit_path.display()))?;
+    println!("{} {}", "systemd 유닛 작성:".green(), unit_path.display());
+
+    let scope: &[&str] = if args.user { &["--user"] } else { &[] };
+    systemctl(scope, &["daemon-reload"]).await?;
+    systemctl(scope, &["enable", "--now", &format!("{}.service", args.name)]).await?;
+
+    println!(
+        "{}",
+        format!(
+            "서비스를 등록하고 시작했습니다. 로그: journalctl {}-u {} -f",
+            if args.user { "--user " } else { "" },
+            args.name
+        ).green()
+    );
+    Ok(())
+}
+
+fn systemd_unit(args: &InstallServiceArgs, working_dir: &Path, command: &[String]) -> String {
+    let exec_start = command.iter()
+        .map(|arg| systemd_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut unit = String::new();
+
+    unit.push_str("[Unit]\n");
+    unit.push_str("Description=VSIX mirror sync (vsix-downloader daemon)\n");
+    unit.push_str("Wants=network-online.target\n");
+    unit.push_str("After=network-online.target\n\n");
+
+    unit.push_str("[Service]\n");
+    unit.push_str("Type=simple\n");
+    unit.push_str(&format!("WorkingDirectory={}\n", systemd_quote(&working_dir.to_string_lossy())));
+    unit.push_str(&format!("ExecStart={}\n", exec_start));
+    if let Some(run_as) = &args.run_as {
+        unit.push_str(&format!("User={}\n", run_as));
+    }
+    unit.push_str("Restart=on-failure\n");
+    unit.push_str("RestartSec=30\n\n");
+
+    unit.push_str("[Install]\n");
+    unit.push_str(&format!("WantedBy={}\n", if args.user { "default.target" } else { "multi-user.target" }));
+    unit
+}
+
+/// systemd가 인자 하나로 읽도록 감쌉니다. `%`와 `$`는 지정자와 환경 변수 치환을 막기 위해 두 번 씁니다.
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%").replace('$', "$$");
+    if !escaped.is_empty() && !escaped.contains(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | ';')) {
+        return escaped;
+    }
+    format!("\"{}\"", escaped.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+async fn systemctl(scope: &[&str], args: &[&str]) -> Result<()> {
+    let status = Command::new("systemctl")
+        .args(scope)
+        .args(args)
+        .status()
+        .await
+        .with_context(|| "Failed to run systemctl")?;
+
+    if !status.success() {
+        return Err(anyhow!("systemctl {} 명령이 실패했습니다", scope.iter().chain(args).copied().collect::<Vec<_>>().join(" ")));
+    }
+    Ok(())
+}
+
+/// 별도 래퍼 없이 실행 파일을 Windows 서비스 제어 관리자에 맞출 수 없으므로,
+/// 부팅 시(`--user`면 로그온 시) 시작하는 예약 작업으로 등록합니다.
+async fn install_windows_task(args: &InstallServiceArgs, working_dir: &Path, command: &[String]) -> Result<()> {
+    let task_command = format!(
+        "cmd /c cd /d {} && {}",
+        windows_quote(&working_dir.to_string_lossy()),
+        command.iter().map(|arg| windows_quote(arg)).collect::<Vec<_>>().join(" ")
+    );
+
+    let mut schtasks = vec!["/Create", "/F", "/TN", &args.name, "/TR", &task_command];
+    if args.user {
+        schtasks.extend(["/SC", "ONLOGON"]);
+    } else {
+        schtasks.extend(["/SC", "ONSTART", "/RU", args.run_as.as_deref().unwrap_or("SYSTEM")]);
+    }
+
+    if args.print {
+        println!("schtasks {}", schtasks.iter().map(|arg| windows_quote(arg)).collect::<Vec<_>>().join(" "));
+        return Ok(());
+    }
+
+    run_schtasks(&schtasks).await?;
+    run_schtasks(&["/Run", "/TN", &args.name]).await?;
+
+    println!("{} {}", "예약 작업을 등록하고 시작했습니다:".green(), args.name);
+    Ok(())
+}
+
+fn windows_quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains(|c: char| c.is_whitespace() || c == '"') {
+        return arg.to_string();
+    }
+    format!("\"{}\"", arg.replace('"', "\\\""))
+}
+
+async fn run_schtasks(args: &[&str]) -> Result<()> {
+    let status = Command::new("schtasks")
+        .args(args)
+        .status()
+        .await
+        .with_context(|| "Failed to run schtasks")?;
+
+    if !status.success() {
+        return Err(anyhow!("schtasks {} 명령이 실패했습니다", args.join(" ")));
+    }
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use sha2::{Digest, Sha256};
+
+use crate::output::ResultsFormat;
+use crate::{load_downloads, sort_by_id, write_results};
+use crate::{DownloadInfo, DownloadStats, Extension, MergeResultsArgs, Results};
+
+/// 여러 CI 작업이 목록을 나눠 처리할 때 이 작업이 맡은 부분 (`--shard 2/5`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    /// 1부터 시작하는 샤드 번호
+    index: u64,
+    count: u64,
+}
+
+impl Shard {
+    /// ID(대소문자 무시)의 해시로 샤드를 정하므로 목록 순서나 다른 항목이 바뀌어도 같은 확장 프로그램은 같은 샤드에 속합니다.
+    fn contains(self, id: &str) -> bool {
+        let digest = Sha256::digest(id.to_ascii_lowercase().as_bytes());
+        let mut prefix = [0u8; 8];
+        prefix.copy_from_slice(&digest[..8]);
+        u64::from_be_bytes(prefix) % self.count == self.index - 1
+    }
+
+    /// 목록에서 이 샤드에 속한 확장 프로그램만 남깁니다.
+    pub fn select(self, extensions: Vec<Extension>) -> Vec<Extension> {
+        let total = extensions.len();
+        let selected: Vec<Extension> = extensions.into_iter()
+            .filter(|e| self.contains(&e.id))
+            .collect();
+
+        println!("{}", format!("샤드 {}: 확장 프로그램 {}개 중 {}개를 처리합니다.", self, total, selected.len()).blue());
+        selected
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (index, count) = value.split_once('/')
+            .ok_or_else(|| format!("샤드는 번호/개수 형식이어야 합니다 (예: 2/5): {}", value))?;
+        let index: u64 = index.trim().parse().map_err(|_| format!("샤드 번호가 올바르지 않습니다: {}", index))?;
+        let count: u64 = count.trim().parse().map_err(|_| format!("샤드 개수가 올바르지 않습니다: {}", count))?;
+
+        if count == 0 || index == 0 || index > count {
+            return Err(format!("샤드 번호는 1부터 {} 사이여야 합니다: {}", count, value));
+        }
+
+        Ok(Shard { index, count })
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// 샤드별 결과 파일을 하나로 합치고, 각 결과 파일 옆의 다운로드 정보 파일도 출력 파일 옆에 합칩니다.
+/// 같은 ID가 여러 결과에 있으면 나중에 지정한 결과가 우선합니다.
+pub fn merge_results(args: &MergeResultsArgs) -> Result<()> {
+    let format = args.results_format;
+    if format == ResultsFormat::Csv {
+        return Err(anyhow!("CSV 결과 파일은 합칠 수 없습니다. JSON이나 YAML 결과 파일을 사용하세요"));
+    }
+
+    let mut merged = Results {
+        run: None,
+        available: Vec::new(),
+        unavailable: Vec::new(),
+        removed_upstream: Vec::new(),
+        download_stats: None,
+        warnings: Vec::new(),
+    };
+    let mut downloads: Vec<DownloadInfo> = Vec::new();
+    let mut stats = Vec::new();
+
+    for path in &args.results {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let results: Results = format.deserialize(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        for extension in results.available {
+            merged.available.retain(|e| !e.id.eq_ignore_ascii_case(&extension.id));
+            merged.unavailable.retain(|e| !e.id.eq_ignore_ascii_case(&extension.id));
+            merged.available.push(extension);
+        }
+        for extension in results.unavailable {
+            merged.available.retain(|e| !e.id.eq_ignore_ascii_case(&extension.id));
+            merged.unavailable.retain(|e| !e.id.eq_ignore_ascii_case(&extension.id));
+            merged.unavailable.push(extension);
+        }
+        for id in results.removed_upstream {
+            if !merged.removed_upstream.iter().any(|existing| existing.eq_ignore_ascii_case(&id)) {
+                merged.removed_upstream.push(id);
+            }
+        }
+        for warning in results.warnings {
+            if !merged.warnings.contains(&warning) {
+                merged.warnings.push(warning);
+            }
+        }
+        // 실행 정보는 가장 먼저 지정한 결과의 것을 사용
+        merged.run = merged.run.or(results.run);
+        stats.extend(results.download_stats);
+
+        let downloads_path = format.downloads_path(path.parent().filter(|p| !p.as_os_str().is_empty()));
+        for download in load_downloads(&downloads_path, format)? {
+            downloads.retain(|d| !d.id.eq_ignore_ascii_case(&download.id));
+            downloads.push(download);
+        }
+
+        println!("{} {}", "합침:".green(), path.display());
+    }
+
+    merged.download_stats = merge_stats(stats);
+    merged.link_packs();
+    merged.sort();
+    merged.removed_upstream.sort_by_key(|id| id.to_ascii_lowercase());
+    merged.warnings.sort_by_key(|warning| warning.id.to_ascii_lowercase());
+
+    let output_dir = args.output.parent().filter(|p| !p.as_os_str().is_empty());
+    if let Some(dir) = output_dir {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    let downloads_path = format.downloads_path(output_dir);
+    sort_by_id(&mut downloads, |d| (&d.id, d.version.as_deref()));
+    write_downloads(&downloads, &downloads_path, format)?;
+    write_results(&merged, &args.output, &downloads_path, format)?;
+
+    println!(
+        "{}",
+        format!(
+            "결과 {}개를 합쳤습니다 (Open VSX {}개, Marketplace {}개): {}",
+            args.results.len(),
+            merged.available.len(),
+            merged.unavailable.len(),
+            args.output.display()
+        ).green()
+    );
+    Ok(())
+}
+
+fn write_downloads(downloads: &[DownloadInfo], path: &Path, format: ResultsFormat) -> Result<()> {
+    let serialized = format.serialize(&downloads)
+        .with_context(|| "Failed to serialize downloads")?;
+    std::fs::write(path, serialized)
+        .with_context(|| format!("Failed to write downloads to {}", path.display()))
+}
+
+/// 샤드들은 동시에 실행되므로 전체 시간은 가장 오래 걸린 샤드의 시간으로 봅니다.
+fn merge_stats(stats: Vec<DownloadStats>) -> Option<DownloadStats> {
+    let wall_time_ms = stats.iter().map(|s| s.wall_time_ms).max()?;
+    let total_bytes = stats.iter().map(|s| s.total_bytes).sum();
+
+    Some(DownloadStats {
+        downloaded: stats.iter().map(|s| s.downloaded).sum(),
+        total_bytes,
+        wall_time_ms,
+        average_bytes_per_second: crate::bytes_per_second(total_bytes, wall_time_ms),
+        slowest: stats.into_iter()
+            .filter_map(|s| s.slowest)
+            .min_by_key(|t| t.bytes_per_second),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_index_and_count() {
+        assert_eq!("2/5".parse::<Shard>(), Ok(Shard { index: 2, count: 5 }));
+        assert_eq!(" 1 / 1 ".parse::<Shard>(), Ok(Shard { index: 1, count: 1 }));
+        assert!("0/5".parse::<Shard>().is_err());
+        assert!("6/5".parse::<Shard>().is_err());
+        assert!("1/0".parse::<Shard>().is_err());
+        assert!("2".parse::<Shard>().is_err());
+    }
+
+    #[test]
+    fn every_id_belongs_to_exactly_one_shard() {
+        let shards: Vec<Shard> = (1..=4).map(|index| Shard { index, count: 4 }).collect();
+        for id in ["ms-python.python", "rust-lang.rust-analyzer", "golang.go", "redhat.java", "esbenp.prettier-vscode"] {
+            assert_eq!(shards.iter().filter(|shard| shard.contains(id)).count(), 1, "{}", id);
+        }
+    }
+
+    #[test]
+    fn ignores_id_case() {
+        let shard = Shard { index: 1, count: 3 };
+        assert_eq!(shard.contains("MS-Python.Python"), shard.contains("ms-python.python"));
+    }
+}
@@ -0,0 +1,73 @@
+/// `300MiB`, `1.5GB`, `1024` 같은 크기 문자열을 바이트 수로 변환합니다.
+/// 단위가 없으면 바이트로 간주하며, KB/MB/GB는 1000 단위, KiB/MiB/GiB는 1024 단위입니다.
+pub fn parse_size(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    let split_at = input.find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split_at);
+
+    let number: f64 = number.parse()
+        .map_err(|_| format!("유효하지 않은 크기: {}", input))?;
+
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => 1_000,
+        "kib" => 1 << 10,
+        "m" | "mb" => 1_000_000,
+        "mib" => 1 << 20,
+        "g" | "gb" => 1_000_000_000,
+        "gib" => 1 << 30,
+        other => return Err(format!("알 수 없는 크기 단위: {}", other)),
+    };
+
+    Ok((number * multiplier as f64) as u64)
+}
+
+/// 바이트 수를 `12.3 MiB` 같은 1024 단위 문자열로 나타냅니다.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+
+    format!("{:.1} {}", value, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decimal_and_binary_units() {
+        assert_eq!(parse_size("1024"), Ok(1024));
+        assert_eq!(parse_size("10KB"), Ok(10_000));
+        assert_eq!(parse_size("300MiB"), Ok(300 << 20));
+        assert_eq!(parse_size("1.5GB"), Ok(1_500_000_000));
+        assert_eq!(parse_size(" 2 gib "), Ok(2 << 30));
+    }
+
+    #[test]
+    fn rejects_unknown_units() {
+        assert!(parse_size("10XB").is_err());
+        assert!(parse_size("MB").is_err());
+        assert!(parse_size("").is_err());
+    }
+
+    #[test]
+    fn formats_with_binary_units() {
+        assert_eq!(format_size(542), "542 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(300 << 20), "300.0 MiB");
+    }
+}
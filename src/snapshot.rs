@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+
+use crate::installed;
+use crate::run_meta::RunMetadata;
+use crate::{sort_by_id, Extension, Extensions, SnapshotArgs};
+
+const SNAPSHOT_PREFIX: &str = "extensions-";
+const SNAPSHOT_SUFFIX: &str = ".yml";
+
+/// 스냅샷을 한 번 찍거나, `--interval`이 있으면 주기적으로 계속 찍습니다.
+pub async fn run(args: &SnapshotArgs) -> Result<()> {
+    loop {
+        let path = take_snapshot(&args.code_bin, &args.dir).await?;
+        println!("{} {}", "스냅샷을 저장했습니다:".green(), path.display());
+
+        let removed = prune_snapshots(&args.dir, args.keep)?;
+        if removed > 0 {
+            println!("{}", format!("오래된 스냅샷 {}개를 삭제했습니다.", removed).yellow());
+        }
+
+        let Some(interval) = args.interval else {
+            return Ok(());
+        };
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// 설치된 확장 프로그램을 버전이 고정된 목록 형식으로 `<dir>/extensions-<시각>.yml`에 저장합니다.
+async fn take_snapshot(code_bin: &str, dir: &Path) -> Result<PathBuf> {
+    let mut extensions: Vec<Extension> = installed::list_installed(code_bin).await?
+        .into_iter()
+        .map(|e| Extension {
+            id: e.id,
+            uuid: None,
+            version: Some(e.version),
+            source: None,
+            source_file: None,
+        })
+        .collect();
+    sort_by_id(&mut extensions, |e| (&e.id, e.version.as_deref()));
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create snapshot directory: {}", dir.display()))?;
+
+    let path = dir.join(format!(
+        "{}{}{}",
+        SNAPSHOT_PREFIX,
+        Utc::now().format("%Y%m%dT%H%M%SZ"),
+        SNAPSHOT_SUFFIX
+    ));
+
+    let run = RunMetadata::new(None);
+    let serialized = serde_yaml::to_string(&Extensions { run: Some(run), enabled: Some(extensions) })
+        .with_context(|| "Failed to serialize snapshot")?;
+    fs::write(&path, serialized)
+        .with_context(|| format!("Failed to write snapshot to {}", path.display()))?;
+
+    Ok(path)
+}
+
+/// 최근 `keep`개만 남기고 오래된 스냅샷을 삭제합니다. `keep`이 0이면 모두 보관합니다.
+fn prune_snapshots(dir: &Path, keep: usize) -> Result<usize> {
+    if keep == 0 {
+        return Ok(0);
+    }
+
+    let mut snapshots: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read snapshot directory: {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(SNAPSHOT_PREFIX) && n.ends_with(SNAPSHOT_SUFFIX))
+        })
+        .collect();
+
+    // 파일 이름의 시각이 고정 폭이므로 이름순 정렬이 시간순 정렬
+    snapshots.sort();
+
+    let excess = snapshots.len().saturating_sub(keep);
+    for path in &snapshots[..excess] {
+        fs::remove_file(path)
+            .with_context(|| format!("Failed to remove old snapshot: {}", path.display()))?;
+    }
+
+    Ok(excess)
+}
@@ -0,0 +1,71 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::http::{HttpClient, HttpMode};
+use crate::installed;
+use crate::version;
+use crate::vsix;
+use crate::{load_extension_lists, ConflictPolicy, StatusArgs};
+
+/// 목록의 확장 프로그램마다 로컬 설치 여부, 설치 버전과 목록 버전, 다운로드 디렉토리의 VSIX 유무를 출력합니다.
+pub async fn run(args: &StatusArgs) -> Result<()> {
+    let client = HttpClient::new(HttpMode::Live)?;
+    let desired = load_extension_lists(&client, &args.file, args.file_header.as_slice(), ConflictPolicy::Last, args.allow_unknown_fields).await?;
+    let installed = installed::list_installed(&args.code_bin).await?;
+    let packages = vsix::scan_directory(&args.output_dir)?;
+
+    let id_width = desired.iter()
+        .map(|e| e.id.len())
+        .chain(installed.iter().map(|i| i.id.len()))
+        .max()
+        .unwrap_or(0);
+
+    // 한글 제목은 글자당 두 칸을 차지하므로 그만큼 적게 채움
+    println!(
+        "{:<id_width$}  {:<12}  {:<12}  VSIX",
+        "ID", "설치 버전", "목록 버전",
+        id_width = id_width
+    );
+
+    for extension in &desired {
+        let current = installed.iter().find(|i| i.id.eq_ignore_ascii_case(&extension.id));
+        let wanted = extension.version.as_deref().unwrap_or("latest");
+
+        // 고정 버전이 없으면 디렉토리에 있는 가장 높은 버전을 표시
+        let package = packages.iter()
+            .filter(|p| p.id.eq_ignore_ascii_case(&extension.id))
+            .filter(|p| extension.version.as_ref().is_none_or(|v| &p.version == v))
+            .max_by(|a, b| version::compare_versions(&a.version, &b.version));
+
+        let installed_column = format!("{:<16}", current.map_or("-", |c| c.version.as_str()));
+        let installed_column = match (current, &extension.version) {
+            (None, _) => installed_column.red(),
+            (Some(c), Some(v)) if &c.version != v => installed_column.yellow(),
+            _ => installed_column.green(),
+        };
+
+        let vsix_column = match package {
+            Some(p) => p.version.green(),
+            None => "-".red(),
+        };
+
+        println!(
+            "{:<id_width$}  {}  {:<16}  {}",
+            extension.id, installed_column, wanted, vsix_column,
+            id_width = id_width
+        );
+    }
+
+    let extras: Vec<_> = installed.iter()
+        .filter(|i| !desired.iter().any(|d| d.id.eq_ignore_ascii_case(&i.id)))
+        .collect();
+
+    if !extras.is_empty() {
+        println!("\n{}", "목록에 없는 설치된 확장 프로그램:".yellow());
+        for extra in extras {
+            println!("  {}@{}", extra.id, extra.version);
+        }
+    }
+
+    Ok(())
+}
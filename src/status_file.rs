@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use colored::Colorize;
+use indicatif::ProgressBar;
+use serde::Serialize;
+
+use crate::output;
+use crate::resume::{WorkItem, WorkStatus};
+
+/// 상태 파일을 다시 쓰는 최소 간격
+const WRITE_INTERVAL: Duration = Duration::from_secs(2);
+
+static LIVE: Mutex<Option<LiveStatus>> = Mutex::new(None);
+
+/// `--status-file`로 지정한 파일에 대시보드가 읽을 진행 상황을 기록하는 중인 실행
+struct LiveStatus {
+    path: PathBuf,
+    run_id: String,
+    counts: Counts,
+    in_flight: Vec<(u64, String, ProgressBar)>,
+    next_download: u64,
+    last_write: Option<Instant>,
+}
+
+/// 작업 상태별 확장 프로그램 수
+#[derive(Debug, Default, Clone, Serialize)]
+struct Counts {
+    total: usize,
+    /// 확인을 기다리는 항목
+    queued: usize,
+    checking: usize,
+    /// 확인이 끝나 Marketplace 다운로드를 기다리는 항목
+    awaiting_download: usize,
+    downloading: usize,
+    done: usize,
+    failed: usize,
+}
+
+#[derive(Serialize)]
+struct Snapshot<'a> {
+    run_id: &'a str,
+    /// running, finished, failed
+    state: &'a str,
+    updated: String,
+    #[serde(flatten)]
+    counts: &'a Counts,
+    in_flight: Vec<InFlight<'a>>,
+}
+
+#[derive(Serialize)]
+struct InFlight<'a> {
+    file: &'a str,
+    bytes: u64,
+    /// Content-Length를 모르면 없음
+    total_bytes: Option<u64>,
+    percent: Option<f64>,
+}
+
+/// 진행 중인 다운로드를 상태 파일의 `in_flight`에서 빼는 값 (다운로드가 끝나거나 실패하면 버림)
+pub struct DownloadGuard(Option<u64>);
+
+impl Drop for DownloadGuard {
+    fn drop(&mut self) {
+        let Some(key) = self.0 else {
+            return;
+        };
+        with_live(|live| live.in_flight.retain(|(k, _, _)| *k != key));
+    }
+}
+
+/// 실행을 시작할 때 상태 파일 기록을 켭니다. `path`가 없으면 아무것도 기록하지 않습니다.
+pub fn start(path: Option<PathBuf>, run_id: &str) {
+    let mut live = LIVE.lock().expect("live status lock poisoned");
+    *live = path.map(|path| LiveStatus {
+        path,
+        run_id: run_id.to_string(),
+        counts: Counts::default(),
+        in_flight: Vec::new(),
+        next_download: 0,
+        last_write: None,
+    });
+    if let Some(live) = live.as_mut() {
+        live.write("running");
+    }
+}
+
+/// 실행이 끝나면 마지막 상태를 기록하고 기록을 끕니다.
+pub fn finish(succeeded: bool) {
+    let mut live = LIVE.lock().expect("live status lock poisoned");
+    if let Some(mut finished) = live.take() {
+        finished.in_flight.clear();
+        finished.write(if succeeded { "finished" } else { "failed" });
+    }
+}
+
+/// 작업 상태가 바뀔 때마다 상태별 수를 다시 셉니다.
+pub fn record_items(items: &[WorkItem]) {
+    with_live(|live| {
+        let mut counts = Counts { total: items.len(), ..Counts::default() };
+        for item in items {
+            match item.status {
+                WorkStatus::Pending => counts.queued += 1,
+                WorkStatus::Checking => counts.checking += 1,
+                WorkStatus::Checked => counts.awaiting_download += 1,
+                WorkStatus::Downloading => counts.downloading += 1,
+                WorkStatus::Done => counts.done += 1,
+                WorkStatus::Failed => counts.failed += 1,
+            }
+        }
+        live.counts = counts;
+    });
+}
+
+/// 진행률 표시바의 위치로 다운로드 진행률을 기록합니다.
+pub fn track_download(file: &str, pb: &ProgressBar) -> DownloadGuard {
+    let key = with_live(|live| {
+        let key = live.next_download;
+        live.next_download += 1;
+        live.in_flight.push((key, file.to_string(), pb.clone()));
+        key
+    });
+    DownloadGuard(key)
+}
+
+/// 마지막 기록 뒤 일정 시간이 지났으면 상태 파일을 다시 씁니다. 다운로드 중에 자주 불러도 됩니다.
+pub fn tick() {
+    with_live(|_| {});
+}
+
+/// 상태 파일을 기록 중이면 상태를 바꾸고, 마지막 기록 뒤 일정 시간이 지났으면 파일을 다시 씁니다.
+fn with_live<T>(change: impl FnOnce(&mut LiveStatus) -> T) -> Option<T> {
+    let mut live = LIVE.lock().expect("live status lock poisoned");
+    let live = live.as_mut()?;
+    let value = change(live);
+
+    if live.last_write.is_none_or(|last| last.elapsed() >= WRITE_INTERVAL) {
+        live.write("running");
+    }
+    Some(value)
+}
+
+impl LiveStatus {
+    /// 대시보드가 쓰는 도중의 파일을 읽지 않도록 임시 파일에 쓴 뒤 바꿔치기합니다.
+    /// 기록에 실패해도 동기화는 계속합니다.
+    fn write(&mut self, state: &str) {
+        let in_flight = self.in_flight.iter()
+            .map(|(_, file, pb)| {
+                let bytes = pb.position();
+                let total_bytes = pb.length().filter(|total| *total > 0);
+                InFlight {
+                    file,
+                    bytes,
+                    total_bytes,
+                    percent: total_bytes.map(|total| (bytes as f64 * 1000.0 / total as f64).round() / 10.0),
+                }
+            })
+            .collect();
+        let snapshot = Snapshot {
+            run_id: &self.run_id,
+            state,
+            updated: Utc::now().to_rfc3339(),
+            counts: &self.counts,
+            in_flight,
+        };
+
+        let written = serde_json::to_vec_pretty(&snapshot)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| output::write_atomically(&self.path, &json));
+        if let Err(e) = written {
+            println!("{} 상태 파일을 기록하지 못했습니다: {:#}", "경고:".yellow(), e);
+        }
+        self.last_write = Some(Instant::now());
+    }
+}
@@ -0,0 +1,106 @@
+use anyhow::{Context, Result};
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::error::VsixError;
+use crate::http::HttpClient;
+use crate::marketplace;
+
+const OPEN_VSX_SEARCH_URL: &str = "https://open-vsx.org/api/-/search";
+
+/// 레지스트리마다 검색 결과에서 살펴볼 후보 수
+const SEARCH_SIZE: u32 = 20;
+
+/// 출력할 제안 수
+const MAX_SUGGESTIONS: usize = 3;
+
+/// 이 거리(편집 횟수)보다 먼 후보는 오타로 보지 않음
+const MAX_DISTANCE: usize = 4;
+
+#[derive(Debug, Deserialize)]
+struct OpenVsxSearchResponse {
+    #[serde(default)]
+    extensions: Vec<OpenVsxSearchEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenVsxSearchEntry {
+    namespace: String,
+    name: String,
+}
+
+/// 두 레지스트리의 검색 API에서 `extension_id`와 철자가 가까운 ID를 찾습니다.
+/// 검색에 실패하면 제안 없이 빈 목록을 반환합니다.
+pub async fn similar_ids(client: &HttpClient, extension_id: &str) -> Vec<String> {
+    // 게시자 이름 오타가 흔하므로 확장 프로그램 이름으로 검색
+    let name = extension_id.split_once('.').map_or(extension_id, |(_, name)| name);
+
+    let mut candidates: Vec<String> = Vec::new();
+    if let Ok(extensions) = marketplace::search_extensions(client, name, SEARCH_SIZE).await {
+        candidates.extend(extensions.iter().map(|e| e.id()));
+    }
+    if let Ok(ids) = search_open_vsx(client, name).await {
+        candidates.extend(ids);
+    }
+
+    rank_candidates(extension_id, candidates)
+}
+
+async fn search_open_vsx(client: &HttpClient, text: &str) -> Result<Vec<String>> {
+    let url = Url::parse_with_params(OPEN_VSX_SEARCH_URL, &[("query", text), ("size", &SEARCH_SIZE.to_string())])
+        .with_context(|| format!("Failed to build Open VSX search URL for {}", text))?
+        .to_string();
+
+    let response = client.get(&url).await?;
+    if !response.status.is_success() {
+        return Err(VsixError::from_status(response.status, &url).into());
+    }
+
+    let response: OpenVsxSearchResponse = response.json().await
+        .with_context(|| format!("Failed to parse Open VSX search response for {}", text))?;
+
+    Ok(response.extensions.into_iter()
+        .map(|e| format!("{}.{}", e.namespace, e.name))
+        .collect())
+}
+
+/// 대소문자를 무시한 편집 거리가 가까운 순으로 중복 없이 고릅니다.
+fn rank_candidates(extension_id: &str, candidates: Vec<String>) -> Vec<String> {
+    let target = extension_id.to_ascii_lowercase();
+
+    let mut ranked: Vec<(usize, String)> = Vec::new();
+    for candidate in candidates {
+        let lower = candidate.to_ascii_lowercase();
+        if lower == target || ranked.iter().any(|(_, c)| c.eq_ignore_ascii_case(&candidate)) {
+            continue;
+        }
+
+        let distance = edit_distance(&target, &lower);
+        if distance <= MAX_DISTANCE {
+            ranked.push((distance, candidate));
+        }
+    }
+
+    ranked.sort_by_key(|(distance, _)| *distance);
+    ranked.into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// 레벤슈타인 거리
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
@@ -0,0 +1,71 @@
+use std::sync::{Mutex, OnceLock};
+
+use indicatif::ProgressBar;
+use tokio::sync::Semaphore;
+
+use crate::plain;
+use crate::progress_bars;
+
+/// 받은 VSIX의 압축 확인, 매니페스트 해석, 해시 계산을 다운로드와 따로 실행하는 단계.
+/// 파일을 읽는 작업이 비동기 실행기를 막아 다른 다운로드가 멈추지 않도록 블로킹 스레드에서 실행하고,
+/// `--verify-jobs`개까지만 동시에 검사합니다.
+struct Stage {
+    permits: Semaphore,
+    /// 검사 중이거나 기다리는 파일이 있는 동안만 표시하는 진행률 표시바
+    bar: Mutex<Option<ProgressBar>>,
+}
+
+static STAGE: OnceLock<Stage> = OnceLock::new();
+
+/// `--verify-jobs` 기본값 (CPU 수)
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism().map_or(4, |n| n.get())
+}
+
+/// 검증 단계에서 `task`를 실행합니다. 동시 검사 수는 처음 호출할 때의 `jobs`로 정해집니다.
+pub async fn run<T: Send + 'static>(jobs: usize, id: &str, task: impl FnOnce() -> T + Send + 'static) -> T {
+    let stage = STAGE.get_or_init(|| Stage {
+        permits: Semaphore::new(jobs.max(1)),
+        bar: Mutex::new(None),
+    });
+    stage.queued();
+
+    let result = {
+        let _permit = stage.permits.acquire().await.expect("validation stage is never closed");
+        stage.started(id);
+        tokio::task::spawn_blocking(task).await.expect("validation task panicked")
+    };
+
+    stage.finished();
+    result
+}
+
+impl Stage {
+    fn queued(&self) {
+        let mut bar = self.bar.lock().expect("validation progress lock poisoned");
+        let bar = bar.get_or_insert_with(|| {
+            let bar = progress_bars().add(ProgressBar::new(0));
+            bar.set_style(plain::progress_style("[검증] {bar:40.green/white} {pos:>4}/{len:4} {msg}"));
+            bar
+        });
+        bar.inc_length(1);
+    }
+
+    fn started(&self, id: &str) {
+        if let Some(bar) = self.bar.lock().expect("validation progress lock poisoned").as_ref() {
+            bar.set_message(id.to_string());
+        }
+    }
+
+    /// 기다리는 검사가 없으면 표시바를 지워 다음 검사 묶음이 새 표시바로 시작하게 합니다.
+    fn finished(&self) {
+        let mut bar = self.bar.lock().expect("validation progress lock poisoned");
+        if let Some(current) = bar.as_ref() {
+            current.inc(1);
+            if current.length().is_some_and(|length| current.position() >= length) {
+                current.finish_and_clear();
+                *bar = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::cmp::Ordering;
+
+/// `1.2.3`, `v1.10.0`, `1.2.3-pre` 같은 버전 문자열을 숫자 단위로 비교합니다.
+/// 숫자가 아닌 부분(프리릴리스 표기 등)부터는 비교에서 제외합니다.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    numeric_parts(a).cmp(&numeric_parts(b))
+}
+
+fn numeric_parts(version: &str) -> Vec<u64> {
+    version.trim_start_matches('v')
+        .split(['.', '-'])
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+/// `engines.vscode` 범위(`^1.78.0`, `>=1.80.0`, `1.82.x`)에서 최소 요구 버전을 꺼냅니다.
+/// 제한이 없는 범위(`*`)는 None을 반환합니다.
+pub fn minimum_engine_version(range: &str) -> Option<String> {
+    let lower = range.split_whitespace().next()?;
+    let lower = lower.trim_start_matches(['^', '~', '>', '=']);
+
+    if lower.is_empty() || lower == "*" {
+        return None;
+    }
+
+    let parts: Vec<&str> = lower.split('.')
+        .map(|part| if part.eq_ignore_ascii_case("x") || part == "*" { "0" } else { part })
+        .collect();
+
+    Some(parts.join("."))
+}
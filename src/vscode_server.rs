@@ -0,0 +1,225 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+
+use crate::version;
+use crate::{Extension, Extensions};
+
+/// 확장 프로그램 디렉토리의 설치 목록 파일
+const EXTENSIONS_MANIFEST: &str = "extensions.json";
+
+/// 제거됐지만 아직 디렉토리가 남아 있는 확장 프로그램 목록 (디렉토리 이름 -> true)
+const OBSOLETE_FILE: &str = ".obsolete";
+
+/// 압축 파일 안에서 확장 프로그램 디렉토리를 찾을 때 볼 최대 깊이
+const MAX_ARCHIVE_DEPTH: usize = 6;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestEntry {
+    identifier: ManifestIdentifier,
+    version: String,
+    #[serde(default)]
+    relative_location: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestIdentifier {
+    id: String,
+    #[serde(default)]
+    uuid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageManifest {
+    publisher: String,
+    name: String,
+    version: String,
+}
+
+/// 목록 파일 대신 VSCode 원격 서버(WSL, Remote-SSH)의 확장 프로그램 디렉토리가 지정됐으면 설치된 확장 프로그램을 읽습니다.
+/// `~/.vscode-server/extensions` 디렉토리, 그 상위 `~/.vscode-server` 디렉토리, 또는 이를 묶은 `.tar`/`.tar.gz`/`.tgz` 파일을 받습니다.
+/// 설치된 버전으로 고정된 목록을 반환하며, 해당하지 않는 경로면 None입니다.
+pub fn read_list(source: &Path) -> Result<Option<Extensions>> {
+    let extensions = if source.is_dir() {
+        read_directory(&extensions_directory(source))?
+    } else if is_tarball(source) {
+        read_tarball(source)?
+    } else {
+        return Ok(None);
+    };
+
+    if extensions.is_empty() {
+        return Err(anyhow!("확장 프로그램 디렉토리에서 설치된 확장 프로그램을 찾을 수 없습니다: {}", source.display()));
+    }
+
+    Ok(Some(Extensions { run: None, enabled: Some(extensions) }))
+}
+
+fn is_tarball(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    name.ends_with(".tar") || name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// `~/.vscode-server`처럼 `extensions` 하위 디렉토리가 있으면 그 디렉토리
+fn extensions_directory(dir: &Path) -> PathBuf {
+    let nested = dir.join("extensions");
+    if !dir.join(EXTENSIONS_MANIFEST).exists() && nested.is_dir() {
+        nested
+    } else {
+        dir.to_path_buf()
+    }
+}
+
+fn read_directory(dir: &Path) -> Result<Vec<Extension>> {
+    let obsolete = fs::read_to_string(dir.join(OBSOLETE_FILE)).ok();
+
+    let manifest_path = dir.join(EXTENSIONS_MANIFEST);
+    if manifest_path.exists() {
+        let content = fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+        return parse_manifest(&content, obsolete.as_deref())
+            .with_context(|| format!("Failed to parse {}", manifest_path.display()));
+    }
+
+    // 오래된 서버는 extensions.json 없이 디렉토리마다 package.json만 있음
+    let mut packages = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))? {
+        let path = entry.with_context(|| format!("Failed to read directory: {}", dir.display()))?.path();
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        if let Ok(content) = fs::read_to_string(path.join("package.json")) {
+            packages.push((name, content));
+        }
+    }
+
+    Ok(parse_packages(&packages, obsolete.as_deref()))
+}
+
+fn read_tarball(path: &Path) -> Result<Vec<Extension>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let reader: Box<dyn Read> = if path.to_string_lossy().to_lowercase().ends_with(".tar") {
+        Box::new(file)
+    } else {
+        Box::new(GzDecoder::new(file))
+    };
+    let mut archive = tar::Archive::new(reader);
+
+    // 압축 파일 안의 경로 -> 내용 (extensions.json, .obsolete, 확장 프로그램의 package.json)
+    let mut files: BTreeMap<PathBuf, String> = BTreeMap::new();
+    for entry in archive.entries().with_context(|| format!("Failed to read archive: {}", path.display()))? {
+        let mut entry = entry.with_context(|| format!("Failed to read archive: {}", path.display()))?;
+        let entry_path = entry.path()
+            .with_context(|| format!("Failed to read archive: {}", path.display()))?
+            .into_owned();
+
+        let name = entry_path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let wanted = matches!(name.as_str(), EXTENSIONS_MANIFEST | OBSOLETE_FILE | "package.json");
+        if !wanted || entry_path.components().count() > MAX_ARCHIVE_DEPTH {
+            continue;
+        }
+
+        let mut content = String::new();
+        if entry.read_to_string(&mut content).is_ok() {
+            files.insert(entry_path, content);
+        }
+    }
+
+    // 가장 바깥의 extensions.json이 있는 디렉토리를 확장 프로그램 디렉토리로 봄
+    let manifest = files.iter()
+        .filter(|(p, _)| p.file_name().is_some_and(|n| n == EXTENSIONS_MANIFEST))
+        .min_by_key(|(p, _)| p.components().count());
+    if let Some((manifest_path, content)) = manifest {
+        let root = manifest_path.parent().unwrap_or(Path::new(""));
+        let obsolete = files.get(&root.join(OBSOLETE_FILE)).map(String::as_str);
+        return parse_manifest(content, obsolete)
+            .with_context(|| format!("Failed to parse {} in {}", manifest_path.display(), path.display()));
+    }
+
+    // extensions.json이 없으면 가장 얕은 package.json들이 확장 프로그램 디렉토리마다 하나씩 있는 것으로 봄
+    let depth = files.keys()
+        .filter(|p| p.file_name().is_some_and(|n| n == "package.json"))
+        .map(|p| p.components().count())
+        .min();
+    let Some(depth) = depth else {
+        return Ok(Vec::new());
+    };
+
+    let root = files.keys()
+        .find(|p| p.components().count() == depth)
+        .and_then(|p| p.parent()?.parent())
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let obsolete = files.get(&root.join(OBSOLETE_FILE)).cloned();
+
+    let packages: Vec<(String, String)> = files.iter()
+        .filter(|(p, _)| p.file_name().is_some_and(|n| n == "package.json") && p.components().count() == depth)
+        .filter_map(|(p, content)| {
+            let name = p.parent()?.file_name()?.to_string_lossy().into_owned();
+            Some((name, content.clone()))
+        })
+        .collect();
+
+    Ok(parse_packages(&packages, obsolete.as_deref()))
+}
+
+fn parse_manifest(content: &str, obsolete: Option<&str>) -> Result<Vec<Extension>> {
+    let entries: Vec<ManifestEntry> = serde_json::from_str(content)?;
+    let obsolete = obsolete_directories(obsolete);
+
+    Ok(entries.into_iter()
+        .filter(|entry| entry.relative_location.as_ref().is_none_or(|dir| !obsolete.contains(dir)))
+        .map(|entry| Extension {
+            id: entry.identifier.id,
+            uuid: entry.identifier.uuid,
+            version: Some(entry.version),
+            source: None,
+            source_file: None,
+        })
+        .collect())
+}
+
+/// (디렉토리 이름, package.json 내용) 목록에서 확장 프로그램을 읽습니다.
+/// 업데이트 후 정리되지 않아 같은 ID가 여러 버전 남아 있으면 가장 높은 버전을 씁니다.
+fn parse_packages(packages: &[(String, String)], obsolete: Option<&str>) -> Vec<Extension> {
+    let obsolete = obsolete_directories(obsolete);
+    let mut extensions: Vec<Extension> = Vec::new();
+
+    let manifests = packages.iter()
+        .filter(|(dir, _)| !obsolete.contains(dir))
+        .filter_map(|(_, content)| serde_json::from_str::<PackageManifest>(content).ok());
+    for package in manifests {
+        let id = format!("{}.{}", package.publisher, package.name);
+        match extensions.iter_mut().find(|e| e.id.eq_ignore_ascii_case(&id)) {
+            Some(existing) => {
+                let current = existing.version.as_deref().unwrap_or_default();
+                if version::compare_versions(&package.version, current) == Ordering::Greater {
+                    existing.version = Some(package.version);
+                }
+            },
+            None => extensions.push(Extension {
+                id,
+                uuid: None,
+                version: Some(package.version),
+                source: None,
+                source_file: None,
+            }),
+        }
+    }
+
+    extensions.sort_by_key(|e| e.id.to_lowercase());
+    extensions
+}
+
+fn obsolete_directories(content: Option<&str>) -> Vec<String> {
+    content
+        .and_then(|c| serde_json::from_str::<BTreeMap<String, bool>>(c).ok())
+        .map(|map| map.into_iter().filter(|(_, removed)| *removed).map(|(dir, _)| dir).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,305 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+
+use crate::filename;
+
+/// VSIX(ZIP) 파일의 로컬 파일 헤더 시그니처
+pub const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// 오류 메시지에 담을 서버 응답 본문의 최대 길이
+const MAX_SERVER_MESSAGE_LEN: usize = 300;
+
+/// VSIX 대신 오류 페이지나 API 오류를 돌려줄 때 쓰이는 Content-Type인지 확인합니다.
+/// 게시자와 CDN에 따라 octet-stream, zip, vsix 등 다양한 값이 오므로 텍스트 계열만 거부합니다.
+pub fn is_error_content_type(content_type: &str) -> bool {
+    let content_type = content_type.to_ascii_lowercase();
+    content_type.starts_with("text/")
+        || content_type.contains("html")
+        || content_type.contains("json")
+        || content_type.contains("xml")
+}
+
+/// 잘못된 응답 본문에서 사람이 읽을 수 있는 서버 메시지를 추출합니다.
+/// HTML 태그와 연속 공백을 걷어내고 적당한 길이로 자릅니다.
+pub fn server_message_excerpt(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body);
+
+    let mut stripped = String::new();
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => {
+                in_tag = false;
+                stripped.push(' ');
+            },
+            c if !in_tag => stripped.push(c),
+            _ => {},
+        }
+    }
+
+    let collapsed = stripped.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() > MAX_SERVER_MESSAGE_LEN {
+        let truncated: String = collapsed.chars().take(MAX_SERVER_MESSAGE_LEN).collect();
+        format!("{}...", truncated)
+    } else {
+        collapsed
+    }
+}
+
+/// VSIX 안의 텍스트 파일 하나를 읽습니다.
+pub fn read_text_entry(path: &Path, entry_name: &str) -> Result<String> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = ZipArchive::new(file)
+        .with_context(|| format!("Failed to read VSIX archive: {}", path.display()))?;
+
+    let mut content = String::new();
+    archive.by_name(entry_name)
+        .with_context(|| format!("{} not found in {}", entry_name, path.display()))?
+        .read_to_string(&mut content)
+        .with_context(|| format!("Failed to read {} in {}", entry_name, path.display()))?;
+
+    Ok(content)
+}
+
+/// VSIX 안에서 확장 프로그램 매니페스트가 있는 경로
+pub const PACKAGE_JSON_PATH: &str = "extension/package.json";
+
+/// 다운로드 디렉토리에 있는 VSIX 파일과 매니페스트의 ID, 버전
+#[derive(Debug, Clone)]
+pub struct LocalVsix {
+    pub id: String,
+    pub version: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageManifest {
+    publisher: String,
+    name: String,
+    version: String,
+}
+
+/// VSIX 파일의 `extension/package.json`에서 `publisher.name` ID와 버전을 읽습니다.
+pub fn read_manifest(path: &Path) -> Result<LocalVsix> {
+    let content = read_text_entry(path, PACKAGE_JSON_PATH)?;
+    let manifest: PackageManifest = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {} in {}", PACKAGE_JSON_PATH, path.display()))?;
+
+    Ok(LocalVsix {
+        id: format!("{}.{}", manifest.publisher, manifest.name),
+        version: manifest.version,
+        path: path.to_path_buf(),
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct EngineManifest {
+    #[serde(default)]
+    engines: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackManifest {
+    #[serde(default)]
+    extension_pack: Vec<String>,
+}
+
+/// VSIX 파일의 `extension/package.json`에서 `engines.vscode` 범위를 읽습니다.
+pub fn read_engine(path: &Path) -> Result<Option<String>> {
+    let content = read_text_entry(path, PACKAGE_JSON_PATH)?;
+    let manifest: EngineManifest = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {} in {}", PACKAGE_JSON_PATH, path.display()))?;
+
+    Ok(manifest.engines.get("vscode").cloned())
+}
+
+/// VSIX 파일의 `extension/package.json`에서 확장 팩 구성(`extensionPack`)을 읽습니다. 확장 팩이 아니면 빈 목록입니다.
+pub fn read_extension_pack(path: &Path) -> Result<Vec<String>> {
+    let content = read_text_entry(path, PACKAGE_JSON_PATH)?;
+    let manifest: PackManifest = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {} in {}", PACKAGE_JSON_PATH, path.display()))?;
+
+    Ok(manifest.extension_pack)
+}
+
+/// 디렉토리 바로 아래의 `.vsix` 파일을 모두 읽습니다. 매니페스트를 읽을 수 없는 파일은 건너뜁니다.
+pub fn scan_directory(dir: &Path) -> Result<Vec<LocalVsix>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut packages = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))? {
+        let path = entry.with_context(|| format!("Failed to read directory: {}", dir.display()))?.path();
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("vsix")) {
+            if let Ok(package) = read_manifest(&path) {
+                packages.push(package);
+            }
+        }
+    }
+
+    Ok(packages)
+}
+
+/// VSIX 루트의 패키지 매니페스트
+const VSIX_MANIFEST_PATH: &str = "extension.vsixmanifest";
+
+/// `extension.vsixmanifest`의 `<Identity>` 정보
+#[derive(Debug, Clone)]
+pub struct VsixIdentity {
+    pub publisher: String,
+    pub name: String,
+    pub version: String,
+    /// 플랫폼별 빌드의 대상 플랫폼 (예: `linux-x64`). 범용 패키지는 없음
+    pub target_platform: Option<String>,
+}
+
+impl VsixIdentity {
+    pub fn id(&self) -> String {
+        format!("{}.{}", self.publisher, self.name)
+    }
+}
+
+/// VSIX 파일의 `extension.vsixmanifest`에서 게시자, 이름, 버전, 대상 플랫폼을 읽습니다.
+pub fn read_identity(path: &Path) -> Result<VsixIdentity> {
+    let content = read_text_entry(path, VSIX_MANIFEST_PATH)?;
+    let document = roxmltree::Document::parse(&content)
+        .with_context(|| format!("Failed to parse {} in {}", VSIX_MANIFEST_PATH, path.display()))?;
+
+    let identity = document.descendants()
+        .find(|node| node.tag_name().name() == "Identity")
+        .with_context(|| format!("Identity not found in {} of {}", VSIX_MANIFEST_PATH, path.display()))?;
+
+    let attribute = |name: &str| {
+        identity.attribute(name)
+            .map(|v| v.to_string())
+            .with_context(|| format!("Identity has no {} attribute in {}", name, path.display()))
+    };
+
+    Ok(VsixIdentity {
+        publisher: attribute("Publisher")?,
+        name: attribute("Id")?,
+        version: attribute("Version")?,
+        target_platform: identity.attribute("TargetPlatform").map(|v| v.to_string()),
+    })
+}
+
+/// VSIX 안의 파일 하나를 그대로 읽습니다.
+pub fn read_entry(path: &Path, entry_name: &str) -> Result<Vec<u8>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = ZipArchive::new(file)
+        .with_context(|| format!("Failed to read VSIX archive: {}", path.display()))?;
+
+    let mut content = Vec::new();
+    archive.by_name(entry_name)
+        .with_context(|| format!("{} not found in {}", entry_name, path.display()))?
+        .read_to_end(&mut content)
+        .with_context(|| format!("Failed to read {} in {}", entry_name, path.display()))?;
+
+    Ok(content)
+}
+
+/// `extension.vsixmanifest`의 `<Assets>`에 있는 자산 종류(`Type`)와 VSIX 안의 경로(`Path`)를 읽습니다.
+pub fn read_assets(path: &Path) -> Result<Vec<(String, String)>> {
+    let content = read_text_entry(path, VSIX_MANIFEST_PATH)?;
+    let document = roxmltree::Document::parse(&content)
+        .with_context(|| format!("Failed to parse {} in {}", VSIX_MANIFEST_PATH, path.display()))?;
+
+    Ok(document.descendants()
+        .filter(|node| node.tag_name().name() == "Asset")
+        .filter_map(|asset| Some((asset.attribute("Type")?.to_string(), asset.attribute("Path")?.to_string())))
+        .collect())
+}
+
+/// 파일 크기와 SHA-256 해시를 계산합니다.
+pub fn file_digest(path: &Path) -> Result<(u64, String)> {
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+/// VSIX를 디렉토리 구조 그대로 풉니다. 디렉토리 밖을 가리키는 항목(`../`, 절대 경로)은 건너뜁니다.
+/// 풀어낸 파일 수와 건너뛴 항목 이름을 반환합니다.
+pub fn extract(path: &Path, out_dir: &Path) -> Result<(usize, Vec<String>)> {
+    extract_entries(path, out_dir, None)
+}
+
+/// VSIX의 `extension/` 디렉토리 내용만 `out_dir` 바로 아래에 풉니다 (VSCode가 설치하는 확장 프로그램 디렉토리 구조).
+pub fn extract_extension(path: &Path, out_dir: &Path) -> Result<(usize, Vec<String>)> {
+    extract_entries(path, out_dir, Some(Path::new("extension")))
+}
+
+/// `prefix`가 있으면 그 디렉토리 아래 항목만 prefix를 떼고 풉니다.
+fn extract_entries(path: &Path, out_dir: &Path, prefix: Option<&Path>) -> Result<(usize, Vec<String>)> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = ZipArchive::new(file)
+        .with_context(|| format!("Failed to read VSIX archive: {}", path.display()))?;
+
+    fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create directory: {}", out_dir.display()))?;
+
+    let mut extracted = 0;
+    let mut skipped = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)
+            .with_context(|| format!("Failed to read entry {} in {}", index, path.display()))?;
+
+        let Some(relative) = entry.enclosed_name() else {
+            skipped.push(entry.name().to_string());
+            continue;
+        };
+        let relative = match prefix {
+            Some(prefix) => match relative.strip_prefix(prefix) {
+                Ok(inner) if !inner.as_os_str().is_empty() => inner.to_path_buf(),
+                _ => continue,
+            },
+            None => relative,
+        };
+        // Windows에서는 쓸 수 없는 이름이 있으므로 경로 구성 요소마다 이름을 정리.
+        // 다른 플랫폼(code-server 프로비저닝 등)에서는 확장 프로그램이 참조하는 이름을 그대로 둠
+        let relative: PathBuf = if cfg!(windows) {
+            relative.components()
+                .map(|component| filename::sanitize_file_name(&component.as_os_str().to_string_lossy()))
+                .collect()
+        } else {
+            relative
+        };
+        let target = filename::long_path(&out_dir.join(relative));
+
+        if entry.is_dir() {
+            fs::create_dir_all(&target)
+                .with_context(|| format!("Failed to create directory: {}", target.display()))?;
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let mut output = File::create(&target)
+            .with_context(|| format!("Failed to create {}", target.display()))?;
+        std::io::copy(&mut entry, &mut output)
+            .with_context(|| format!("Failed to extract {}", target.display()))?;
+        extracted += 1;
+    }
+
+    Ok((extracted, skipped))
+}
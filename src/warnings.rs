@@ -0,0 +1,79 @@
+use std::sync::Mutex;
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+/// 실패는 아니지만 확인해 볼 만한 항목의 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// 레지스트리에서 더 이상 사용하지 않는 확장 프로그램으로 표시됨
+    Deprecated,
+    /// 목록의 ID와 레지스트리의 ID가 대소문자만 다름
+    IdCase,
+    /// 웹 전용 빌드만 있어 받지 않음
+    WebOnly,
+    /// 크기 제한을 넘어 받지 않음 (`--max-size-policy skip`)
+    Oversized,
+    /// `--assets signature`로 받은 Marketplace 서명이 올바르지 않거나 받지 못함 (오류 코드 `signature_invalid`와 같은 이름)
+    SignatureInvalid,
+}
+
+impl WarningKind {
+    /// 다운로드를 건너뛴 경고인지 (실패 목록에서는 빠짐)
+    pub fn skips_download(self) -> bool {
+        matches!(self, WarningKind::WebOnly | WarningKind::Oversized)
+    }
+}
+
+/// 결과 파일의 `warnings`에 기록되는 경고 하나
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Warning {
+    pub id: String,
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+static WARNINGS: Mutex<Vec<Warning>> = Mutex::new(Vec::new());
+
+/// 동기화를 시작할 때 이전 동기화의 경고를 비웁니다 (daemon, --all-profiles).
+pub fn reset() {
+    WARNINGS.lock().expect("warnings lock poisoned").clear();
+}
+
+/// 경고를 기록합니다. 같은 항목의 같은 종류 경고는 한 번만 남깁니다.
+pub fn push(id: &str, kind: WarningKind, message: impl Into<String>) {
+    let mut warnings = WARNINGS.lock().expect("warnings lock poisoned");
+    if warnings.iter().any(|w| w.kind == kind && w.id.eq_ignore_ascii_case(id)) {
+        return;
+    }
+    warnings.push(Warning { id: id.to_string(), kind, message: message.into() });
+}
+
+/// 지금까지 기록된 경고 (ID 순)
+pub fn snapshot() -> Vec<Warning> {
+    let mut warnings = WARNINGS.lock().expect("warnings lock poisoned").clone();
+    warnings.sort_by_key(|w| w.id.to_ascii_lowercase());
+    warnings
+}
+
+/// 확장 프로그램 하나에 기록된 경고
+pub fn of(id: &str) -> Vec<Warning> {
+    WARNINGS.lock().expect("warnings lock poisoned").iter()
+        .filter(|w| w.id.eq_ignore_ascii_case(id))
+        .cloned()
+        .collect()
+}
+
+/// 요약에 경고 목록을 출력합니다.
+pub fn print(warnings: &[Warning]) {
+    if warnings.is_empty() {
+        return;
+    }
+
+    println!("{}", format!("\n경고 {}개:", warnings.len()).yellow().bold());
+    let id_width = warnings.iter().map(|w| w.id.len()).max().unwrap_or(0);
+    for warning in warnings {
+        println!("  {:<id_width$}  {}", warning.id.yellow(), warning.message, id_width = id_width);
+    }
+}
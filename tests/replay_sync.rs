@@ -0,0 +1,93 @@
+//! 기록된 응답(`tests/fixtures/replay`)을 재생해 네트워크 없이 동기화 전체를 실행합니다.
+//! Open VSX에 없는 `inc.ext`를 Marketplace에서 조회하고 VSIX를 받는 경로입니다.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::Value;
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/replay")
+}
+
+/// 테스트마다 비어 있는 작업 디렉토리
+fn work_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vsix-downloader-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// `dir`에서 기록된 응답을 재생해 명령을 실행하고, 실패하면 출력과 함께 테스트를 실패시킵니다.
+fn run(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_vsix-downloader"))
+        .current_dir(dir)
+        .args(args)
+        .args(["--plain", "-a", "--replay"])
+        .arg(fixtures())
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    stdout
+}
+
+fn read_json(path: &Path) -> Value {
+    let content = fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    serde_json::from_str(&content).unwrap()
+}
+
+#[test]
+fn sync_downloads_from_marketplace_when_missing_on_openvsx() {
+    let dir = work_dir("replay-sync");
+    fs::write(dir.join("l.yml"), "enabled:\n  - id: inc.ext\n").unwrap();
+
+    run(&dir, &["sync", "-f", "l.yml"]);
+
+    let vsix = fs::read(dir.join("downloads/inc-ext.vsix")).unwrap();
+    assert_eq!(vsix.len(), 542);
+    assert!(vsix.starts_with(b"PK"));
+
+    let results = read_json(&dir.join("results.json"));
+    assert_eq!(results["available"], Value::Array(Vec::new()));
+    assert_eq!(results["unavailable"][0]["id"], "inc.ext");
+    assert_eq!(results["unavailable"][0]["error"], "not_found");
+
+    let downloads = read_json(&dir.join("downloads.json"));
+    assert_eq!(downloads[0]["id"], "inc.ext");
+    assert_eq!(downloads[0]["version"], "1.0.0");
+    assert_eq!(downloads[0]["success"], true);
+    assert_eq!(downloads[0]["size"], 542);
+    assert!(!dir.join("downloads.json.journal").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn retry_failed_reads_latest_run_directory() {
+    let dir = work_dir("replay-retry-run-dirs");
+    // 기록이 없는 항목은 fixture_missing으로 실패해 다시 시도할 대상이 됨
+    fs::write(dir.join("l.yml"), "enabled:\n  - id: inc.ext\n  - id: nosuch.recorded\n").unwrap();
+
+    let stdout = run(&dir, &["sync", "-f", "l.yml", "--run-dirs"]);
+    assert!(stdout.contains("retry-failed -f l.yml --run-dirs"), "{}", stdout);
+
+    let latest = fs::canonicalize(dir.join("downloads/latest")).unwrap();
+    let stdout = run(&dir, &["retry-failed", "-f", "l.yml", "--run-dirs"]);
+    assert!(stdout.contains("실패한 1개 확장 프로그램을 다시 시도합니다"), "{}", stdout);
+
+    // 다시 시도한 결과는 출력 디렉토리 바로 아래나 현재 디렉토리가 아니라 실행 디렉토리에 합쳐짐
+    assert!(!dir.join("results.json").exists());
+    assert!(!dir.join("downloads/results.json").exists());
+    let results = read_json(&latest.join("results.json"));
+    let ids: Vec<&str> = results["unavailable"].as_array().unwrap().iter()
+        .map(|e| e["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, ["inc.ext", "nosuch.recorded"]);
+    assert!(latest.join("inc-ext.vsix").exists());
+    assert!(!dir.join("sync-state.json").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}